@@ -0,0 +1,44 @@
+#[macro_use]
+extern crate criterion;
+extern crate ed25519_dalek;
+extern crate shuttle_core;
+
+use criterion::{BenchmarkId, Criterion};
+use ed25519_dalek::Signature;
+use shuttle_core::{BatchVerifier, KeyPair};
+
+fn signatures(n: usize) -> Vec<(Vec<u8>, Signature, KeyPair)> {
+    (0..n)
+        .map(|i| {
+            let keypair = KeyPair::random().unwrap();
+            let message = format!("transaction hash {}", i).into_bytes();
+            let signature = keypair.sign(&message);
+            (message, signature, keypair)
+        })
+        .collect()
+}
+
+fn bench_verify(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify");
+    for n in &[16, 64, 256] {
+        group.bench_with_input(BenchmarkId::new("batch", n), n, |b, n| {
+            let mut batch = BatchVerifier::with_capacity(*n);
+            for (message, signature, keypair) in signatures(*n) {
+                batch.add(&message, &signature, keypair.public_key());
+            }
+            b.iter(|| assert!(batch.verify()))
+        });
+        group.bench_with_input(BenchmarkId::new("single", n), n, |b, n| {
+            let signatures = signatures(*n);
+            b.iter(|| {
+                for (message, signature, keypair) in &signatures {
+                    assert!(keypair.verify(message, signature));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_verify);
+criterion_main!(benches);
@@ -0,0 +1,19 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate shuttle_core;
+
+use std::str::{self, FromStr};
+use shuttle_core::Amount;
+
+fuzz_target!(|data: &[u8]| {
+    let input = match str::from_utf8(data) {
+        Ok(input) => input,
+        Err(_) => return,
+    };
+    // A parsed amount always fits in stroops.
+    if let Ok(amount) = Amount::from_str(input) {
+        let stroops = amount.as_stroops().unwrap();
+        assert_eq!(Amount::from_stroops(stroops).unwrap(), amount);
+    }
+});
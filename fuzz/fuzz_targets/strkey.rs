@@ -0,0 +1,31 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate shuttle_core;
+
+use std::str;
+use shuttle_core::crypto::strkey;
+use shuttle_core::StrKey;
+
+fuzz_target!(|data: &[u8]| {
+    let input = match str::from_utf8(data) {
+        Ok(input) => input,
+        Err(_) => return,
+    };
+    // Only the canonical encoding is accepted, so decoding is the inverse of encoding.
+    if let Ok(key) = StrKey::decode(input) {
+        assert_eq!(key.to_string(), input);
+    }
+    if let Ok(key) = strkey::decode_account_id(input) {
+        assert_eq!(strkey::encode_account_id(&key).unwrap(), input);
+    }
+    if let Ok(key) = strkey::decode_secret_seed(input) {
+        assert_eq!(strkey::encode_secret_seed(&key).unwrap(), input);
+    }
+    if let Ok(key) = strkey::decode_pre_auth_tx(input) {
+        assert_eq!(strkey::encode_pre_auth_tx(&key).unwrap(), input);
+    }
+    if let Ok(key) = strkey::decode_sha256_hash(input) {
+        assert_eq!(strkey::encode_sha256_hash(&key).unwrap(), input);
+    }
+});
@@ -0,0 +1,20 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate shuttle_core;
+
+use std::str;
+use shuttle_core::{FromXdr, SignedTransaction, ToXdr};
+
+fuzz_target!(|data: &[u8]| {
+    let mut reader = data;
+    if let Ok(tx) = SignedTransaction::from_reader(&mut reader) {
+        // Anything we decode can be encoded again, and decodes to the same envelope.
+        let encoded = tx.to_base64().unwrap();
+        let decoded = SignedTransaction::from_base64(&encoded).unwrap();
+        assert_eq!(decoded.to_base64().unwrap(), encoded);
+    }
+    if let Ok(input) = str::from_utf8(data) {
+        let _ = SignedTransaction::from_base64(input);
+    }
+});
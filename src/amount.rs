@@ -43,18 +43,49 @@ impl Amount {
 impl FromStr for Amount {
     type Err = Error;
 
+    /// Parse a plain decimal number, e.g. `-12.5`, that fits in stroops.
+    ///
+    /// Exponents, whitespace and more than 7 decimal digits are rejected.
     fn from_str(s: &str) -> result::Result<Amount, Error> {
-        let inner = BigDecimal::from_str(&s)?;
-        // Check we don't lose precision
-        let (_, scale) = inner.as_bigint_and_exponent();
-        if scale > STELLAR_SCALE {
-            Err(Error::InvalidAmountScale)
-        } else {
-            let scaled_inner = inner.with_scale(STELLAR_SCALE);
-            Ok(Amount {
-                inner: scaled_inner,
-            })
+        let stroops = parse_stroops(s)?;
+        Amount::from_stroops(Stroops::new(stroops))
+    }
+}
+
+fn parse_stroops(s: &str) -> Result<i64> {
+    let (negative, unsigned) = match s.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, s),
+    };
+    let (integer, fraction) = match unsigned.find('.') {
+        Some(pos) => (&unsigned[..pos], Some(&unsigned[pos + 1..])),
+        None => (unsigned, None),
+    };
+    let is_digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    if !is_digits(integer) || !fraction.is_none_or(is_digits) {
+        return Err(Error::InvalidAmountFormat);
+    }
+    let fraction = fraction.unwrap_or("");
+    if fraction.len() > STELLAR_SCALE as usize {
+        return Err(Error::InvalidAmountScale);
+    }
+    let mut stroops: u64 = 0;
+    let padding = STELLAR_SCALE as usize - fraction.len();
+    for b in integer.bytes().chain(fraction.bytes()).chain((0..padding).map(|_| b'0')) {
+        stroops = stroops
+            .checked_mul(10)
+            .and_then(|stroops| stroops.checked_add(u64::from(b - b'0')))
+            .ok_or(Error::InvalidStroopsAmount)?;
+    }
+    if negative {
+        if stroops > i64::MAX as u64 + 1 {
+            return Err(Error::InvalidStroopsAmount);
         }
+        Ok((stroops as i64).wrapping_neg())
+    } else if stroops > i64::MAX as u64 {
+        Err(Error::InvalidStroopsAmount)
+    } else {
+        Ok(stroops as i64)
     }
 }
 
@@ -132,4 +163,30 @@ mod tests {
         let stroops = amount.as_stroops().unwrap();
         assert_eq!(stroops, Stroops::new(1234567800));
     }
+
+    #[test]
+    fn test_amount_from_str_malformed() {
+        let cases = [
+            "", "-", ".", "1.", ".5", "--1", "+1", "1e3", "1E-3", " 1", "1 ", "1,5", "0x10", "1.2.3",
+            "NaN", "inf", "١",
+        ];
+        for case in &cases {
+            assert!(str::parse::<Amount>(case).is_err(), "{}", case);
+        }
+        assert!(str::parse::<Amount>("1e1000000000").is_err());
+        assert!(str::parse::<Amount>(&"9".repeat(10_000)).is_err());
+    }
+
+    #[test]
+    fn test_amount_from_str_limits() {
+        let max = str::parse::<Amount>("922337203685.4775807").unwrap();
+        assert_eq!(max.as_stroops().unwrap(), Stroops::new(i64::MAX));
+        let min = str::parse::<Amount>("-922337203685.4775808").unwrap();
+        assert_eq!(min.as_stroops().unwrap(), Stroops::new(i64::MIN));
+        assert!(str::parse::<Amount>("922337203685.4775808").is_err());
+        assert!(str::parse::<Amount>("-922337203685.4775809").is_err());
+        let negative = str::parse::<Amount>("-1.5").unwrap();
+        assert_eq!(negative.as_stroops().unwrap(), Stroops::new(-15_000_000));
+        assert_eq!(str::parse::<Amount>("-0").unwrap(), str::parse::<Amount>("0").unwrap());
+    }
 }
@@ -0,0 +1,456 @@
+use amount::Stroops;
+use ed25519_dalek::PublicKey;
+use error::{Error, Result};
+use ledger_entry::{LedgerEntryData, LedgerKey, OfferEntry, TrustLineAsset, TrustLineEntry};
+use transaction_meta::{LedgerEntryChange, TransactionMeta};
+
+/// The balance of an account in an asset changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceChange {
+    /// The account.
+    pub account_id: PublicKey,
+    /// The asset, `TrustLineAsset::Native` for the account balance.
+    pub asset: TrustLineAsset,
+    /// Balance before, zero if the account or trust line did not exist.
+    pub before: Stroops,
+    /// Balance after, zero if the account or trust line was removed.
+    pub after: Stroops,
+    /// `after - before`.
+    pub delta: Stroops,
+}
+
+/// The sequence number of an account changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequenceChange {
+    /// The account.
+    pub account_id: PublicKey,
+    /// Sequence number before, `None` if the account was created.
+    pub before: Option<u64>,
+    /// Sequence number after, `None` if the account was merged.
+    pub after: Option<u64>,
+}
+
+/// The net changes of one or more transactions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BalanceChanges {
+    /// Nonzero balance changes, in order of first change.
+    pub balances: Vec<BalanceChange>,
+    /// Sequence number changes, in order of first change.
+    pub sequences: Vec<SequenceChange>,
+    /// Trust lines that did not exist before.
+    pub created_trust_lines: Vec<TrustLineEntry>,
+    /// Trust lines that no longer exist, as they were before.
+    pub removed_trust_lines: Vec<TrustLineEntry>,
+    /// Offers that did not exist before.
+    pub created_offers: Vec<OfferEntry>,
+    /// Offers that no longer exist, as they were before.
+    pub removed_offers: Vec<OfferEntry>,
+    /// Entries updated or removed without a known prior state, as in version 0
+    /// meta, whose changes can not be computed.
+    pub unknown_before: Vec<LedgerKey>,
+}
+
+/// Computes the net balance changes from ledger entry changes.
+///
+/// Changes are applied in order: every entry is compared between its first
+/// known state and its last value, so fee processing changes added before
+/// the transaction meta are included in the balance deltas. Claimable
+/// balances and liquidity pool reserves are not reported.
+///
+/// Version 0 meta has no entry states: entries it updates or removes that
+/// were not tracked before are reported in `BalanceChanges::unknown_before`.
+#[derive(Debug, Clone, Default)]
+pub struct BalanceAnalyzer {
+    entries: Vec<TrackedEntry>,
+}
+
+#[derive(Debug, Clone)]
+struct TrackedEntry {
+    key: LedgerKey,
+    // `None` if the state before is unknown.
+    before: Option<Option<LedgerEntryData>>,
+    after: Option<LedgerEntryData>,
+}
+
+impl BalanceAnalyzer {
+    /// Create an analyzer without changes.
+    pub fn new() -> BalanceAnalyzer {
+        BalanceAnalyzer::default()
+    }
+
+    /// Add the changes of a transaction meta.
+    pub fn add_meta(&mut self, meta: &TransactionMeta) -> Result<()> {
+        let states = meta.version > 0;
+        for change in meta.changes() {
+            self.add_change(change, states)?;
+        }
+        Ok(())
+    }
+
+    /// Add ledger entry changes, e.g. the fee processing changes.
+    ///
+    /// Returns an error if an entry is updated or removed before its state
+    /// is known.
+    pub fn add_changes<'a, I>(&mut self, changes: I) -> Result<()>
+    where
+        I: IntoIterator<Item = &'a LedgerEntryChange>,
+    {
+        for change in changes {
+            self.add_change(change, true)?;
+        }
+        Ok(())
+    }
+
+    // `states` tells whether the changes include the state of updated and
+    // removed entries.
+    fn add_change(&mut self, change: &LedgerEntryChange, states: bool) -> Result<()> {
+        let (key, data, known_before) = match *change {
+            LedgerEntryChange::State(ref entry) => {
+                let key = entry.ledger_key();
+                if self.position(&key).is_none() {
+                    self.entries.push(TrackedEntry {
+                        key,
+                        before: Some(Some(entry.data.clone())),
+                        after: Some(entry.data.clone()),
+                    });
+                }
+                return Ok(());
+            }
+            LedgerEntryChange::Created(ref entry) | LedgerEntryChange::Restored(ref entry) => {
+                (entry.ledger_key(), Some(&entry.data), false)
+            }
+            LedgerEntryChange::Updated(ref entry) => (entry.ledger_key(), Some(&entry.data), true),
+            LedgerEntryChange::Removed(ref key) => (key.clone(), None, true),
+        };
+        let after = data.cloned();
+        match self.position(&key) {
+            Some(index) => self.entries[index].after = after,
+            None if known_before && states => return Err(Error::InvalidLedgerEntryChanges),
+            None => self.entries.push(TrackedEntry {
+                key,
+                before: if known_before { None } else { Some(None) },
+                after,
+            }),
+        }
+        Ok(())
+    }
+
+    fn position(&self, key: &LedgerKey) -> Option<usize> {
+        self.entries.iter().position(|entry| entry.key == *key)
+    }
+
+    /// Compute the net changes.
+    pub fn finish(&self) -> Result<BalanceChanges> {
+        let mut changes = BalanceChanges::default();
+        for entry in &self.entries {
+            let before = match entry.before {
+                Some(ref before) => before,
+                None => {
+                    changes.unknown_before.push(entry.key.clone());
+                    continue;
+                }
+            };
+            match entry.key {
+                LedgerKey::Account { account_id } => {
+                    let native = TrustLineAsset::Native;
+                    let (balance_before, balance_after) =
+                        (account_balance(before), account_balance(&entry.after));
+                    changes.push_balance(account_id, native, balance_before, balance_after)?;
+                    let before = account_sequence(before);
+                    let after = account_sequence(&entry.after);
+                    if before != after {
+                        changes.sequences.push(SequenceChange {
+                            account_id,
+                            before,
+                            after,
+                        });
+                    }
+                }
+                LedgerKey::TrustLine {
+                    account_id,
+                    ref asset,
+                } => {
+                    let before = trust_line(before);
+                    let after = trust_line(&entry.after);
+                    let balance = |t: Option<&TrustLineEntry>| {
+                        t.map(|t| t.balance.clone()).unwrap_or(Stroops::new(0))
+                    };
+                    let (balance_before, balance_after) = (balance(before), balance(after));
+                    changes.push_balance(account_id, asset.clone(), balance_before, balance_after)?;
+                    match (before, after) {
+                        (None, Some(created)) => changes.created_trust_lines.push(created.clone()),
+                        (Some(removed), None) => changes.removed_trust_lines.push(removed.clone()),
+                        _ => {}
+                    }
+                }
+                LedgerKey::Offer { .. } => match (offer(before), offer(&entry.after)) {
+                    (None, Some(created)) => changes.created_offers.push(created.clone()),
+                    (Some(removed), None) => changes.removed_offers.push(removed.clone()),
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+        Ok(changes)
+    }
+}
+
+impl BalanceChanges {
+    fn push_balance(
+        &mut self,
+        account_id: PublicKey,
+        asset: TrustLineAsset,
+        before: Stroops,
+        after: Stroops,
+    ) -> Result<()> {
+        let delta = after
+            .0
+            .checked_sub(before.0)
+            .ok_or(Error::InvalidLedgerEntryChanges)?;
+        if delta != 0 {
+            self.balances.push(BalanceChange {
+                account_id,
+                asset,
+                before,
+                after,
+                delta: Stroops::new(delta),
+            });
+        }
+        Ok(())
+    }
+}
+
+fn account_balance(data: &Option<LedgerEntryData>) -> Stroops {
+    match *data {
+        Some(LedgerEntryData::Account(ref account)) => account.balance.clone(),
+        _ => Stroops::new(0),
+    }
+}
+
+fn account_sequence(data: &Option<LedgerEntryData>) -> Option<u64> {
+    match *data {
+        Some(LedgerEntryData::Account(ref account)) => Some(account.sequence),
+        _ => None,
+    }
+}
+
+fn trust_line(data: &Option<LedgerEntryData>) -> Option<&TrustLineEntry> {
+    match *data {
+        Some(LedgerEntryData::TrustLine(ref trust_line)) => Some(trust_line),
+        _ => None,
+    }
+}
+
+fn offer(data: &Option<LedgerEntryData>) -> Option<&OfferEntry> {
+    match *data {
+        Some(LedgerEntryData::Offer(ref offer)) => Some(offer),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BalanceAnalyzer, BalanceChange, SequenceChange};
+    use amount::{Price, Stroops};
+    use asset::{Asset, CreditAsset};
+    use crypto::keypair::from_account_id;
+    use ed25519_dalek::PublicKey;
+    use error::Error;
+    use ledger_entry::{AccountEntry, LedgerEntry, LedgerEntryData, LedgerKey, OfferEntry,
+                       Thresholds, TrustLineAsset, TrustLineEntry};
+    use transaction_meta::{LedgerEntryChange, OperationMeta, TransactionMeta};
+
+    fn alice() -> PublicKey {
+        from_account_id("GCEAKB6W342KSAQ6SVJYROF5W5FJTPZDDOSIOT3Y6CNQ3U2ZBAH7AQN3").unwrap()
+    }
+
+    fn bob() -> PublicKey {
+        from_account_id("GCLDNMHZTEY6PUYQBYOVERBBZ2W3RLMYOSZWHAMY5R4YW2N6MM4LFA72").unwrap()
+    }
+
+    fn entry(data: LedgerEntryData) -> LedgerEntry {
+        LedgerEntry {
+            last_modified_ledger_seq: 7,
+            data,
+            extension: None,
+        }
+    }
+
+    fn account(account_id: PublicKey, balance: i64, sequence: u64) -> LedgerEntry {
+        entry(LedgerEntryData::Account(AccountEntry {
+            account_id,
+            balance: Stroops::new(balance),
+            sequence,
+            num_sub_entries: 0,
+            inflation_dest: None,
+            flags: 0,
+            home_domain: String::new(),
+            thresholds: Thresholds {
+                master_weight: 1,
+                low: 0,
+                medium: 0,
+                high: 0,
+            },
+            signers: Vec::new(),
+            extension: None,
+        }))
+    }
+
+    fn usd() -> TrustLineAsset {
+        TrustLineAsset::Credit(CreditAsset::new("USD".to_string(), bob()).unwrap())
+    }
+
+    fn trust_line(balance: i64) -> TrustLineEntry {
+        TrustLineEntry {
+            account_id: alice(),
+            asset: usd(),
+            balance: Stroops::new(balance),
+            limit: Stroops::new(1_000_000),
+            flags: TrustLineEntry::AUTHORIZED,
+            extension: None,
+        }
+    }
+
+    fn offer() -> OfferEntry {
+        OfferEntry {
+            seller_id: alice(),
+            offer_id: 42,
+            selling: Asset::Native,
+            buying: Asset::Native,
+            amount: Stroops::new(10),
+            price: Price::new(1, 2),
+            flags: 0,
+        }
+    }
+
+    #[test]
+    fn test_fee_and_payment() {
+        let fee = vec![
+            LedgerEntryChange::State(account(alice(), 1000, 5)),
+            LedgerEntryChange::Updated(account(alice(), 900, 5)),
+        ];
+        let meta = TransactionMeta {
+            version: 2,
+            tx_changes_before: vec![
+                LedgerEntryChange::State(account(alice(), 900, 5)),
+                LedgerEntryChange::Updated(account(alice(), 900, 6)),
+            ],
+            operations: vec![OperationMeta {
+                changes: vec![
+                    LedgerEntryChange::Created(account(bob(), 500, 30)),
+                    LedgerEntryChange::State(account(alice(), 900, 6)),
+                    LedgerEntryChange::Updated(account(alice(), 400, 6)),
+                ],
+            }],
+            tx_changes_after: Vec::new(),
+        };
+        let mut analyzer = BalanceAnalyzer::new();
+        analyzer.add_changes(&fee).unwrap();
+        analyzer.add_meta(&meta).unwrap();
+        let changes = analyzer.finish().unwrap();
+        assert_eq!(
+            changes.balances,
+            vec![
+                BalanceChange {
+                    account_id: alice(),
+                    asset: TrustLineAsset::Native,
+                    before: Stroops::new(1000),
+                    after: Stroops::new(400),
+                    delta: Stroops::new(-600),
+                },
+                BalanceChange {
+                    account_id: bob(),
+                    asset: TrustLineAsset::Native,
+                    before: Stroops::new(0),
+                    after: Stroops::new(500),
+                    delta: Stroops::new(500),
+                },
+            ]
+        );
+        assert_eq!(
+            changes.sequences,
+            vec![
+                SequenceChange {
+                    account_id: alice(),
+                    before: Some(5),
+                    after: Some(6),
+                },
+                SequenceChange {
+                    account_id: bob(),
+                    before: None,
+                    after: Some(30),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trust_lines_and_offers() {
+        let changes = vec![
+            LedgerEntryChange::Created(entry(LedgerEntryData::TrustLine(trust_line(0)))),
+            LedgerEntryChange::Updated(entry(LedgerEntryData::TrustLine(trust_line(25)))),
+            LedgerEntryChange::State(entry(LedgerEntryData::Offer(offer()))),
+            LedgerEntryChange::Removed(LedgerKey::Offer {
+                seller_id: alice(),
+                offer_id: 42,
+            }),
+        ];
+        let mut analyzer = BalanceAnalyzer::new();
+        analyzer.add_changes(&changes).unwrap();
+        let changes = analyzer.finish().unwrap();
+        assert_eq!(changes.balances.len(), 1);
+        assert_eq!(changes.balances[0].asset, usd());
+        assert_eq!(changes.balances[0].delta, Stroops::new(25));
+        assert_eq!(changes.created_trust_lines, vec![trust_line(25)]);
+        assert!(changes.removed_trust_lines.is_empty());
+        assert!(changes.created_offers.is_empty());
+        assert_eq!(changes.removed_offers, vec![offer()]);
+    }
+
+    #[test]
+    fn test_v0_meta() {
+        let carol =
+            from_account_id("GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ").unwrap();
+        let fee = vec![
+            LedgerEntryChange::State(account(alice(), 1000, 5)),
+            LedgerEntryChange::Updated(account(alice(), 900, 5)),
+        ];
+        // Version 0 meta only has the values after each operation.
+        let meta = TransactionMeta {
+            version: 0,
+            tx_changes_before: Vec::new(),
+            operations: vec![OperationMeta {
+                changes: vec![
+                    LedgerEntryChange::Updated(account(alice(), 400, 6)),
+                    LedgerEntryChange::Updated(account(carol, 700, 1)),
+                ],
+            }],
+            tx_changes_after: Vec::new(),
+        };
+        let mut analyzer = BalanceAnalyzer::new();
+        analyzer.add_changes(&fee).unwrap();
+        analyzer.add_meta(&meta).unwrap();
+        let changes = analyzer.finish().unwrap();
+        assert_eq!(changes.balances.len(), 1);
+        assert_eq!(changes.balances[0].account_id, alice());
+        assert_eq!(changes.balances[0].delta, Stroops::new(-600));
+        assert_eq!(changes.unknown_before, vec![LedgerKey::Account { account_id: carol }]);
+
+        // Later versions always include the state.
+        let meta = TransactionMeta { version: 1, ..meta };
+        match BalanceAnalyzer::new().add_meta(&meta) {
+            Err(Error::InvalidLedgerEntryChanges) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_update_without_state() {
+        let changes = vec![LedgerEntryChange::Updated(account(alice(), 1, 1))];
+        let mut analyzer = BalanceAnalyzer::new();
+        match analyzer.add_changes(&changes) {
+            Err(Error::InvalidLedgerEntryChanges) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use ed25519_dalek::{self, PublicKey, Signature};
+use error::Result;
+use signature::SignatureHint;
+use transaction::SignedTransaction;
+
+/// Below this number of items, signatures are verified one by one when
+/// looking for the invalid ones.
+const MIN_BATCH_SIZE: usize = 4;
+
+/// A signature to verify.
+#[derive(Debug, Clone)]
+struct Entry {
+    message: Vec<u8>,
+    /// `None` if there is no signature for the public key.
+    signature: Option<Signature>,
+    public_key: PublicKey,
+}
+
+impl Entry {
+    fn verify(&self) -> bool {
+        match self.signature {
+            None => false,
+            Some(ref signature) => self.public_key.verify(&self.message, signature).is_ok(),
+        }
+    }
+}
+
+/// Verify many signatures at once, using ed25519 batch verification.
+///
+/// Batch verification is faster than verifying each signature, but only
+/// tells if all signatures are valid. When they are not, `find_invalid`
+/// splits the batch to pinpoint the invalid signatures.
+#[derive(Debug, Clone, Default)]
+pub struct BatchVerifier {
+    entries: Vec<Entry>,
+}
+
+impl BatchVerifier {
+    /// Create an empty batch.
+    pub fn new() -> BatchVerifier {
+        BatchVerifier::default()
+    }
+
+    /// Create an empty batch with room for `capacity` signatures.
+    pub fn with_capacity(capacity: usize) -> BatchVerifier {
+        BatchVerifier {
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Add the `signature` of `message` by `public_key`.
+    pub fn add(&mut self, message: &[u8], signature: &Signature, public_key: &PublicKey) {
+        self.entries.push(Entry {
+            message: message.to_vec(),
+            signature: Some(*signature),
+            public_key: *public_key,
+        });
+    }
+
+    /// Add the signature of the transaction by `public_key`.
+    ///
+    /// The signature is found by its hint. If the transaction has no
+    /// signature with the hint of `public_key`, the entry is invalid.
+    pub fn add_transaction(
+        &mut self,
+        transaction: &SignedTransaction,
+        public_key: &PublicKey,
+    ) -> Result<()> {
+        let message = transaction.hash()?;
+        let hint = SignatureHint::from_public_key(public_key);
+        let candidates: Vec<Signature> = transaction
+            .signatures()
+            .iter()
+            .filter(|signature| *signature.hint() == hint)
+            .filter_map(|signature| signature.ed25519_signature())
+            .collect();
+        let signature = if candidates.len() > 1 {
+            // Hints are only 4 bytes: on collision pick the signature that verifies.
+            candidates
+                .iter()
+                .find(|signature| public_key.verify(&message, signature).is_ok())
+                .or_else(|| candidates.first())
+                .cloned()
+        } else {
+            candidates.first().cloned()
+        };
+        self.entries.push(Entry {
+            message,
+            signature,
+            public_key: *public_key,
+        });
+        Ok(())
+    }
+
+    /// Return the number of signatures in the batch.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return `true` if the batch is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Return `true` if all signatures in the batch are valid.
+    pub fn verify(&self) -> bool {
+        verify_entries(&self.entries)
+    }
+
+    /// Return the indexes, in insertion order, of the invalid signatures.
+    pub fn find_invalid(&self) -> Vec<usize> {
+        let mut invalid = Vec::new();
+        find_invalid_entries(&self.entries, 0, &mut invalid);
+        invalid
+    }
+}
+
+fn verify_entries(entries: &[Entry]) -> bool {
+    if entries.is_empty() {
+        return true;
+    }
+    if entries.iter().any(|entry| entry.signature.is_none()) {
+        return false;
+    }
+    let messages: Vec<&[u8]> = entries.iter().map(|entry| &entry.message[..]).collect();
+    let signatures: Vec<Signature> = entries.iter().filter_map(|entry| entry.signature).collect();
+    let public_keys: Vec<PublicKey> = entries.iter().map(|entry| entry.public_key).collect();
+    ed25519_dalek::verify_batch(&messages, &signatures, &public_keys).is_ok()
+}
+
+fn find_invalid_entries(entries: &[Entry], offset: usize, invalid: &mut Vec<usize>) {
+    if entries.len() <= MIN_BATCH_SIZE {
+        for (i, entry) in entries.iter().enumerate() {
+            if !entry.verify() {
+                invalid.push(offset + i);
+            }
+        }
+        return;
+    }
+    if verify_entries(entries) {
+        return;
+    }
+    let (left, right) = entries.split_at(entries.len() / 2);
+    find_invalid_entries(left, offset, invalid);
+    find_invalid_entries(right, offset + left.len(), invalid);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BatchVerifier;
+    use {Account, KeyPair, Network, OperationBuilder, TransactionBuilder};
+
+    fn keypairs(n: usize) -> Vec<KeyPair> {
+        (0..n).map(|_| KeyPair::random().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_all_valid() {
+        let mut batch = BatchVerifier::new();
+        assert!(batch.verify());
+        for (i, keypair) in keypairs(20).iter().enumerate() {
+            let message = format!("message {}", i);
+            batch.add(message.as_bytes(), &keypair.sign(message.as_bytes()), keypair.public_key());
+        }
+        assert_eq!(batch.len(), 20);
+        assert!(batch.verify());
+        assert!(batch.find_invalid().is_empty());
+    }
+
+    #[test]
+    fn test_find_invalid() {
+        let keypairs = keypairs(37);
+        let mut batch = BatchVerifier::with_capacity(keypairs.len());
+        for (i, keypair) in keypairs.iter().enumerate() {
+            let message = format!("message {}", i);
+            let signature = if i == 3 || i == 20 || i == 36 {
+                keypair.sign(b"other message")
+            } else {
+                keypair.sign(message.as_bytes())
+            };
+            batch.add(message.as_bytes(), &signature, keypair.public_key());
+        }
+        assert!(!batch.verify());
+        assert_eq!(batch.find_invalid(), vec![3, 20, 36]);
+    }
+
+    #[test]
+    fn test_transactions() {
+        let network = Network::test_network();
+        let keypairs = keypairs(10);
+        let mut batch = BatchVerifier::new();
+        for (i, keypair) in keypairs.iter().enumerate() {
+            let mut account = Account::new(*keypair.public_key(), 999);
+            let tx = TransactionBuilder::new(&mut account)
+                .operation(OperationBuilder::inflation().build())
+                .build()
+                .unwrap();
+            let signer = if i == 7 { &keypairs[0] } else { keypair };
+            let signed_tx = tx.sign(signer.inner(), &network).unwrap();
+            batch.add_transaction(&signed_tx, keypair.public_key()).unwrap();
+        }
+        assert!(!batch.verify());
+        assert_eq!(batch.find_invalid(), vec![7]);
+    }
+}
@@ -0,0 +1,74 @@
+//! Search for an account id matching a pattern.
+//!
+//! ```text
+//! shuttle-vanity (--prefix GBANK | --suffix XLM | --regex '^G.*XLM$') [--threads N]
+//! shuttle-vanity --help
+//! ```
+extern crate shuttle_core;
+
+use std::env;
+use std::process;
+use std::time::Duration;
+use shuttle_core::{VanityPattern, VanitySearch};
+
+const USAGE: &str =
+    "usage: shuttle-vanity (--prefix PREFIX | --suffix SUFFIX | --regex REGEX) [--threads N]";
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    eprintln!("{}", USAGE);
+    process::exit(2)
+}
+
+fn main() {
+    let mut pattern = None;
+    let mut threads = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "-h" || arg == "--help" {
+            println!("{}", USAGE);
+            process::exit(0)
+        }
+        let value = args.next().unwrap_or_else(|| fail(&format!("missing value for {}", arg)));
+        match arg.as_str() {
+            "--prefix" => pattern = Some(VanityPattern::prefix(&value)),
+            "--suffix" => pattern = Some(VanityPattern::suffix(&value)),
+            "--regex" => pattern = Some(VanityPattern::regex(&value)),
+            "--threads" => {
+                threads = Some(value.parse().unwrap_or_else(|_| fail("invalid number of threads")))
+            }
+            _ => fail(&format!("unknown argument {}", arg)),
+        }
+    }
+    let pattern = match pattern {
+        None => fail("missing pattern"),
+        Some(Err(_)) => fail(
+            "invalid pattern: use the base32 alphabet A-Z and 2-7, \
+             prefixes start with G followed by A, B, C or D",
+        ),
+        Some(Ok(pattern)) => pattern,
+    };
+
+    let mut search = VanitySearch::new(pattern);
+    if let Some(threads) = threads {
+        search = search.with_threads(threads);
+    }
+    if let Some(expected) = search.expected_attempts() {
+        eprintln!("expected attempts: {:.0}", expected);
+    }
+    let keypair = search.run_with_progress(Duration::from_secs(5), |progress| {
+        eprintln!(
+            "{} attempts in {}s ({:.0}/s)",
+            progress.attempts,
+            progress.elapsed.as_secs(),
+            progress.rate()
+        );
+    });
+    match keypair {
+        None => process::exit(1),
+        Some(keypair) => {
+            println!("{}", keypair.account_id().expect("valid account id"));
+            println!("{}", keypair.secret_seed().expect("valid secret seed"));
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use std::sync::{Condvar, Mutex, MutexGuard};
+use ed25519_dalek::{Keypair, PublicKey};
+use account::Account;
+use error::{Error, Result};
+use network::Network;
+use operation::Operation;
+use transaction::{SignedTransaction, Transaction};
+use transaction_builder::TransactionBuilder;
+
+/// A pool of channel accounts used to submit transactions in parallel.
+///
+/// Each channel account is the source of the transactions it is used for,
+/// and consumes its own sequence numbers, while the primary account is the
+/// source of the operations. Transactions built from the pool must be signed
+/// by both the channel and the primary account.
+#[derive(Debug)]
+pub struct ChannelPool {
+    primary: PublicKey,
+    size: usize,
+    free: Mutex<Vec<Account>>,
+    available: Condvar,
+}
+
+impl ChannelPool {
+    /// Create a pool of `channels` with `primary` as operations source.
+    pub fn new(primary: PublicKey, channels: Vec<Account>) -> ChannelPool {
+        let size = channels.len();
+        ChannelPool {
+            primary,
+            size,
+            free: Mutex::new(channels),
+            available: Condvar::new(),
+        }
+    }
+
+    /// Return the primary account public key.
+    pub fn primary(&self) -> &PublicKey {
+        &self.primary
+    }
+
+    /// Return the number of channels in the pool.
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    /// Return `true` if the pool has no channels.
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Return the number of channels not in use.
+    pub fn available(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Acquire a channel, blocking until one is free.
+    ///
+    /// Returns `None` if the pool has no channels.
+    pub fn acquire<'a>(&'a self) -> Option<Channel<'a>> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut free = self.lock();
+        loop {
+            if let Some(account) = free.pop() {
+                return Some(Channel::new(self, account));
+            }
+            free = self.available
+                .wait(free)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Acquire a channel if one is free, without blocking.
+    pub fn try_acquire<'a>(&'a self) -> Option<Channel<'a>> {
+        let account = self.lock().pop()?;
+        Some(Channel::new(self, account))
+    }
+
+    /// Sign `tx` with both the `channel` and the `primary` keypairs.
+    pub fn sign(
+        &self,
+        tx: Transaction,
+        channel: &Keypair,
+        primary: &Keypair,
+        network: &Network,
+    ) -> Result<SignedTransaction> {
+        if channel.public != *tx.source() || primary.public != self.primary {
+            return Err(Error::InvalidPublicKey);
+        }
+        let mut signed = SignedTransaction::new(tx, network)?;
+        signed.sign(channel)?;
+        signed.sign(primary)?;
+        Ok(signed)
+    }
+
+    fn lock<'a>(&'a self) -> MutexGuard<'a, Vec<Account>> {
+        self.free.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn release(&self, account: Account) {
+        self.lock().push(account);
+        self.available.notify_one();
+    }
+}
+
+/// A channel account acquired from a `ChannelPool`.
+///
+/// The channel is returned to the pool when dropped.
+#[derive(Debug)]
+pub struct Channel<'a> {
+    pool: &'a ChannelPool,
+    account: Option<Account>,
+}
+
+impl<'a> Channel<'a> {
+    fn new(pool: &'a ChannelPool, account: Account) -> Channel<'a> {
+        Channel {
+            pool,
+            account: Some(account),
+        }
+    }
+
+    /// Return the channel account.
+    pub fn account(&self) -> &Account {
+        self.account.as_ref().expect("channel account")
+    }
+
+    /// Return the channel account, e.g. to reset its sequence number
+    /// after a failed submission.
+    pub fn account_mut(&mut self) -> &mut Account {
+        self.account.as_mut().expect("channel account")
+    }
+
+    /// Build a transaction with the channel as source and `operations`.
+    ///
+    /// Operations without a source account are executed on behalf of
+    /// the pool primary account.
+    pub fn build<I>(&mut self, operations: I) -> Result<Transaction>
+    where
+        I: IntoIterator<Item = Operation>,
+    {
+        let primary = self.pool.primary;
+        let mut builder = TransactionBuilder::new(self.account_mut());
+        for op in operations {
+            builder = builder.operation(with_default_source(op, primary));
+        }
+        builder.build()
+    }
+}
+
+impl<'a> Drop for Channel<'a> {
+    fn drop(&mut self) {
+        if let Some(account) = self.account.take() {
+            self.pool.release(account);
+        }
+    }
+}
+
+fn with_default_source(mut op: Operation, primary: PublicKey) -> Operation {
+    {
+        let source = match op {
+            Operation::CreateAccount(ref mut op) => &mut op.source,
+            Operation::Payment(ref mut op) => &mut op.source,
+            Operation::PathPayment(ref mut op) => &mut op.source,
+            Operation::ManageOffer(ref mut op) => &mut op.source,
+            Operation::CreatePassiveOffer(ref mut op) => &mut op.source,
+            Operation::Inflation(ref mut op) => &mut op.source,
+            Operation::ManageData(ref mut op) => &mut op.source,
+            // These operations carry no source account yet.
+            Operation::SetOptions
+            | Operation::ChangeTrust
+            | Operation::AllowTrust
+            | Operation::AccountMerge => return op,
+        };
+        if source.is_none() {
+            *source = Some(primary);
+        }
+    }
+    op
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::thread;
+    use {Account, Network, Operation, OperationBuilder};
+    use crypto::keypair::from_secret_seed;
+    use super::ChannelPool;
+
+    const PRIMARY: &str = "SDFRU2NGDPXYIY67BVS6L6W4OY33HCFCEJQ73TZZPR3IDYVVI7BVPV5Q";
+    const CHANNELS: [&str; 2] = [
+        "SCRG6SFG64YDEVGWDWTZBE6BWEW25WICOGOUTODVICCA3L3FOQIG26E6",
+        "SCOHYGOOQBONAMHLFFTS3OHG2V45GHRVU6Q5HI7VZ2T4C3WRSVCUAWRN",
+    ];
+
+    fn pool() -> ChannelPool {
+        let primary = from_secret_seed(PRIMARY).unwrap();
+        let channels = CHANNELS
+            .iter()
+            .map(|s| Account::new(from_secret_seed(s).unwrap().public, 100))
+            .collect();
+        ChannelPool::new(primary.public, channels)
+    }
+
+    #[test]
+    fn test_acquire_release() {
+        let pool = pool();
+        assert_eq!(pool.available(), 2);
+        {
+            let _a = pool.acquire().unwrap();
+            let _b = pool.try_acquire().unwrap();
+            assert_eq!(pool.available(), 0);
+            assert!(pool.try_acquire().is_none());
+        }
+        assert_eq!(pool.available(), 2);
+    }
+
+    #[test]
+    fn test_build_and_sign() {
+        let pool = pool();
+        let primary = from_secret_seed(PRIMARY).unwrap();
+        let mut channel = pool.acquire().unwrap();
+        let channel_kp = CHANNELS
+            .iter()
+            .map(|s| from_secret_seed(s).unwrap())
+            .find(|kp| kp.public == *channel.account().account_id())
+            .unwrap();
+        let tx = channel
+            .build(vec![OperationBuilder::inflation().build()])
+            .unwrap();
+        assert_eq!(tx.sequence(), 101);
+        assert_eq!(tx.source(), channel.account().account_id());
+        match tx.operations()[0] {
+            Operation::Inflation(ref op) => assert_eq!(op.source, Some(primary.public)),
+            _ => panic!("expected inflation"),
+        }
+
+        let network = Network::test_network();
+        assert!(pool.sign(tx.clone(), &primary, &primary, &network).is_err());
+        let signed = pool.sign(tx, &channel_kp, &primary, &network).unwrap();
+        assert_eq!(signed.signatures().len(), 2);
+    }
+
+    #[test]
+    fn test_parallel_sequences() {
+        let pool = Arc::new(pool());
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let pool = pool.clone();
+                thread::spawn(move || {
+                    let mut seqs = Vec::new();
+                    for _ in 0..10 {
+                        let mut channel = pool.acquire().unwrap();
+                        let tx = channel
+                            .build(vec![OperationBuilder::inflation().build()])
+                            .unwrap();
+                        seqs.push((*tx.source().as_bytes(), tx.sequence()));
+                    }
+                    seqs
+                })
+            })
+            .collect();
+        let mut seen = HashSet::new();
+        for handle in handles {
+            for seq in handle.join().unwrap() {
+                assert!(seen.insert(seq));
+            }
+        }
+        assert_eq!(seen.len(), 80);
+        assert_eq!(pool.available(), 2);
+    }
+}
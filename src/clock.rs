@@ -0,0 +1,60 @@
+use std::fmt;
+use std::time::SystemTime;
+use time_bounds::UnixTimestamp;
+
+/// A source for the current time.
+///
+/// All lookups of "now" go through a `Clock`, so that tests can use a
+/// `FixedClock` instead of the system time.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Return the current time.
+    fn now(&self) -> UnixTimestamp;
+}
+
+/// A clock that returns the system time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> UnixTimestamp {
+        UnixTimestamp::from_system_time(SystemTime::now())
+    }
+}
+
+/// A clock that always returns the same time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedClock(pub UnixTimestamp);
+
+impl FixedClock {
+    /// Create a clock that always returns `now`.
+    pub fn new(now: UnixTimestamp) -> FixedClock {
+        FixedClock(now)
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> UnixTimestamp {
+        self.0.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+    use super::{Clock, FixedClock, SystemClock};
+    use time_bounds::UnixTimestamp;
+
+    #[test]
+    fn test_fixed_clock() {
+        let clock = FixedClock::new(UnixTimestamp(1234));
+        assert_eq!(clock.now(), UnixTimestamp(1234));
+        assert_eq!(clock.now(), UnixTimestamp(1234));
+    }
+
+    #[test]
+    fn test_system_clock() {
+        let now = SystemClock.now();
+        let later = UNIX_EPOCH + Duration::from_secs(1_500_000_000);
+        assert!(now > UnixTimestamp::from_system_time(later));
+    }
+}
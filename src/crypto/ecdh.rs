@@ -1,21 +1,59 @@
 //! Elliptic-curve Diffie–Hellman
+use std::fmt;
 use crypto;
+use crypto::HmacSha256Key;
+use curve25519_dalek::constants::X25519_BASEPOINT;
+use curve25519_dalek::montgomery::MontgomeryPoint;
 use curve25519_dalek::scalar::Scalar;
-use std::ops::Mul;
+use error::{Error, Result};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
 
 
 /// EC secret key.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Clone)]
 pub struct Curve25519Secret(pub [u8; 32]);
 
 impl Curve25519Secret {
     /// Create a random secret key.
     pub fn random() -> Curve25519Secret {
-        let seed = crypto::random_bytes(32);
+        let mut seed = crypto::random_bytes(32);
         let mut data = [0; 32];
         data.copy_from_slice(&seed[..32]);
+        seed.zeroize();
         Curve25519Secret(data)
     }
+
+    /// The secret key as X25519 scalar, clamped as specified in RFC 7748.
+    fn to_scalar(&self) -> Scalar {
+        let mut bytes = self.0;
+        bytes[0] &= 248;
+        bytes[31] &= 127;
+        bytes[31] |= 64;
+        let scalar = Scalar::from_bits(bytes);
+        bytes.zeroize();
+        scalar
+    }
+}
+
+impl PartialEq for Curve25519Secret {
+    fn eq(&self, other: &Curve25519Secret) -> bool {
+        self.0.ct_eq(&other.0).into()
+    }
+}
+
+impl Eq for Curve25519Secret {}
+
+impl fmt::Debug for Curve25519Secret {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Curve25519Secret(<redacted>)")
+    }
+}
+
+impl Drop for Curve25519Secret {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
 }
 
 /// EC public key.
@@ -25,10 +63,117 @@ pub struct Curve25519Public(pub [u8; 32]);
 impl Curve25519Public {
     /// Create a public key, derived from the secret key.
     pub fn derive_from_secret(secret: &Curve25519Secret) -> Curve25519Public {
-        let scalar = Scalar::from_bytes_mod_order(secret.0);
-        let q = [0; 32];
-        let scalarq = Scalar::from_bytes_mod_order(q);
-        let group_element = scalarq.mul(&scalar);
-        Curve25519Public(group_element.to_bytes())
+        let point = X25519_BASEPOINT * secret.to_scalar();
+        Curve25519Public(point.to_bytes())
+    }
+}
+
+/// Derive the key shared between the local and remote peers.
+///
+/// Follows stellar-core: the key is `hkdf-extract(q | A | B)`, where `q` is the
+/// X25519 shared secret, `A` the public key of the peer that initiated the
+/// connection and `B` the other peer public key.
+pub fn shared_key(
+    local_secret: &Curve25519Secret,
+    remote_public: &Curve25519Public,
+    we_called_remote: bool,
+) -> Result<HmacSha256Key> {
+    let local_public = Curve25519Public::derive_from_secret(local_secret);
+    let mut q = (MontgomeryPoint(remote_public.0) * local_secret.to_scalar()).to_bytes();
+    if bool::from(q.ct_eq(&[0; 32])) {
+        return Err(Error::InvalidPublicKey);
+    }
+    let (public_a, public_b) = if we_called_remote {
+        (&local_public, remote_public)
+    } else {
+        (remote_public, &local_public)
+    };
+    let mut buf = Vec::with_capacity(96);
+    buf.extend_from_slice(&q);
+    buf.extend_from_slice(&public_a.0);
+    buf.extend_from_slice(&public_b.0);
+    let key = HmacSha256Key::hkdf_extract(&buf);
+    q.zeroize();
+    buf.zeroize();
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{shared_key, Curve25519Public, Curve25519Secret};
+
+    // RFC 7748, section 6.1
+    const ALICE_SECRET: [u8; 32] = [
+        0x77, 0x07, 0x6D, 0x0A, 0x73, 0x18, 0xA5, 0x7D, 0x3C, 0x16, 0xC1, 0x72, 0x51, 0xB2,
+        0x66, 0x45, 0xDF, 0x4C, 0x2F, 0x87, 0xEB, 0xC0, 0x99, 0x2A, 0xB1, 0x77, 0xFB, 0xA5,
+        0x1D, 0xB9, 0x2C, 0x2A,
+    ];
+    const ALICE_PUBLIC: [u8; 32] = [
+        0x85, 0x20, 0xF0, 0x09, 0x89, 0x30, 0xA7, 0x54, 0x74, 0x8B, 0x7D, 0xDC, 0xB4, 0x3E,
+        0xF7, 0x5A, 0x0D, 0xBF, 0x3A, 0x0D, 0x26, 0x38, 0x1A, 0xF4, 0xEB, 0xA4, 0xA9, 0x8E,
+        0xAA, 0x9B, 0x4E, 0x6A,
+    ];
+    const BOB_SECRET: [u8; 32] = [
+        0x5D, 0xAB, 0x08, 0x7E, 0x62, 0x4A, 0x8A, 0x4B, 0x79, 0xE1, 0x7F, 0x8B, 0x83, 0x80,
+        0x0E, 0xE6, 0x6F, 0x3B, 0xB1, 0x29, 0x26, 0x18, 0xB6, 0xFD, 0x1C, 0x2F, 0x8B, 0x27,
+        0xFF, 0x88, 0xE0, 0xEB,
+    ];
+    const BOB_PUBLIC: [u8; 32] = [
+        0xDE, 0x9E, 0xDB, 0x7D, 0x7B, 0x7D, 0xC1, 0xB4, 0xD3, 0x5B, 0x61, 0xC2, 0xEC, 0xE4,
+        0x35, 0x37, 0x3F, 0x83, 0x43, 0xC8, 0x5B, 0x78, 0x67, 0x4D, 0xAD, 0xFC, 0x7E, 0x14,
+        0x6F, 0x88, 0x2B, 0x4F,
+    ];
+
+    #[test]
+    fn test_derive_from_secret() {
+        let alice = Curve25519Public::derive_from_secret(&Curve25519Secret(ALICE_SECRET));
+        let bob = Curve25519Public::derive_from_secret(&Curve25519Secret(BOB_SECRET));
+        assert_eq!(alice, Curve25519Public(ALICE_PUBLIC));
+        assert_eq!(bob, Curve25519Public(BOB_PUBLIC));
+    }
+
+    #[test]
+    fn test_shared_key() {
+        let expected = [
+            0x90, 0x5B, 0xB9, 0x44, 0xD3, 0xDD, 0xFC, 0x0F, 0xB3, 0x23, 0xCD, 0x2C, 0x01, 0x50,
+            0x58, 0x20, 0xE5, 0x33, 0x3E, 0x35, 0x68, 0xF1, 0xFE, 0x77, 0x40, 0xB3, 0xFD, 0x8A,
+            0x66, 0x19, 0x89, 0x49,
+        ];
+        let alice_key = shared_key(
+            &Curve25519Secret(ALICE_SECRET),
+            &Curve25519Public(BOB_PUBLIC),
+            true,
+        ).unwrap();
+        let bob_key = shared_key(
+            &Curve25519Secret(BOB_SECRET),
+            &Curve25519Public(ALICE_PUBLIC),
+            false,
+        ).unwrap();
+        assert_eq!(alice_key.0, expected);
+        assert_eq!(bob_key, alice_key);
+    }
+
+    #[test]
+    fn test_shared_key_random() {
+        let a = Curve25519Secret::random();
+        let b = Curve25519Secret::random();
+        let a_pub = Curve25519Public::derive_from_secret(&a);
+        let b_pub = Curve25519Public::derive_from_secret(&b);
+        assert_ne!(a_pub, b_pub);
+        let k_a = shared_key(&a, &b_pub, false).unwrap();
+        let k_b = shared_key(&b, &a_pub, true).unwrap();
+        assert_eq!(k_a, k_b);
+    }
+
+    #[test]
+    fn test_secret_debug_redacted() {
+        let secret = Curve25519Secret(ALICE_SECRET);
+        assert_eq!(format!("{:?}", secret), "Curve25519Secret(<redacted>)");
+    }
+
+    #[test]
+    fn test_shared_key_low_order_point() {
+        let a = Curve25519Secret::random();
+        assert!(shared_key(&a, &Curve25519Public([0; 32]), true).is_err());
     }
 }
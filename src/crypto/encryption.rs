@@ -0,0 +1,217 @@
+//! Encryption of data addressed to Stellar accounts.
+//!
+//! The ed25519 account keys are converted to X25519 keys and used with
+//! libsodium compatible boxes: sealed boxes are anonymous, authenticated boxes
+//! prove the sender identity to the recipient.
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use ed25519_dalek::{ExpandedSecretKey, Keypair, PublicKey, SecretKey};
+use crypto;
+use crypto::backend;
+use crypto::{Curve25519Public, Curve25519Secret};
+use error::{Error, Result};
+use memo::Memo;
+use zeroize::Zeroize;
+use operation::Operation;
+use operation_builder::OperationBuilder;
+
+/// Maximum length of a data entry name.
+const MAX_DATA_NAME_LEN: usize = 64;
+/// Maximum length of a data entry value.
+const MAX_DATA_VALUE_LEN: usize = 64;
+/// Length of the box nonce.
+const NONCE_LEN: usize = 24;
+
+/// Convert the account public key to a X25519 public key.
+pub fn to_curve25519_public(public: &PublicKey) -> Result<Curve25519Public> {
+    let point = CompressedEdwardsY(public.to_bytes())
+        .decompress()
+        .ok_or(Error::InvalidPublicKey)?;
+    Ok(Curve25519Public(point.to_montgomery().to_bytes()))
+}
+
+/// Convert the account secret key to a X25519 secret key.
+pub fn to_curve25519_secret(secret: &SecretKey) -> Curve25519Secret {
+    let mut expanded = ExpandedSecretKey::from(secret).to_bytes();
+    let mut key = [0; 32];
+    key.copy_from_slice(&expanded[..32]);
+    expanded.zeroize();
+    Curve25519Secret(key)
+}
+
+/// Encrypt `message` for `recipient`, without revealing the sender.
+pub fn seal(message: &[u8], recipient: &PublicKey) -> Result<Vec<u8>> {
+    let pk = to_curve25519_public(recipient)?;
+    Ok(backend::sealed_box_seal(message, &pk.0))
+}
+
+/// Decrypt a `ciphertext` created with `seal` for `recipient`.
+pub fn open_sealed(ciphertext: &[u8], recipient: &Keypair) -> Result<Vec<u8>> {
+    let pk = to_curve25519_public(&recipient.public)?;
+    let sk = to_curve25519_secret(&recipient.secret);
+    backend::sealed_box_open(ciphertext, &pk.0, &sk.0)
+}
+
+/// Encrypt and authenticate `message` from `sender` to `recipient`.
+///
+/// The returned ciphertext is prefixed by the random nonce.
+pub fn encrypt(message: &[u8], sender: &Keypair, recipient: &PublicKey) -> Result<Vec<u8>> {
+    let pk = to_curve25519_public(recipient)?;
+    let sk = to_curve25519_secret(&sender.secret);
+    let mut nonce = [0; NONCE_LEN];
+    nonce.copy_from_slice(&crypto::random_bytes(NONCE_LEN));
+    let mut out = nonce.to_vec();
+    out.extend(backend::box_seal(message, &nonce, &pk.0, &sk.0));
+    Ok(out)
+}
+
+/// Decrypt and verify a `ciphertext` created with `encrypt` by `sender` for `recipient`.
+pub fn decrypt(ciphertext: &[u8], recipient: &Keypair, sender: &PublicKey) -> Result<Vec<u8>> {
+    if ciphertext.len() < NONCE_LEN {
+        return Err(Error::InvalidCiphertext);
+    }
+    let (nonce_bytes, boxed) = ciphertext.split_at(NONCE_LEN);
+    let mut nonce = [0; NONCE_LEN];
+    nonce.copy_from_slice(nonce_bytes);
+    let pk = to_curve25519_public(sender)?;
+    let sk = to_curve25519_secret(&recipient.secret);
+    backend::box_open(boxed, &nonce, &pk.0, &sk.0)
+}
+
+/// Return a hash memo referencing `ciphertext`, which is stored elsewhere.
+pub fn memo_reference(ciphertext: &[u8]) -> Memo {
+    let mut hash = [0; 32];
+    hash.copy_from_slice(&crypto::hash(ciphertext));
+    Memo::Hash(hash)
+}
+
+/// Split `ciphertext` into `ManageData` operations named `<prefix>.<index>`.
+pub fn to_data_operations(prefix: &str, ciphertext: &[u8]) -> Result<Vec<Operation>> {
+    ciphertext
+        .chunks(MAX_DATA_VALUE_LEN)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let name = data_name(prefix, i)?;
+            Ok(OperationBuilder::set_data(name, chunk.to_vec()).build())
+        })
+        .collect()
+}
+
+/// Join the data `entries` named `<prefix>.<index>` back into the ciphertext.
+///
+/// Entries with other names are ignored.
+pub fn from_data_entries(prefix: &str, entries: &[(String, Vec<u8>)]) -> Result<Vec<u8>> {
+    let mut ciphertext = Vec::new();
+    let mut i = 0;
+    loop {
+        let name = data_name(prefix, i)?;
+        match entries.iter().find(|entry| entry.0 == name) {
+            None => break,
+            Some(entry) => ciphertext.extend_from_slice(&entry.1),
+        }
+        i += 1;
+    }
+    if ciphertext.is_empty() {
+        Err(Error::InvalidCiphertext)
+    } else {
+        Ok(ciphertext)
+    }
+}
+
+fn data_name(prefix: &str, index: usize) -> Result<String> {
+    let name = format!("{}.{}", prefix, index);
+    if name.len() > MAX_DATA_NAME_LEN {
+        Err(Error::InvalidDataName)
+    } else {
+        Ok(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decrypt, encrypt, from_data_entries, memo_reference, open_sealed, seal,
+                to_curve25519_public, to_curve25519_secret, to_data_operations};
+    use crypto::keypair::from_secret_seed;
+    use base64;
+    use crypto::{shared_key, Curve25519Public};
+    use Operation;
+
+    const ALICE: &str = "SDFRU2NGDPXYIY67BVS6L6W4OY33HCFCEJQ73TZZPR3IDYVVI7BVPV5Q";
+    const BOB: &str = "SCRG6SFG64YDEVGWDWTZBE6BWEW25WICOGOUTODVICCA3L3FOQIG26E6";
+
+    #[test]
+    fn test_curve25519_conversion() {
+        let alice = from_secret_seed(ALICE).unwrap();
+        let bob = from_secret_seed(BOB).unwrap();
+        let alice_secret = to_curve25519_secret(&alice.secret);
+        let alice_public = to_curve25519_public(&alice.public).unwrap();
+        assert_eq!(Curve25519Public::derive_from_secret(&alice_secret), alice_public);
+
+        let bob_secret = to_curve25519_secret(&bob.secret);
+        let bob_public = to_curve25519_public(&bob.public).unwrap();
+        let k_a = shared_key(&alice_secret, &bob_public, true).unwrap();
+        let k_b = shared_key(&bob_secret, &alice_public, false).unwrap();
+        assert_eq!(k_a, k_b);
+    }
+
+    #[test]
+    fn test_sealed_box() {
+        let bob = from_secret_seed(BOB).unwrap();
+        let alice = from_secret_seed(ALICE).unwrap();
+        let ciphertext = seal(b"kyc reference 42", &bob.public).unwrap();
+        assert_eq!(open_sealed(&ciphertext, &bob).unwrap(), b"kyc reference 42");
+        assert!(open_sealed(&ciphertext, &alice).is_err());
+    }
+
+    #[test]
+    fn test_authenticated_box() {
+        let alice = from_secret_seed(ALICE).unwrap();
+        let bob = from_secret_seed(BOB).unwrap();
+        let ciphertext = encrypt(b"kyc reference 42", &alice, &bob.public).unwrap();
+        let plaintext = decrypt(&ciphertext, &bob, &alice.public).unwrap();
+        assert_eq!(plaintext, b"kyc reference 42");
+        assert!(decrypt(&ciphertext, &bob, &bob.public).is_err());
+        assert!(decrypt(&ciphertext[..10], &bob, &alice.public).is_err());
+    }
+
+    #[test]
+    fn test_open_libsodium_ciphertexts() {
+        let alice = from_secret_seed(ALICE).unwrap();
+        let bob = from_secret_seed(BOB).unwrap();
+        let sealed = base64::decode(
+            "jRACEOOpR/b14e9d5OOvr7pZVmhOUS/2rcLCvcFJ1SbCXrCKZKEPGrQ5CDm3\
+             8aTAbl2odbYtQFjMyI4M4DfeIg==",
+        ).unwrap();
+        assert_eq!(open_sealed(&sealed, &bob).unwrap(), b"kyc reference 42");
+        let boxed = base64::decode(
+            "WJTp47D/ciPMcebtdgKSx30VsLkEiL0WdhbMNNmgAfHA8myc5063sBgfCLW5\
+             5HO/z7Fvu4zGUlw=",
+        ).unwrap();
+        assert_eq!(decrypt(&boxed, &bob, &alice.public).unwrap(), b"kyc reference 42");
+    }
+
+    #[test]
+    fn test_data_operations() {
+        let bob = from_secret_seed(BOB).unwrap();
+        let ciphertext = seal(&[7; 100], &bob.public).unwrap();
+        let ops = to_data_operations("kyc", &ciphertext).unwrap();
+        assert_eq!(ops.len(), 3);
+        let entries: Vec<(String, Vec<u8>)> = ops
+            .into_iter()
+            .map(|op| match op {
+                Operation::ManageData(op) => (op.name, op.value.unwrap()),
+                _ => panic!("expected manage data"),
+            })
+            .collect();
+        assert_eq!(entries[0].0, "kyc.0");
+        let joined = from_data_entries("kyc", &entries).unwrap();
+        assert_eq!(joined, ciphertext);
+        assert_eq!(open_sealed(&joined, &bob).unwrap(), vec![7; 100]);
+        assert!(to_data_operations(&"x".repeat(64), &ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_memo_reference() {
+        let memo = memo_reference(b"ciphertext");
+        assert!(memo.is_hash());
+    }
+}
@@ -1,9 +1,13 @@
+use std::fmt;
 use error::{Error, Result};
 use signature::{DecoratedSignature, SignatureHint};
-//use network::Network;
+use network::Network;
+use crypto::message;
 use crypto::strkey;
-//use crypto;
-use ed25519_dalek::{PublicKey, SecretKey};
+use crypto;
+use ed25519_dalek::{PublicKey, SecretKey, Signature};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
 
 /// Create from `account_id`, e.g. `GB3KJPLFUYN5VL6R3GU3EGCGVCKFDSD7BEDX42HWG5BWFKB3KQGJJRMA`.
 pub fn from_account_id(account_id: &str) -> Result<PublicKey> {
@@ -19,53 +23,16 @@ pub fn from_slice(data: &[u8]) -> Result<PublicKey> {
 pub fn account_id(public: &ed25519_dalek::PublicKey) -> Result<String> {
     strkey::encode_account_id(public.as_bytes())
 }
-/*
-impl PublicKey {
-
-    /// Return the public key as string, starting with `G`.
-    pub fn account_id(&self) -> Result<String> {
-        strkey::encode_account_id(&self.key.to_bytes())
-    }
-
-    /// Return the inner key buffer.
-    pub fn buf(&self) -> &[u8] {
-        self.key.as_bytes()SecretKey
-    }SecretKey
-SecretKey
-    /// Return the inner key.SecretKey
-    pub fn inner(&self) -> &ed255SecretKey19_dalek::PublicKey {
-        &self.keySecretKey
-    }SecretKey
-}
-*/
 /// Get secret string from secret key
 pub fn secret_seed(key: &SecretKey) -> Result<String> {
     strkey::encode_secret_seed(key.as_bytes())
 }
-/*
-/// The secret key of the account.
-#[derive(Debug, Clone)]
-pub struct SecretKey {
-    /// A field.
-    pub key: ed25519_dalek::SecretKey,
-}
-
-impl SecretKey {
-    /// Return the inner key.
-    pub fn inner(&self) -> &ed25519_dalek::SecretKey {
-        &self.key
-    }
-
-    /// Return the secret key as String, starting with `S`.
-    pub fn secret_seed(&self) -> Result<String> {
-        strkey::encode_secret_seed(&self.key.to_bytes())
-    }
-}
-*/
 /// Get keypair from secret string
 pub fn from_secret_seed(data: &str) -> Result<ed25519_dalek::Keypair> {
-    let bytes = strkey::decode_secret_seed(&data)?;
-    let secret = ed25519_dalek::SecretKey::from_bytes(&bytes).unwrap();
+    let mut bytes = strkey::decode_secret_seed(data)?;
+    let secret = ed25519_dalek::SecretKey::from_bytes(&bytes);
+    bytes.zeroize();
+    let secret = secret.map_err(|_| Error::InvalidSeed)?;
     let public = ed25519_dalek::PublicKey::from(&secret);
     let keypair = ed25519_dalek::Keypair{secret, public};
     Ok(keypair)
@@ -80,18 +47,29 @@ pub fn sign_decorated(kp: &ed25519_dalek::Keypair, message: &[u8]) -> DecoratedS
 pub fn signature_hint(kp: &ed25519_dalek::Keypair) -> SignatureHint {
     SignatureHint::from_public_key(&kp.public)
 }
-/*
+
+/// A key pair, used to sign transactions.
+///
+/// `Display` shows the account id, `Debug` never shows the secret key.
+pub struct KeyPair {
+    inner: ed25519_dalek::Keypair,
+}
+
 impl KeyPair {
     /// Create the key pair from the secret seed, e.g. `SDAKFNYEIAORZKKCYRILFQKLLOCNPL5SWJ3YY5NM3ZH6GJSZGXHZEPQS`.
     pub fn from_secret_seed(data: &str) -> Result<KeyPair> {
-        let bytes = strkey::decode_secret_seed(&data)?;
-        Self::from_seed_bytes(&bytes)
+        let mut bytes = strkey::decode_secret_seed(data)?;
+        let keypair = Self::from_seed_bytes(&bytes);
+        bytes.zeroize();
+        keypair
     }
 
     /// Create a random key pair.
     pub fn random() -> Result<KeyPair> {
-        let seed = crypto::random_bytes(32);
-        Self::from_seed_bytes(&seed)
+        let mut seed = crypto::random_bytes(32);
+        let keypair = Self::from_seed_bytes(&seed);
+        seed.zeroize();
+        keypair
     }
 
     /// Create a key pair from the `network` passphrase.
@@ -100,62 +78,121 @@ impl KeyPair {
         Self::from_seed_bytes(&bytes)
     }
 
-    /// Crete a key pair from raw bytes.
+    /// Create a key pair from the raw 32 bytes seed.
     pub fn from_seed_bytes(data: &[u8]) -> Result<KeyPair> {
-        let keypair = ed25519_dalek::Keypair::from_bytes(&data).ok().ok_or(Error::InvalidSeed)?;
-        let public = PublicKey { key: keypair.public };
-        let secret = SecretKey { key: keypair.secret };
-
-        Ok(KeyPair { public, secret })
+        let secret = SecretKey::from_bytes(data).ok().ok_or(Error::InvalidSeed)?;
+        let public = PublicKey::from(&secret);
+        let inner = ed25519_dalek::Keypair { secret, public };
+        Ok(KeyPair { inner })
     }
 
     /// Return the public key.
     pub fn public_key(&self) -> &PublicKey {
-        &self.public
+        &self.inner.public
     }
 
     /// Return the secret key.
     pub fn secret_key(&self) -> &SecretKey {
-        &self.secret
+        &self.inner.secret
+    }
+
+    /// Return the inner key pair.
+    pub fn inner(&self) -> &ed25519_dalek::Keypair {
+        &self.inner
+    }
+
+    /// Return the public key as string, starting with `G`.
+    pub fn account_id(&self) -> Result<String> {
+        account_id(&self.inner.public)
+    }
+
+    /// Return the secret key as string, starting with `S`.
+    pub fn secret_seed(&self) -> Result<String> {
+        secret_seed(&self.inner.secret)
     }
 
     /// Sign the `message`.
     pub fn sign(&self, message: &[u8]) -> Signature {
-        let kp = ed25519_dalek::Keypair{
-            public: self.public.key,
-            secret: self.secret.key,
-        };        
-        Signature{sig: kp.sign(&message)}
-        
-        //Signature::sign(&self.secret, &message)
+        self.inner.sign(message)
     }
 
     /// Sign the `message` together with the signature hint.
     pub fn sign_decorated(&self, message: &[u8]) -> DecoratedSignature {
-        let hint = self.signature_hint();
-        let signature = self.sign(message);
-        DecoratedSignature::new(hint, signature)
+        sign_decorated(&self.inner, message)
     }
 
     /// Verify the `signature` against the `message`.
     pub fn verify(&self, message: &[u8], signature: &Signature) -> bool {
-        let kp = ed25519_dalek::Keypair{
-            secret: self.secret.key,
-            public: self.public.key,
-        };        
-        kp.verify(&message, &signature.sig).is_ok()
-        //signature.verify(&self.public, &message)
+        self.inner.public.verify(message, signature).is_ok()
+    }
+
+    /// Sign an off-chain `message` following SEP-53, returning the base64 encoded signature.
+    pub fn sign_message<M: AsRef<[u8]>>(&self, message: M) -> String {
+        message::sign_message(&self.inner, message)
+    }
+
+    /// Verify the base64 encoded SEP-53 `signature` of `message`.
+    pub fn verify_message<M: AsRef<[u8]>>(&self, message: M, signature: &str) -> bool {
+        message::verify_message(&self.inner.public, message, signature)
     }
 
     /// Return the signature hint, that is the last 4 bytes of the public key.
     pub fn signature_hint(&self) -> SignatureHint {
-        SignatureHint::from_public_key(&self.public)
+        signature_hint(&self.inner)
+    }
+}
+
+impl From<ed25519_dalek::Keypair> for KeyPair {
+    fn from(inner: ed25519_dalek::Keypair) -> KeyPair {
+        KeyPair { inner }
+    }
+}
+
+impl Clone for KeyPair {
+    fn clone(&self) -> KeyPair {
+        let secret = SecretKey::from_bytes(self.inner.secret.as_bytes())
+            .expect("secret key is always valid");
+        let public = self.inner.public;
+        KeyPair {
+            inner: ed25519_dalek::Keypair { secret, public },
+        }
     }
 }
-*/
+
+impl PartialEq for KeyPair {
+    fn eq(&self, other: &KeyPair) -> bool {
+        let same_secret: bool = self.inner
+            .secret
+            .as_bytes()
+            .ct_eq(other.inner.secret.as_bytes())
+            .into();
+        self.inner.public == other.inner.public && same_secret
+    }
+}
+
+impl Eq for KeyPair {}
+
+impl fmt::Display for KeyPair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.account_id() {
+            Ok(id) => write!(f, "{}", id),
+            Err(_) => Err(fmt::Error),
+        }
+    }
+}
+
+impl fmt::Debug for KeyPair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("KeyPair")
+            .field("public", &self.account_id().unwrap_or_default())
+            .field("secret", &"<redacted>")
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{from_secret_seed, account_id, sign_decorated};
+    use super::{from_secret_seed, account_id, sign_decorated, KeyPair};
     use Network;
     #[test]
     fn test_from_secret_seed() {
@@ -250,4 +287,66 @@ mod tests {
         assert_eq!(sign.hint().to_vec(), vec![0x0B, 0xFA, 0xD1, 0x34]);
     }
 
+    #[test]
+    fn test_keypair_from_secret_seed() {
+        let the_secret = "SD7X7LEHBNMUIKQGKPARG5TDJNBHKC346OUARHGZL5ITC6IJPXHILY36";
+        let kp = KeyPair::from_secret_seed(the_secret).unwrap();
+        assert_eq!(kp.secret_seed().unwrap(), the_secret);
+        let message = "test post please ignore".as_bytes();
+        let sign = kp.sign(message);
+        assert!(kp.verify(message, &sign));
+        assert!(!kp.verify("another message".as_bytes(), &sign));
+        let decorated = kp.sign_decorated(message);
+        assert_eq!(decorated.hint().to_vec(), vec![0x0B, 0xFA, 0xD1, 0x34]);
+        assert_eq!(decorated.ed25519_signature(), Some(sign));
+    }
+
+    #[test]
+    fn test_keypair_random() {
+        let kp = KeyPair::random().unwrap();
+        let seed = kp.secret_seed().unwrap();
+        let other = KeyPair::from_secret_seed(&seed).unwrap();
+        assert_eq!(kp, other);
+        assert_ne!(kp, KeyPair::random().unwrap());
+    }
+
+    #[test]
+    fn test_keypair_from_network() {
+        let kp = KeyPair::from_network(&Network::public_network()).unwrap();
+        assert_eq!(
+            kp.to_string(),
+            "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7"
+        );
+    }
+
+    #[test]
+    fn test_from_secret_seed_malformed() {
+        assert!(from_secret_seed("GCZHXL5HXQX5ABDM26LHYRCQZ5OJFHLOPLZX47WEBP3V2PF5AVFK2A5D").is_err());
+        assert!(from_secret_seed("SDFRU2NGDPXYIY67BVS6L6W4OY33HCFCEJQ73TZZPR3IDYVVI7BVPV5A").is_err());
+        // Valid checksum, but 31 bytes payload.
+        let short = ::crypto::strkey::encode_secret_seed(&[1; 31]).unwrap();
+        assert!(from_secret_seed(&short).is_err());
+        assert!(KeyPair::from_secret_seed(&short).is_err());
+    }
+
+    #[test]
+    fn test_keypair_debug_redacts_secret() {
+        let the_secret = "SD7X7LEHBNMUIKQGKPARG5TDJNBHKC346OUARHGZL5ITC6IJPXHILY36";
+        let kp = KeyPair::from_secret_seed(the_secret).unwrap();
+        let debug = format!("{:?}", kp);
+        assert!(debug.contains(&kp.account_id().unwrap()));
+        assert!(debug.contains("<redacted>"));
+        assert!(!debug.contains(the_secret));
+    }
+
+    #[test]
+    fn test_keypair_sign_message() {
+        let kp = KeyPair::random().unwrap();
+        let signature = kp.sign_message("I own this account");
+        assert!(kp.verify_message("I own this account", &signature));
+        assert!(!kp.verify_message("I own that account", &signature));
+        // A message signature is not a signature of the raw message.
+        let raw = kp.sign(b"I own this account");
+        assert!(!kp.verify_message("I own this account", &::base64::encode(&raw.to_bytes()[..])));
+    }
 }
@@ -0,0 +1,287 @@
+//! Password protected storage of secret seeds.
+//!
+//! The keystore is a versioned JSON document. The seed is encrypted with
+//! xsalsa20-poly1305, using a key derived from the password with scrypt.
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "label": "payments",
+//!   "public_key": "GB3KJPLFUYN5VL6R3GU3EGCGVCKFDSD7BEDX42HWG5BWFKB3KQGJJRMA",
+//!   "kdf": { "algorithm": "scrypt", "salt": "...", "opslimit": 524288, "memlimit": 16777216 },
+//!   "cipher": { "algorithm": "xsalsa20poly1305", "nonce": "...", "ciphertext": "..." }
+//! }
+//! ```
+use base64;
+use serde_json;
+use crypto;
+use crypto::backend;
+use crypto::keypair::KeyPair;
+use error::{Error, Result};
+use zeroize::Zeroize;
+
+/// Version of the keystore format.
+pub const KEYSTORE_VERSION: u32 = 1;
+
+const KDF_ALGORITHM: &str = "scrypt";
+const CIPHER_ALGORITHM: &str = "xsalsa20poly1305";
+const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 24;
+/// Interactive limits of libsodium scrypt: `N = 2^14, r = 8, p = 1`.
+const OPSLIMIT: usize = 524_288;
+const MEMLIMIT: usize = 16_777_216;
+/// Sensitive limits of libsodium scrypt, the maximum accepted when decrypting.
+const MAX_OPSLIMIT: usize = 33_554_432;
+const MAX_MEMLIMIT: usize = 1_073_741_824;
+
+/// A secret seed encrypted with a password.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keystore {
+    version: u32,
+    label: Option<String>,
+    public_key: String,
+    kdf: KdfParams,
+    cipher: CipherParams,
+}
+
+/// Parameters of the key derivation function.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct KdfParams {
+    algorithm: String,
+    salt: String,
+    opslimit: usize,
+    memlimit: usize,
+}
+
+/// Parameters and output of the cipher.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CipherParams {
+    algorithm: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl Keystore {
+    /// Encrypt the secret `seed`, e.g. `SDAKFNYEIAORZKKCYRILFQKLLOCNPL5SWJ3YY5NM3ZH6GJSZGXHZEPQS`.
+    pub fn encrypt_seed(seed: &str, password: &str) -> Result<Keystore> {
+        let keypair = KeyPair::from_secret_seed(seed)?;
+        Self::encrypt_keypair(&keypair, password)
+    }
+
+    /// Encrypt the secret key of `keypair`.
+    pub fn encrypt_keypair(keypair: &KeyPair, password: &str) -> Result<Keystore> {
+        let public_key = keypair.account_id()?;
+        let (kdf, cipher) = encrypt(keypair.secret_key().as_bytes(), password)?;
+        Ok(Keystore {
+            version: KEYSTORE_VERSION,
+            label: None,
+            public_key,
+            kdf,
+            cipher,
+        })
+    }
+
+    /// Set the keystore label.
+    pub fn with_label<S: Into<String>>(mut self, label: S) -> Keystore {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Return the keystore label.
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Return the account id of the encrypted key.
+    pub fn public_key(&self) -> &str {
+        &self.public_key
+    }
+
+    /// Decrypt the secret seed, suitable for `from_secret_seed`.
+    pub fn decrypt_seed(&self, password: &str) -> Result<String> {
+        self.decrypt_keypair(password)?.secret_seed()
+    }
+
+    /// Decrypt the key pair.
+    pub fn decrypt_keypair(&self, password: &str) -> Result<KeyPair> {
+        let mut seed = decrypt(&self.kdf, &self.cipher, password)?;
+        let keypair = KeyPair::from_seed_bytes(&seed);
+        seed.zeroize();
+        let keypair = keypair.map_err(|_| Error::InvalidKeystore)?;
+        if keypair.account_id()? != self.public_key {
+            return Err(Error::InvalidKeystore);
+        }
+        Ok(keypair)
+    }
+
+    /// Encrypt the seed again with `new_password`, using a fresh salt and nonce.
+    pub fn change_password(&mut self, old_password: &str, new_password: &str) -> Result<()> {
+        let keypair = self.decrypt_keypair(old_password)?;
+        let (kdf, cipher) = encrypt(keypair.secret_key().as_bytes(), new_password)?;
+        self.kdf = kdf;
+        self.cipher = cipher;
+        Ok(())
+    }
+
+    /// Serialize the keystore to JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Deserialize the keystore from JSON.
+    pub fn from_json(json: &str) -> Result<Keystore> {
+        let keystore: Keystore = serde_json::from_str(json)?;
+        if keystore.version != KEYSTORE_VERSION
+            || keystore.kdf.algorithm != KDF_ALGORITHM
+            || keystore.cipher.algorithm != CIPHER_ALGORITHM
+        {
+            return Err(Error::InvalidKeystore);
+        }
+        Ok(keystore)
+    }
+}
+
+fn derive_key(
+    password: &str,
+    salt: &[u8; SALT_LEN],
+    opslimit: usize,
+    memlimit: usize,
+) -> Result<[u8; 32]> {
+    let mut key = [0; 32];
+    backend::scrypt(password.as_bytes(), salt, opslimit, memlimit, &mut key)?;
+    Ok(key)
+}
+
+fn encrypt(secret: &[u8], password: &str) -> Result<(KdfParams, CipherParams)> {
+    let mut salt = [0; SALT_LEN];
+    salt.copy_from_slice(&crypto::random_bytes(SALT_LEN));
+    let mut nonce = [0; NONCE_LEN];
+    nonce.copy_from_slice(&crypto::random_bytes(NONCE_LEN));
+    let mut key = derive_key(password, &salt, OPSLIMIT, MEMLIMIT)?;
+    let ciphertext = backend::secretbox_seal(secret, &nonce, &key);
+    key.zeroize();
+    let kdf = KdfParams {
+        algorithm: KDF_ALGORITHM.to_string(),
+        salt: base64::encode(&salt),
+        opslimit: OPSLIMIT,
+        memlimit: MEMLIMIT,
+    };
+    let cipher = CipherParams {
+        algorithm: CIPHER_ALGORITHM.to_string(),
+        nonce: base64::encode(&nonce),
+        ciphertext: base64::encode(&ciphertext),
+    };
+    Ok((kdf, cipher))
+}
+
+fn decrypt(kdf: &KdfParams, cipher: &CipherParams, password: &str) -> Result<Vec<u8>> {
+    // Refuse parameters that would make a crafted file exhaust memory or cpu.
+    if kdf.opslimit > MAX_OPSLIMIT || kdf.memlimit > MAX_MEMLIMIT {
+        return Err(Error::InvalidKeystore);
+    }
+    let mut salt = [0; SALT_LEN];
+    decode_into(&kdf.salt, &mut salt)?;
+    let mut nonce = [0; NONCE_LEN];
+    decode_into(&cipher.nonce, &mut nonce)?;
+    let ciphertext = base64::decode(&cipher.ciphertext)?;
+    let mut key = derive_key(password, &salt, kdf.opslimit, kdf.memlimit)?;
+    let seed = backend::secretbox_open(&ciphertext, &nonce, &key);
+    key.zeroize();
+    seed.map_err(|_| Error::InvalidPassword)
+}
+
+/// Decode the base64 `data` into `out`, which must have the same length.
+fn decode_into(data: &str, out: &mut [u8]) -> Result<()> {
+    let bytes = base64::decode(data)?;
+    if bytes.len() != out.len() {
+        return Err(Error::InvalidKeystore);
+    }
+    out.copy_from_slice(&bytes);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Keystore;
+    use crypto::keypair::from_secret_seed;
+    use error::Error;
+
+    const SEED: &str = "SDFRU2NGDPXYIY67BVS6L6W4OY33HCFCEJQ73TZZPR3IDYVVI7BVPV5Q";
+
+    #[test]
+    fn test_encrypt_decrypt_seed() {
+        let keystore = Keystore::encrypt_seed(SEED, "correct horse")
+            .unwrap()
+            .with_label("payments");
+        assert_eq!(keystore.label(), Some("payments"));
+        let keypair = from_secret_seed(SEED).unwrap();
+        let account_id = ::crypto::keypair::account_id(&keypair.public).unwrap();
+        assert_eq!(keystore.public_key(), account_id);
+
+        let seed = keystore.decrypt_seed("correct horse").unwrap();
+        assert_eq!(seed, SEED);
+        assert!(from_secret_seed(&seed).is_ok());
+        match keystore.decrypt_seed("wrong horse") {
+            Err(Error::InvalidPassword) => {}
+            _ => panic!("expected invalid password"),
+        }
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let keystore = Keystore::encrypt_seed(SEED, "correct horse")
+            .unwrap()
+            .with_label("payments");
+        let json = keystore.to_json().unwrap();
+        assert!(!json.contains(SEED));
+        let decoded = Keystore::from_json(&json).unwrap();
+        assert_eq!(decoded, keystore);
+        assert_eq!(decoded.decrypt_seed("correct horse").unwrap(), SEED);
+    }
+
+    #[test]
+    fn test_decrypt_libsodium_keystore() {
+        let json = r#"{
+            "version": 1,
+            "label": "payments",
+            "public_key": "GBR6A7TTX6MUYO6WZXZFAX3L2QSLYIHIGKN52EBNVKKB4AN4B6CRD22T",
+            "kdf": {
+                "algorithm": "scrypt",
+                "salt": "F6o5tkRjxcGqrGrAMLdAOJUaBHs3PAX0lk+j3bASwsQ=",
+                "opslimit": 524288,
+                "memlimit": 16777216
+            },
+            "cipher": {
+                "algorithm": "xsalsa20poly1305",
+                "nonce": "YDGkHh+MCxrlE7xJEj6a9jhDZdV2CqK0",
+                "ciphertext": "bSQLbX4U9clqCEHJDHnkvJYQA2XrTrtPDJKz42VE8lPK6w6cnLOTAsj+vgbgBxQG"
+            }
+        }"#;
+        let keystore = Keystore::from_json(json).unwrap();
+        assert_eq!(keystore.decrypt_seed("correct horse").unwrap(), SEED);
+    }
+
+    #[test]
+    fn test_change_password() {
+        let mut keystore = Keystore::encrypt_seed(SEED, "old").unwrap();
+        assert!(keystore.change_password("wrong", "new").is_err());
+        keystore.change_password("old", "new").unwrap();
+        assert!(keystore.decrypt_seed("old").is_err());
+        assert_eq!(keystore.decrypt_seed("new").unwrap(), SEED);
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        let keystore = Keystore::encrypt_seed(SEED, "pw").unwrap();
+        let json = keystore.to_json().unwrap();
+        assert!(Keystore::from_json(&json.replace("\"version\": 1", "\"version\": 2")).is_err());
+        assert!(Keystore::from_json(&json.replace("scrypt", "pbkdf2")).is_err());
+        assert!(Keystore::from_json("{}").is_err());
+
+        let other = "SCRG6SFG64YDEVGWDWTZBE6BWEW25WICOGOUTODVICCA3L3FOQIG26E6";
+        let other_public = Keystore::encrypt_seed(other, "pw").unwrap().public_key().to_string();
+        let tampered = json.replace(keystore.public_key(), &other_public);
+        let tampered = Keystore::from_json(&tampered).unwrap();
+        assert!(tampered.decrypt_seed("pw").is_err());
+    }
+}
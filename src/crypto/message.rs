@@ -0,0 +1,83 @@
+//! Signing of arbitrary messages, as specified in SEP-53.
+//!
+//! The signed data is `sha256("Stellar Signed Message:\n" | message)`, so that
+//! a message signature can never be mistaken for a transaction signature.
+use base64;
+use ed25519_dalek::{self, PublicKey, Signature};
+use crypto;
+
+/// Prefix prepended to messages before hashing.
+pub const MESSAGE_PREFIX: &[u8] = b"Stellar Signed Message:\n";
+
+/// Return the hash of `message` that is signed.
+pub fn message_hash<M: AsRef<[u8]>>(message: M) -> Vec<u8> {
+    let message = message.as_ref();
+    let mut payload = Vec::with_capacity(MESSAGE_PREFIX.len() + message.len());
+    payload.extend_from_slice(MESSAGE_PREFIX);
+    payload.extend_from_slice(message);
+    crypto::hash(&payload)
+}
+
+/// Sign `message`, either bytes or UTF-8 text, returning the base64 encoded signature.
+pub fn sign_message<M: AsRef<[u8]>>(keypair: &ed25519_dalek::Keypair, message: M) -> String {
+    let signature = keypair.sign(&message_hash(message));
+    base64::encode(&signature.to_bytes()[..])
+}
+
+/// Verify the base64 encoded `signature` of `message` by `public`.
+///
+/// Return `false` if the signature is malformed.
+pub fn verify_message<M: AsRef<[u8]>>(public: &PublicKey, message: M, signature: &str) -> bool {
+    let bytes = match base64::decode(signature) {
+        Ok(bytes) => bytes,
+        Err(_) => return false,
+    };
+    match Signature::from_bytes(&bytes) {
+        Ok(signature) => public.verify(&message_hash(message), &signature).is_ok(),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64;
+    use super::{sign_message, verify_message};
+    use crypto::keypair::{from_account_id, from_secret_seed};
+
+    // SEP-53 test vectors.
+    const SEED: &str = "SAKICEVQLYWGSOJS4WW7HZJWAHZVEEBS527LHK5V4MLJALYKICQCJXMW";
+    const ACCOUNT_ID: &str = "GBXFXNDLV4LSWA4VB7YIL5GBD7BVNR22SGBTDKMO2SBZZHDXSKZYCP7L";
+
+    #[test]
+    fn test_sign_message() {
+        let keypair = from_secret_seed(SEED).unwrap();
+        assert_eq!(
+            sign_message(&keypair, "Hello, World!"),
+            "fO5dbYhXUhBMhe6kId/cuVq/AfEnHRHEvsP8vXh03M1uLpi5e46yO2Q8rEBzu3feXQewcQE5GArp88u6ePK6BA=="
+        );
+        assert_eq!(
+            sign_message(&keypair, "こんにちは、世界！"),
+            "CDU265Xs8y3OWbB/56H9jPgUss5G9A0qFuTqH2zs2YDgTm+++dIfmAEceFqB7bhfN3am59lCtDXrCtwH2k1GBA=="
+        );
+        let binary = base64::decode("2zZDP1sa1BVBfLP7TeeMk3sUbaxAkUhBhDiNdrksaFo=").unwrap();
+        assert_eq!(
+            sign_message(&keypair, &binary),
+            "VA1+7hefNwv2NKScH6n+Sljj15kLAge+M2wE7fzFOf+L0MMbssA1mwfJZRyyrhBORQRle10X1Dxpx+UOI4EbDQ=="
+        );
+    }
+
+    #[test]
+    fn test_verify_message() {
+        let public = from_account_id(ACCOUNT_ID).unwrap();
+        let signature =
+            "fO5dbYhXUhBMhe6kId/cuVq/AfEnHRHEvsP8vXh03M1uLpi5e46yO2Q8rEBzu3feXQewcQE5GArp88u6ePK6BA==";
+        assert!(verify_message(&public, "Hello, World!", signature));
+        assert!(verify_message(&public, b"Hello, World!", signature));
+        assert!(!verify_message(&public, "Hello, World", signature));
+        assert!(!verify_message(&public, "Hello, World!", "not base64!"));
+        assert!(!verify_message(&public, "Hello, World!", "AAAA"));
+
+        let other = from_account_id("GBR6A7TTX6MUYO6WZXZFAX3L2QSLYIHIGKN52EBNVKKB4AN4B6CRD22T").unwrap();
+        assert!(!verify_message(&other, "Hello, World!", signature));
+    }
+}
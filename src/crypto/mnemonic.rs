@@ -0,0 +1,297 @@
+//! Key derivation from mnemonic phrases, as specified in SEP-5.
+//!
+//! The mnemonic is a BIP-39 phrase, the keys are derived from its seed with
+//! SLIP-10 for ed25519 along the path `m/44'/148'/index'`.
+use std::fmt;
+use bip39;
+use crypto::keypair::KeyPair;
+use crypto::sha::hmac_sha512;
+use error::{Error, Result};
+use zeroize::Zeroize;
+
+pub use bip39::{Language, MnemonicType};
+
+/// SLIP-10 key used to derive the master key.
+const MASTER_KEY: &[u8] = b"ed25519 seed";
+/// Offset of hardened indexes.
+const HARDENED_OFFSET: u32 = 0x8000_0000;
+/// BIP-44 purpose.
+const PURPOSE: u32 = 44;
+/// SLIP-44 coin type of Stellar.
+const COIN_TYPE: u32 = 148;
+
+/// A BIP-39 mnemonic phrase.
+///
+/// `Debug` never shows the phrase.
+pub struct Mnemonic {
+    inner: bip39::Mnemonic,
+}
+
+impl Mnemonic {
+    /// Generate a random mnemonic of `mnemonic_type` words in `language`.
+    pub fn random(mnemonic_type: MnemonicType, language: Language) -> Mnemonic {
+        let inner = bip39::Mnemonic::new(mnemonic_type, language);
+        Mnemonic { inner }
+    }
+
+    /// Create the mnemonic from `entropy`, 16 to 32 bytes in steps of 4.
+    pub fn from_entropy(entropy: &[u8], language: Language) -> Result<Mnemonic> {
+        let inner = bip39::Mnemonic::from_entropy(entropy, language)
+            .map_err(|_| Error::InvalidMnemonic)?;
+        Ok(Mnemonic { inner })
+    }
+
+    /// Create the mnemonic from the `phrase`, checking the words and the checksum.
+    pub fn from_phrase(phrase: &str, language: Language) -> Result<Mnemonic> {
+        let inner =
+            bip39::Mnemonic::from_phrase(phrase, language).map_err(|_| Error::InvalidMnemonic)?;
+        Ok(Mnemonic { inner })
+    }
+
+    /// Return the mnemonic phrase.
+    pub fn phrase(&self) -> &str {
+        self.inner.phrase()
+    }
+
+    /// Return the mnemonic language.
+    pub fn language(&self) -> Language {
+        self.inner.language()
+    }
+
+    /// Return the seed protected by the (possibly empty) `passphrase`.
+    pub fn seed(&self, passphrase: &str) -> Seed {
+        let seed = bip39::Seed::new(&self.inner, passphrase);
+        let mut bytes = [0; 64];
+        bytes.copy_from_slice(seed.as_bytes());
+        Seed(bytes)
+    }
+
+    /// Derive the key pair of the account at `index`.
+    pub fn keypair(&self, passphrase: &str, index: u32) -> Result<KeyPair> {
+        self.seed(passphrase).keypair(index)
+    }
+}
+
+impl fmt::Debug for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Mnemonic(<redacted>)")
+    }
+}
+
+/// The 64 bytes seed of a mnemonic.
+pub struct Seed(pub [u8; 64]);
+
+impl Seed {
+    /// Derive the key pair of the account at `index`, i.e. `m/44'/148'/index'`.
+    pub fn keypair(&self, index: u32) -> Result<KeyPair> {
+        self.derive(&[PURPOSE, COIN_TYPE, index])
+    }
+
+    /// Derive the key pair at `path`.
+    ///
+    /// Ed25519 only supports hardened derivation: each index in `path` is
+    /// hardened and must be smaller than `2^31`.
+    pub fn derive(&self, path: &[u32]) -> Result<KeyPair> {
+        let mut node = hmac_sha512(MASTER_KEY, &self.0);
+        for index in path {
+            if *index >= HARDENED_OFFSET {
+                node.zeroize();
+                return Err(Error::InvalidDerivationPath);
+            }
+            let child = derive_child(&node, index + HARDENED_OFFSET);
+            node.zeroize();
+            node = child;
+        }
+        let keypair = KeyPair::from_seed_bytes(&node[..32]);
+        node.zeroize();
+        keypair
+    }
+}
+
+impl fmt::Debug for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Seed(<redacted>)")
+    }
+}
+
+impl Drop for Seed {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+/// Derive the hardened child of `node`, the key followed by the chain code.
+fn derive_child(node: &[u8; 64], index: u32) -> [u8; 64] {
+    let mut data = Vec::with_capacity(37);
+    data.push(0);
+    data.extend_from_slice(&node[..32]);
+    data.extend_from_slice(&index.to_be_bytes());
+    let child = hmac_sha512(&node[32..], &data);
+    data.zeroize();
+    child
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Language, Mnemonic, MnemonicType};
+
+    // SEP-5 test vectors, indices 0 to 9.
+    const PHRASE_12: &str =
+        "illness spike retreat truth genius clock brain pass fit cave bargain toe";
+    const PHRASE_24: &str = "cable spray genius state float twenty onion head street palace \
+                             net private method loan turn phrase state blanket interest dry \
+                             amazing dress blast tube";
+
+    #[test]
+    fn test_derive_12_words() {
+        let mnemonic = Mnemonic::from_phrase(PHRASE_12, Language::English).unwrap();
+        let expected = [
+            (
+                "GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6",
+                "SBGWSG6BTNCKCOB3DIFBGCVMUPQFYPA2G4O34RMTB343OYPXU5DJDVMN",
+            ),
+            (
+                "GBAW5XGWORWVFE2XTJYDTLDHXTY2Q2MO73HYCGB3XMFMQ562Q2W2GJQX",
+                "SCEPFFWGAG5P2VX5DHIYK3XEMZYLTYWIPWYEKXFHSK25RVMIUNJ7CTIS",
+            ),
+            (
+                "GAY5PRAHJ2HIYBYCLZXTHID6SPVELOOYH2LBPH3LD4RUMXUW3DOYTLXW",
+                "SDAILLEZCSA67DUEP3XUPZJ7NYG7KGVRM46XA7K5QWWUIGADUZCZWTJP",
+            ),
+            (
+                "GAOD5NRAEORFE34G5D4EOSKIJB6V4Z2FGPBCJNQI6MNICVITE6CSYIAE",
+                "SBMWLNV75BPI2VB4G27RWOMABVRTSSF7352CCYGVELZDSHCXWCYFKXIX",
+            ),
+            (
+                "GBCUXLFLSL2JE3NWLHAWXQZN6SQC6577YMAU3M3BEMWKYPFWXBSRCWV4",
+                "SCPCY3CEHMOP2TADSV2ERNNZBNHBGP4V32VGOORIEV6QJLXD5NMCJUXI",
+            ),
+            (
+                "GBRQY5JFN5UBG5PGOSUOL4M6D7VRMAYU6WW2ZWXBMCKB7GPT3YCBU2XZ",
+                "SCK27SFHI3WUDOEMJREV7ZJQG34SCBR6YWCE6OLEXUS2VVYTSNGCRS6X",
+            ),
+            (
+                "GBY27SJVFEWR3DUACNBSMJB6T4ZPR4C7ZXSTHT6GMZUDL23LAM5S2PQX",
+                "SDJ4WDPOQAJYR3YIAJOJP3E6E4BMRB7VZ4QAEGCP7EYVDW6NQD3LRJMZ",
+            ),
+            (
+                "GAY7T23Z34DWLSTEAUKVBPHHBUE4E3EMZBAQSLV6ZHS764U3TKUSNJOF",
+                "SA3HXJUCE2N27TBIZ5JRBLEBF3TLPQEBINP47E6BTMIWW2RJ5UKR2B3L",
+            ),
+            (
+                "GDJTCF62UUYSAFAVIXHPRBR4AUZV6NYJR75INVDXLLRZLZQ62S44443R",
+                "SCD5OSHUUC75MSJG44BAT3HFZL2HZMMQ5M4GPDL7KA6HJHV3FLMUJAME",
+            ),
+            (
+                "GBTVYYDIYWGUQUTKX6ZMLGSZGMTESJYJKJWAATGZGITA25ZB6T5REF44",
+                "SCJGVMJ66WAUHQHNLMWDFGY2E72QKSI3XGSBYV6BANDFUFE7VY4XNXXR",
+            ),
+        ];
+        let seed = mnemonic.seed("");
+        for (index, &(account_id, secret_seed)) in expected.iter().enumerate() {
+            let keypair = seed.keypair(index as u32).unwrap();
+            assert_eq!(keypair.account_id().unwrap(), account_id);
+            assert_eq!(keypair.secret_seed().unwrap(), secret_seed);
+        }
+    }
+
+    #[test]
+    fn test_derive_24_words_with_passphrase() {
+        let mnemonic = Mnemonic::from_phrase(PHRASE_24, Language::English).unwrap();
+        let expected = [
+            (
+                "GDAHPZ2NSYIIHZXM56Y36SBVTV5QKFIZGYMMBHOU53ETUSWTP62B63EQ",
+                "SAFWTGXVS7ELMNCXELFWCFZOPMHUZ5LXNBGUVRCY3FHLFPXK4QPXYP2X",
+            ),
+            (
+                "GDY47CJARRHHL66JH3RJURDYXAMIQ5DMXZLP3TDAUJ6IN2GUOFX4OJOC",
+                "SBQPDFUGLMWJYEYXFRM5TQX3AX2BR47WKI4FDS7EJQUSEUUVY72MZPJF",
+            ),
+            (
+                "GCLAQF5H5LGJ2A6ACOMNEHSWYDJ3VKVBUBHDWFGRBEPAVZ56L4D7JJID",
+                "SAF2LXRW6FOSVQNC4HHIIDURZL4SCGCG7UEGG23ZQG6Q2DKIGMPZV6BZ",
+            ),
+            (
+                "GBC36J4KG7ZSIQ5UOSJFQNUP4IBRN6LVUFAHQWT2ODEQ7Y3ASWC5ZN3B",
+                "SDCCVBIYZDMXOR4VPC3IYMIPODNEDZCS44LDN7B5ZWECIE57N3BTV4GQ",
+            ),
+            (
+                "GA6NHA4KPH5LFYD6LZH35SIX3DU5CWU3GX6GCKPJPPTQCCQPP627E3CB",
+                "SA5TRXTO7BG2Z6QTQT3O2LC7A7DLZZ2RBTGUNCTG346PLVSSHXPNDVNT",
+            ),
+            (
+                "GBOWMXTLABFNEWO34UJNSJJNVEF6ESLCNNS36S5SX46UZT2MNYJOLA5L",
+                "SDEOED2KPHV355YNOLLDLVQB7HDPQVIGKXCAJMA3HTM4325ZHFZSKKUC",
+            ),
+            (
+                "GBL3F5JUZN3SQKZ7SL4XSXEJI2SNSVGO6WZWNJLG666WOJHNDDLEXTSZ",
+                "SDYNO6TLFNV3IM6THLNGUG5FII4ET2H7NH3KCT6OAHIUSHKR4XBEEI6A",
+            ),
+            (
+                "GA5XPPWXL22HFFL5K5CE37CEPUHXYGSP3NNWGM6IK6K4C3EFHZFKSAND",
+                "SDXMJXAY45W3WEFWMYEPLPIF4CXAD5ECQ37XKMGY5EKLM472SSRJXCYD",
+            ),
+            (
+                "GDS5I7L7LWFUVSYVAOHXJET2565MGGHJ4VHGVJXIKVKNO5D4JWXIZ3XU",
+                "SAIZA26BUP55TDCJ4U7I2MSQEAJDPDSZSBKBPWQTD5OQZQSJAGNN2IQB",
+            ),
+            (
+                "GBOSMFQYKWFDHJWCMCZSMGUMWCZOM4KFMXXS64INDHVCJ2A2JAABCYRR",
+                "SDXDYPDNRMGOF25AWYYKPHFAD3M54IT7LCLG7RWTGR3TS32A4HTUXNOS",
+            ),
+        ];
+        for (index, &(account_id, secret_seed)) in expected.iter().enumerate() {
+            let keypair = mnemonic.keypair("p4ssphr4se", index as u32).unwrap();
+            assert_eq!(keypair.account_id().unwrap(), account_id);
+            assert_eq!(keypair.secret_seed().unwrap(), secret_seed);
+        }
+    }
+
+    #[test]
+    fn test_from_entropy() {
+        let mnemonic = Mnemonic::from_entropy(&[0; 16], Language::English).unwrap();
+        assert!(mnemonic.phrase().starts_with("abandon abandon"));
+        assert!(mnemonic.phrase().ends_with("about"));
+        let keypair = mnemonic.keypair("", 0).unwrap();
+        assert_eq!(
+            keypair.account_id().unwrap(),
+            "GB3JDWCQJCWMJ3IILWIGDTQJJC5567PGVEVXSCVPEQOTDN64VJBDQBYX"
+        );
+        assert!(Mnemonic::from_entropy(&[0; 15], Language::English).is_err());
+    }
+
+    #[test]
+    fn test_random() {
+        let mnemonic = Mnemonic::random(MnemonicType::Words24, Language::English);
+        assert_eq!(mnemonic.phrase().split_whitespace().count(), 24);
+        let restored = Mnemonic::from_phrase(mnemonic.phrase(), Language::English).unwrap();
+        assert_eq!(
+            restored.keypair("", 0).unwrap(),
+            mnemonic.keypair("", 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_invalid_phrase() {
+        let bad_checksum = PHRASE_12.replace("toe", "abandon");
+        assert!(Mnemonic::from_phrase(&bad_checksum, Language::English).is_err());
+        assert!(Mnemonic::from_phrase("illness spike retreat", Language::English).is_err());
+        assert!(Mnemonic::from_phrase("not a bip39 phrase", Language::English).is_err());
+    }
+
+    #[test]
+    fn test_invalid_path() {
+        let seed = Mnemonic::from_phrase(PHRASE_12, Language::English)
+            .unwrap()
+            .seed("");
+        assert!(seed.derive(&[44, 148, 0x8000_0000]).is_err());
+        assert!(seed.keypair(0x8000_0000).is_err());
+    }
+
+    #[test]
+    fn test_debug_redacted() {
+        let mnemonic = Mnemonic::from_phrase(PHRASE_12, Language::English).unwrap();
+        assert_eq!(format!("{:?}", mnemonic), "Mnemonic(<redacted>)");
+        assert_eq!(format!("{:?}", mnemonic.seed("")), "Seed(<redacted>)");
+    }
+}
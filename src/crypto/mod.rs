@@ -1,33 +1,53 @@
-//! Libsodium wrappers.
-use sodiumoxide;
-use sodiumoxide::randombytes;
-use sodiumoxide::crypto::hash::sha256;
+//! Cryptographic primitives.
+//!
+//! The primitives are implemented by libsodium, or in pure Rust when the
+//! `pure-rust` feature is enabled.
 
 /// keypair module
 pub mod keypair;
 /// strkey module
 pub mod strkey;
+/// encryption module
+pub mod encryption;
+/// keystore module
+pub mod keystore;
+/// message module
+pub mod message;
+/// mnemonic module
+pub mod mnemonic;
 mod ecdh;
 mod sha;
+// `pure-rust` wins when both features are enabled, features must stay additive.
+#[cfg(all(feature = "sodium", not(feature = "pure-rust")))]
+mod sodium;
+#[cfg(feature = "pure-rust")]
+mod rust;
 
-pub use self::ecdh::{Curve25519Public, Curve25519Secret};
+#[cfg(all(feature = "sodium", not(feature = "pure-rust")))]
+use self::sodium as backend;
+#[cfg(feature = "pure-rust")]
+use self::rust as backend;
+use error::Result;
+
+pub use self::ecdh::{shared_key, Curve25519Public, Curve25519Secret};
 pub use self::sha::{HmacSha256Key, HmacSha256Mac};
 
 /// Compute sha256 hash of `m`.
 pub fn hash(m: &[u8]) -> Vec<u8> {
-    let digest = sha256::hash(&m);
-    digest.0.to_vec()
+    backend::sha256(m).to_vec()
 }
 
 /// Generate `size` random bytes.
 pub fn random_bytes(size: usize) -> Vec<u8> {
-    randombytes::randombytes(size)
+    let mut bytes = vec![0; size];
+    backend::fill_random(&mut bytes);
+    bytes
 }
 
-/// Initialize the sodium library and chooses faster version of the primitives
-/// if possible.
+/// Initialize the crypto library.
 ///
-/// `init` also makes `KeyPair::random()` thread-safe.
-pub fn init() -> () {
-    sodiumoxide::init().unwrap()
+/// Calling `init` is optional: the library is initialized on first use. It
+/// returns an error if libsodium can not be initialized.
+pub fn init() -> Result<()> {
+    backend::init()
 }
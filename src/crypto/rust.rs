@@ -0,0 +1,117 @@
+//! Primitives implemented in pure Rust, compatible with the libsodium ones.
+use crypto_box;
+use crypto_box::aead::{Aead, OsRng};
+use crypto_secretbox::{KeyInit, XSalsa20Poly1305};
+use getrandom;
+use hmac::{Hmac, Mac};
+use scrypt as scrypt_kdf;
+use sha2::{Digest, Sha256, Sha512};
+use error::{Error, Result};
+
+/// Nothing to initialize.
+pub fn init() -> Result<()> {
+    Ok(())
+}
+
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+pub fn hmac_sha256(key: &[u8; 32], data: &[u8]) -> [u8; 32] {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key).expect("hmac accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
+pub fn hmac_sha512(key: &[u8], data: &[u8]) -> [u8; 64] {
+    let mut mac = <Hmac<Sha512> as Mac>::new_from_slice(key).expect("hmac accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
+/// Fill `buf` with random bytes from the operating system.
+///
+/// Panics if the operating system has no source of randomness, like libsodium aborts.
+pub fn fill_random(buf: &mut [u8]) {
+    getrandom::getrandom(buf).expect("no source of randomness available");
+}
+
+pub fn box_seal(message: &[u8], nonce: &[u8; 24], public: &[u8; 32], secret: &[u8; 32]) -> Vec<u8> {
+    salsa_box(public, secret)
+        .encrypt(nonce.into(), message)
+        .expect("encryption of in memory data can not fail")
+}
+
+pub fn box_open(
+    ciphertext: &[u8],
+    nonce: &[u8; 24],
+    public: &[u8; 32],
+    secret: &[u8; 32],
+) -> Result<Vec<u8>> {
+    salsa_box(public, secret)
+        .decrypt(nonce.into(), ciphertext)
+        .map_err(|_| Error::InvalidCiphertext)
+}
+
+pub fn sealed_box_seal(message: &[u8], public: &[u8; 32]) -> Vec<u8> {
+    crypto_box::PublicKey::from(*public)
+        .seal(&mut OsRng, message)
+        .expect("encryption of in memory data can not fail")
+}
+
+pub fn sealed_box_open(ciphertext: &[u8], _public: &[u8; 32], secret: &[u8; 32]) -> Result<Vec<u8>> {
+    crypto_box::SecretKey::from(*secret)
+        .unseal(ciphertext)
+        .map_err(|_| Error::InvalidCiphertext)
+}
+
+pub fn secretbox_seal(message: &[u8], nonce: &[u8; 24], key: &[u8; 32]) -> Vec<u8> {
+    XSalsa20Poly1305::new(key.into())
+        .encrypt(nonce.into(), message)
+        .expect("encryption of in memory data can not fail")
+}
+
+pub fn secretbox_open(ciphertext: &[u8], nonce: &[u8; 24], key: &[u8; 32]) -> Result<Vec<u8>> {
+    XSalsa20Poly1305::new(key.into())
+        .decrypt(nonce.into(), ciphertext)
+        .map_err(|_| Error::InvalidCiphertext)
+}
+
+/// Derive the key with scrypt, choosing the parameters from the limits like libsodium.
+pub fn scrypt(
+    password: &[u8],
+    salt: &[u8; 32],
+    opslimit: usize,
+    memlimit: usize,
+    out: &mut [u8],
+) -> Result<()> {
+    let (log_n, r, p) = pick_params(opslimit as u64, memlimit as u64);
+    let params = scrypt_kdf::Params::new(log_n, r, p, out.len()).map_err(|_| Error::InvalidKeystore)?;
+    scrypt_kdf::scrypt(password, salt, &params, out).map_err(|_| Error::InvalidKeystore)
+}
+
+fn salsa_box(public: &[u8; 32], secret: &[u8; 32]) -> crypto_box::SalsaBox {
+    let public = crypto_box::PublicKey::from(*public);
+    let secret = crypto_box::SecretKey::from(*secret);
+    crypto_box::SalsaBox::new(&public, &secret)
+}
+
+/// Port of `pickparams` from libsodium `crypto_pwhash_scryptsalsa208sha256`.
+fn pick_params(opslimit: u64, memlimit: u64) -> (u8, u32, u32) {
+    let opslimit = opslimit.max(32_768);
+    let r: u32 = 8;
+    let max_n = if opslimit < memlimit / 32 {
+        opslimit / (u64::from(r) * 4)
+    } else {
+        memlimit / (u64::from(r) * 128)
+    };
+    let mut log_n = 1;
+    while log_n < 63 && (1u64 << log_n) <= max_n / 2 {
+        log_n += 1;
+    }
+    if opslimit < memlimit / 32 {
+        return (log_n, r, 1);
+    }
+    let max_rp = ((opslimit / 4) / (1u64 << log_n)).min(0x3fff_ffff);
+    (log_n, r, (max_rp as u32) / r)
+}
@@ -1,7 +1,10 @@
-use sodiumoxide::crypto::auth::hmacsha256;
+use std::fmt;
+use crypto::backend;
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
 
 /// HMAC key
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Clone)]
 pub struct HmacSha256Key(pub [u8; 32]);
 
 impl HmacSha256Key {
@@ -23,12 +26,33 @@ impl HmacSha256Key {
         bytes.extend_from_slice(&data);
         bytes.push(1);
         let mac = HmacSha256Mac::authenticate(&bytes, &key);
+        bytes.zeroize();
         HmacSha256Key(mac.0)
     }
 }
 
+impl PartialEq for HmacSha256Key {
+    fn eq(&self, other: &HmacSha256Key) -> bool {
+        self.0.ct_eq(&other.0).into()
+    }
+}
+
+impl Eq for HmacSha256Key {}
+
+impl fmt::Debug for HmacSha256Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HmacSha256Key(<redacted>)")
+    }
+}
+
+impl Drop for HmacSha256Key {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
 /// HMAC mac.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct HmacSha256Mac(pub [u8; 32]);
 
 impl HmacSha256Mac {
@@ -39,8 +63,70 @@ impl HmacSha256Mac {
 
     /// Authenticate the message with the key.
     pub fn authenticate(data: &[u8], key: &HmacSha256Key) -> HmacSha256Mac {
-        let hkey = hmacsha256::Key(key.0);
-        let tag = hmacsha256::authenticate(&data, &hkey);
-        HmacSha256Mac(tag.0)
+        HmacSha256Mac(backend::hmac_sha256(&key.0, data))
+    }
+
+    /// Verify, in constant time, that the mac authenticates the message with the key.
+    pub fn verify(&self, data: &[u8], key: &HmacSha256Key) -> bool {
+        *self == HmacSha256Mac::authenticate(data, key)
+    }
+}
+
+impl PartialEq for HmacSha256Mac {
+    fn eq(&self, other: &HmacSha256Mac) -> bool {
+        self.0.ct_eq(&other.0).into()
+    }
+}
+
+impl Eq for HmacSha256Mac {}
+
+/// Compute `HMAC-SHA512(key, data)`, with a key of any length.
+pub fn hmac_sha512(key: &[u8], data: &[u8]) -> [u8; 64] {
+    backend::hmac_sha512(key, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hmac_sha512, HmacSha256Key, HmacSha256Mac};
+    use crypto;
+
+    #[test]
+    fn test_mac_verify() {
+        let key = HmacSha256Key::hkdf_extract(b"some key material");
+        let mac = HmacSha256Mac::authenticate(b"message", &key);
+        assert!(mac.verify(b"message", &key));
+        assert!(!mac.verify(b"other message", &key));
+        assert!(!HmacSha256Mac::zero().verify(b"message", &key));
+    }
+
+    #[test]
+    fn test_sha256() {
+        let expected = [
+            0xBA, 0x78, 0x16, 0xBF, 0x8F, 0x01, 0xCF, 0xEA, 0x41, 0x41, 0x40, 0xDE, 0x5D, 0xAE,
+            0x22, 0x23, 0xB0, 0x03, 0x61, 0xA3, 0x96, 0x17, 0x7A, 0x9C, 0xB4, 0x10, 0xFF, 0x61,
+            0xF2, 0x00, 0x15, 0xAD,
+        ];
+        assert_eq!(crypto::hash(b"abc"), expected.to_vec());
+    }
+
+    #[test]
+    fn test_hmac_sha512() {
+        // RFC 4231, test case 2
+        let expected = [
+            0x16, 0x4B, 0x7A, 0x7B, 0xFC, 0xF8, 0x19, 0xE2, 0xE3, 0x95, 0xFB, 0xE7, 0x3B, 0x56,
+            0xE0, 0xA3, 0x87, 0xBD, 0x64, 0x22, 0x2E, 0x83, 0x1F, 0xD6, 0x10, 0x27, 0x0C, 0xD7,
+            0xEA, 0x25, 0x05, 0x54, 0x97, 0x58, 0xBF, 0x75, 0xC0, 0x5A, 0x99, 0x4A, 0x6D, 0x03,
+            0x4F, 0x65, 0xF8, 0xF0, 0xE6, 0xFD, 0xCA, 0xEA, 0xB1, 0xA3, 0x4D, 0x4A, 0x6B, 0x4B,
+            0x63, 0x6E, 0x07, 0x0A, 0x38, 0xBC, 0xE7, 0x37,
+        ];
+        let mac = hmac_sha512(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(&mac[..], &expected[..]);
+    }
+
+    #[test]
+    fn test_key_debug_redacted() {
+        let key = HmacSha256Key([0xAB; 32]);
+        let debug = format!("{:?}", key);
+        assert_eq!(debug, "HmacSha256Key(<redacted>)");
     }
 }
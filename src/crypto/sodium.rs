@@ -0,0 +1,107 @@
+//! Primitives implemented by libsodium.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+use sodiumoxide;
+use sodiumoxide::crypto::auth::{hmacsha256, hmacsha512};
+use sodiumoxide::crypto::box_;
+use sodiumoxide::crypto::hash::sha256;
+use sodiumoxide::crypto::pwhash::scryptsalsa208sha256 as scrypt;
+use sodiumoxide::crypto::sealedbox;
+use sodiumoxide::crypto::secretbox;
+use sodiumoxide::randombytes;
+use error::{Error, Result};
+
+static INIT: Once = Once::new();
+static INIT_OK: AtomicBool = AtomicBool::new(false);
+
+/// Initialize libsodium, once.
+///
+/// Called by every primitive, so that libsodium is always initialized before use.
+pub fn init() -> Result<()> {
+    INIT.call_once(|| INIT_OK.store(sodiumoxide::init().is_ok(), Ordering::SeqCst));
+    if INIT_OK.load(Ordering::SeqCst) {
+        Ok(())
+    } else {
+        Err(Error::InitError)
+    }
+}
+
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    let _ = init();
+    sha256::hash(data).0
+}
+
+pub fn hmac_sha256(key: &[u8; 32], data: &[u8]) -> [u8; 32] {
+    let _ = init();
+    hmacsha256::authenticate(data, &hmacsha256::Key(*key)).0
+}
+
+pub fn hmac_sha512(key: &[u8], data: &[u8]) -> [u8; 64] {
+    let _ = init();
+    let mut state = hmacsha512::State::init(key);
+    state.update(data);
+    state.finalize().0
+}
+
+pub fn fill_random(buf: &mut [u8]) {
+    let _ = init();
+    randombytes::randombytes_into(buf);
+}
+
+pub fn box_seal(message: &[u8], nonce: &[u8; 24], public: &[u8; 32], secret: &[u8; 32]) -> Vec<u8> {
+    let _ = init();
+    let sk = box_::SecretKey(*secret);
+    box_::seal(message, &box_::Nonce(*nonce), &box_::PublicKey(*public), &sk)
+}
+
+pub fn box_open(
+    ciphertext: &[u8],
+    nonce: &[u8; 24],
+    public: &[u8; 32],
+    secret: &[u8; 32],
+) -> Result<Vec<u8>> {
+    let _ = init();
+    let sk = box_::SecretKey(*secret);
+    box_::open(ciphertext, &box_::Nonce(*nonce), &box_::PublicKey(*public), &sk)
+        .map_err(|_| Error::InvalidCiphertext)
+}
+
+pub fn sealed_box_seal(message: &[u8], public: &[u8; 32]) -> Vec<u8> {
+    let _ = init();
+    sealedbox::seal(message, &box_::PublicKey(*public))
+}
+
+pub fn sealed_box_open(ciphertext: &[u8], public: &[u8; 32], secret: &[u8; 32]) -> Result<Vec<u8>> {
+    let _ = init();
+    let sk = box_::SecretKey(*secret);
+    sealedbox::open(ciphertext, &box_::PublicKey(*public), &sk).map_err(|_| Error::InvalidCiphertext)
+}
+
+pub fn secretbox_seal(message: &[u8], nonce: &[u8; 24], key: &[u8; 32]) -> Vec<u8> {
+    let _ = init();
+    secretbox::seal(message, &secretbox::Nonce(*nonce), &secretbox::Key(*key))
+}
+
+pub fn secretbox_open(ciphertext: &[u8], nonce: &[u8; 24], key: &[u8; 32]) -> Result<Vec<u8>> {
+    let _ = init();
+    secretbox::open(ciphertext, &secretbox::Nonce(*nonce), &secretbox::Key(*key))
+        .map_err(|_| Error::InvalidCiphertext)
+}
+
+pub fn scrypt(
+    password: &[u8],
+    salt: &[u8; 32],
+    opslimit: usize,
+    memlimit: usize,
+    out: &mut [u8],
+) -> Result<()> {
+    let _ = init();
+    scrypt::derive_key(
+        out,
+        password,
+        &scrypt::Salt(*salt),
+        scrypt::OpsLimit(opslimit),
+        scrypt::MemLimit(memlimit),
+    ).map(|_| ())
+        .map_err(|_| Error::InvalidKeystore)
+}
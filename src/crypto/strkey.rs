@@ -1,14 +1,31 @@
 #![allow(dead_code)]
 
-use byteorder::{ByteOrder, LittleEndian};
+use std::fmt;
+use std::str::FromStr;
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
 use base32;
 use crc16::{State, XMODEM};
 use error::{Error, Result};
+use zeroize::Zeroize;
 
 const ACCOUNT_ID_VERSION_BYTE: u8 = 6 << 3; // G
 const SECRET_SEED_VERSION_BYTE: u8 = 18 << 3; // S
 const PRE_AUTH_TX_VERSION_BYTE: u8 = 19 << 3; // T
 const SHA256_HASH_VERSION_BYTE: u8 = 23 << 3; // X
+const MUXED_ACCOUNT_VERSION_BYTE: u8 = 12 << 3; // M
+const SIGNED_PAYLOAD_VERSION_BYTE: u8 = 15 << 3; // P
+const CONTRACT_VERSION_BYTE: u8 = 2 << 3; // C
+const CLAIMABLE_BALANCE_VERSION_BYTE: u8 = 1 << 3; // B
+const LIQUIDITY_POOL_VERSION_BYTE: u8 = 11 << 3; // L
+
+/// Length of keys and hashes.
+const KEY_LEN: usize = 32;
+/// Maximum length of an encoded key, a signed payload with a 64 bytes payload.
+const MAX_ENCODED_LEN: usize = 165;
+/// Maximum length of the payload of a signed payload signer.
+const MAX_SIGNED_PAYLOAD_LEN: usize = 64;
+/// Type of a claimable balance id, only `V0` exists.
+const CLAIMABLE_BALANCE_ID_TYPE_V0: u8 = 0;
 
 static ALPHABET: base32::Alphabet = base32::Alphabet::RFC4648 { padding: false };
 
@@ -18,7 +35,7 @@ pub fn encode_account_id(data: &[u8]) -> Result<String> {
 }
 /// decode_account_id
 pub fn decode_account_id(data: &str) -> Result<Vec<u8>> {
-    decode_check(ACCOUNT_ID_VERSION_BYTE, data)
+    decode_check(ACCOUNT_ID_VERSION_BYTE, KEY_LEN, data)
 }
 /// encode_secret_seed
 pub fn encode_secret_seed(data: &[u8]) -> Result<String> {
@@ -26,7 +43,7 @@ pub fn encode_secret_seed(data: &[u8]) -> Result<String> {
 }
 /// decode_secret_seed
 pub fn decode_secret_seed(data: &str) -> Result<Vec<u8>> {
-    decode_check(SECRET_SEED_VERSION_BYTE, data)
+    decode_check(SECRET_SEED_VERSION_BYTE, KEY_LEN, data)
 }
 /// encode_pre_auth_tx
 pub fn encode_pre_auth_tx(data: &[u8]) -> Result<String> {
@@ -34,7 +51,7 @@ pub fn encode_pre_auth_tx(data: &[u8]) -> Result<String> {
 }
 /// decode_pre_auth_tx
 pub fn decode_pre_auth_tx(data: &str) -> Result<Vec<u8>> {
-    decode_check(PRE_AUTH_TX_VERSION_BYTE, data)
+    decode_check(PRE_AUTH_TX_VERSION_BYTE, KEY_LEN, data)
 }
 /// encode_sha256_hash
 pub fn encode_sha256_hash(data: &[u8]) -> Result<String> {
@@ -42,39 +59,342 @@ pub fn encode_sha256_hash(data: &[u8]) -> Result<String> {
 }
 /// decode_sha256_hash
 pub fn decode_sha256_hash(data: &str) -> Result<Vec<u8>> {
-    decode_check(SHA256_HASH_VERSION_BYTE, data)
+    decode_check(SHA256_HASH_VERSION_BYTE, KEY_LEN, data)
+}
+
+/// The kind of a `StrKey`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StrKeyKind {
+    /// Account id, starting with `G`.
+    AccountId,
+    /// Muxed account, starting with `M`.
+    MuxedAccount,
+    /// Secret seed, starting with `S`.
+    SecretSeed,
+    /// Pre-authorized transaction hash, starting with `T`.
+    PreAuthTx,
+    /// Hash-x signer, starting with `X`.
+    Sha256Hash,
+    /// Signed payload signer, starting with `P`.
+    SignedPayload,
+    /// Contract id, starting with `C`.
+    Contract,
+    /// Claimable balance id, starting with `B`.
+    ClaimableBalance,
+    /// Liquidity pool id, starting with `L`.
+    LiquidityPool,
+}
+
+impl StrKeyKind {
+    fn from_version_byte(version: u8) -> Result<StrKeyKind> {
+        match version {
+            ACCOUNT_ID_VERSION_BYTE => Ok(StrKeyKind::AccountId),
+            MUXED_ACCOUNT_VERSION_BYTE => Ok(StrKeyKind::MuxedAccount),
+            SECRET_SEED_VERSION_BYTE => Ok(StrKeyKind::SecretSeed),
+            PRE_AUTH_TX_VERSION_BYTE => Ok(StrKeyKind::PreAuthTx),
+            SHA256_HASH_VERSION_BYTE => Ok(StrKeyKind::Sha256Hash),
+            SIGNED_PAYLOAD_VERSION_BYTE => Ok(StrKeyKind::SignedPayload),
+            CONTRACT_VERSION_BYTE => Ok(StrKeyKind::Contract),
+            CLAIMABLE_BALANCE_VERSION_BYTE => Ok(StrKeyKind::ClaimableBalance),
+            LIQUIDITY_POOL_VERSION_BYTE => Ok(StrKeyKind::LiquidityPool),
+            _ => Err(Error::InvalidStrKeyVersionByte),
+        }
+    }
+
+    fn version_byte(&self) -> u8 {
+        match *self {
+            StrKeyKind::AccountId => ACCOUNT_ID_VERSION_BYTE,
+            StrKeyKind::MuxedAccount => MUXED_ACCOUNT_VERSION_BYTE,
+            StrKeyKind::SecretSeed => SECRET_SEED_VERSION_BYTE,
+            StrKeyKind::PreAuthTx => PRE_AUTH_TX_VERSION_BYTE,
+            StrKeyKind::Sha256Hash => SHA256_HASH_VERSION_BYTE,
+            StrKeyKind::SignedPayload => SIGNED_PAYLOAD_VERSION_BYTE,
+            StrKeyKind::Contract => CONTRACT_VERSION_BYTE,
+            StrKeyKind::ClaimableBalance => CLAIMABLE_BALANCE_VERSION_BYTE,
+            StrKeyKind::LiquidityPool => LIQUIDITY_POOL_VERSION_BYTE,
+        }
+    }
+}
+
+impl fmt::Display for StrKeyKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            StrKeyKind::AccountId => "account id",
+            StrKeyKind::MuxedAccount => "muxed account",
+            StrKeyKind::SecretSeed => "secret seed",
+            StrKeyKind::PreAuthTx => "pre-authorized transaction",
+            StrKeyKind::Sha256Hash => "sha256 hash",
+            StrKeyKind::SignedPayload => "signed payload",
+            StrKeyKind::Contract => "contract",
+            StrKeyKind::ClaimableBalance => "claimable balance",
+            StrKeyKind::LiquidityPool => "liquidity pool",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Any key or id encoded as a string, e.g. `GB3KJPLFUYN5VL6R3GU3EGCGVCKFDSD7BEDX42HWG5BWFKB3KQGJJRMA`.
+///
+/// `Display` encodes the key, `FromStr` decodes it. `Debug` never shows secret seeds.
+#[derive(Clone, PartialEq, Eq)]
+pub enum StrKey {
+    /// Ed25519 public key of an account.
+    AccountId([u8; 32]),
+    /// Ed25519 public key of an account, with a multiplexing id.
+    MuxedAccount {
+        /// The account public key.
+        key: [u8; 32],
+        /// The multiplexing id.
+        id: u64,
+    },
+    /// Ed25519 secret seed.
+    SecretSeed([u8; 32]),
+    /// Hash of a pre-authorized transaction.
+    PreAuthTx([u8; 32]),
+    /// Hash of the preimage of a hash-x signer.
+    Sha256Hash([u8; 32]),
+    /// Ed25519 public key together with the payload it must sign.
+    SignedPayload(SignedPayload),
+    /// Hash of a contract.
+    Contract([u8; 32]),
+    /// Hash of a claimable balance, version 0.
+    ClaimableBalance([u8; 32]),
+    /// Hash of a liquidity pool.
+    LiquidityPool([u8; 32]),
+}
+
+/// An ed25519 public key together with the payload, 1 to 64 bytes, it must sign.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedPayload {
+    key: [u8; 32],
+    payload: Vec<u8>,
+}
+
+impl SignedPayload {
+    /// Create a signed payload, or fail if `payload` is empty or longer than 64 bytes.
+    pub fn new(key: [u8; 32], payload: Vec<u8>) -> Result<SignedPayload> {
+        if payload.is_empty() || payload.len() > MAX_SIGNED_PAYLOAD_LEN {
+            return Err(Error::InvalidStrKeyLength);
+        }
+        Ok(SignedPayload { key, payload })
+    }
+
+    /// Return the signer public key.
+    pub fn key(&self) -> &[u8; 32] {
+        &self.key
+    }
+
+    /// Return the payload.
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+}
+
+impl StrKey {
+    /// Decode `data`, detecting the kind of key from its version byte.
+    pub fn decode(data: &str) -> Result<StrKey> {
+        let (version, mut payload) = decode_any(data)?;
+        let key = StrKey::from_payload(StrKeyKind::from_version_byte(version)?, &payload);
+        payload.zeroize();
+        key
+    }
+
+    /// Encode the key.
+    pub fn encode(&self) -> String {
+        let mut payload = self.to_payload();
+        let encoded = encode_check(self.kind().version_byte(), &payload);
+        payload.zeroize();
+        encoded.expect("encoding never fails")
+    }
+
+    /// Return the kind of key.
+    pub fn kind(&self) -> StrKeyKind {
+        match *self {
+            StrKey::AccountId(_) => StrKeyKind::AccountId,
+            StrKey::MuxedAccount { .. } => StrKeyKind::MuxedAccount,
+            StrKey::SecretSeed(_) => StrKeyKind::SecretSeed,
+            StrKey::PreAuthTx(_) => StrKeyKind::PreAuthTx,
+            StrKey::Sha256Hash(_) => StrKeyKind::Sha256Hash,
+            StrKey::SignedPayload(_) => StrKeyKind::SignedPayload,
+            StrKey::Contract(_) => StrKeyKind::Contract,
+            StrKey::ClaimableBalance(_) => StrKeyKind::ClaimableBalance,
+            StrKey::LiquidityPool(_) => StrKeyKind::LiquidityPool,
+        }
+    }
+
+    fn from_payload(kind: StrKeyKind, payload: &[u8]) -> Result<StrKey> {
+        match kind {
+            StrKeyKind::AccountId => Ok(StrKey::AccountId(to_array(payload)?)),
+            StrKeyKind::SecretSeed => Ok(StrKey::SecretSeed(to_array(payload)?)),
+            StrKeyKind::PreAuthTx => Ok(StrKey::PreAuthTx(to_array(payload)?)),
+            StrKeyKind::Sha256Hash => Ok(StrKey::Sha256Hash(to_array(payload)?)),
+            StrKeyKind::Contract => Ok(StrKey::Contract(to_array(payload)?)),
+            StrKeyKind::LiquidityPool => Ok(StrKey::LiquidityPool(to_array(payload)?)),
+            StrKeyKind::MuxedAccount => {
+                if payload.len() != 40 {
+                    return Err(Error::InvalidStrKeyLength);
+                }
+                Ok(StrKey::MuxedAccount {
+                    key: to_array(&payload[..32])?,
+                    id: BigEndian::read_u64(&payload[32..]),
+                })
+            }
+            StrKeyKind::SignedPayload => {
+                if payload.len() < 36 {
+                    return Err(Error::InvalidStrKeyLength);
+                }
+                let len = BigEndian::read_u32(&payload[32..36]) as usize;
+                let padded_len = len.div_ceil(4) * 4;
+                if len == 0 || len > MAX_SIGNED_PAYLOAD_LEN || payload.len() != 36 + padded_len {
+                    return Err(Error::InvalidStrKeyLength);
+                }
+                if payload[36 + len..].iter().any(|b| *b != 0) {
+                    return Err(Error::InvalidStrKey);
+                }
+                Ok(StrKey::SignedPayload(SignedPayload {
+                    key: to_array(&payload[..32])?,
+                    payload: payload[36..36 + len].to_vec(),
+                }))
+            }
+            StrKeyKind::ClaimableBalance => {
+                if payload.len() != 33 {
+                    return Err(Error::InvalidStrKeyLength);
+                }
+                if payload[0] != CLAIMABLE_BALANCE_ID_TYPE_V0 {
+                    return Err(Error::InvalidStrKey);
+                }
+                Ok(StrKey::ClaimableBalance(to_array(&payload[1..])?))
+            }
+        }
+    }
+
+    fn to_payload(&self) -> Vec<u8> {
+        match *self {
+            StrKey::AccountId(ref key)
+            | StrKey::SecretSeed(ref key)
+            | StrKey::PreAuthTx(ref key)
+            | StrKey::Sha256Hash(ref key)
+            | StrKey::Contract(ref key)
+            | StrKey::LiquidityPool(ref key) => key.to_vec(),
+            StrKey::MuxedAccount { ref key, id } => {
+                let mut payload = key.to_vec();
+                payload.resize(40, 0);
+                BigEndian::write_u64(&mut payload[32..], id);
+                payload
+            }
+            StrKey::SignedPayload(ref signed) => {
+                let mut data = signed.key.to_vec();
+                data.resize(36, 0);
+                BigEndian::write_u32(&mut data[32..], signed.payload.len() as u32);
+                data.extend_from_slice(&signed.payload);
+                let padded_len = data.len().div_ceil(4) * 4;
+                data.resize(padded_len, 0);
+                data
+            }
+            StrKey::ClaimableBalance(ref hash) => {
+                let mut payload = vec![CLAIMABLE_BALANCE_ID_TYPE_V0];
+                payload.extend_from_slice(hash);
+                payload
+            }
+        }
+    }
+}
+
+impl fmt::Display for StrKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+impl FromStr for StrKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<StrKey> {
+        StrKey::decode(s)
+    }
+}
+
+impl fmt::Debug for StrKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StrKey::SecretSeed(_) => write!(f, "SecretSeed(<redacted>)"),
+            _ => write!(f, "{:?}({})", self.kind(), self.encode()),
+        }
+    }
+}
+
+impl Drop for StrKey {
+    fn drop(&mut self) {
+        if let StrKey::SecretSeed(ref mut seed) = *self {
+            seed.zeroize();
+        }
+    }
+}
+
+fn to_array(data: &[u8]) -> Result<[u8; 32]> {
+    if data.len() != KEY_LEN {
+        return Err(Error::InvalidStrKeyLength);
+    }
+    let mut array = [0; 32];
+    array.copy_from_slice(data);
+    Ok(array)
+}
+
+/// Decode `data`, returning the version byte and the payload.
+///
+/// Only the canonical encoding is accepted: uppercase, without padding and
+/// with the unused trailing bits set to zero.
+fn decode_any(data: &str) -> Result<(u8, Vec<u8>)> {
+    if data.len() > MAX_ENCODED_LEN {
+        return Err(Error::InvalidStrKeyLength);
+    }
+    let mut decoded = base32::decode(ALPHABET, data).ok_or(Error::InvalidStrKey)?;
+    let result = check_decoded(data, &decoded);
+    decoded.zeroize();
+    result
+}
+
+fn check_decoded(data: &str, decoded: &[u8]) -> Result<(u8, Vec<u8>)> {
+    let mut encoded = base32::encode(ALPHABET, decoded);
+    let canonical = encoded == data;
+    encoded.zeroize();
+    if !canonical {
+        return Err(Error::NonCanonicalEncoding);
+    }
+    if decoded.len() < 3 {
+        return Err(Error::InvalidStrKeyLength);
+    }
+    let (payload, checksum_bytes) = decoded.split_at(decoded.len() - 2);
+    if !verify_checksum(calculate_checksum(payload), checksum_bytes) {
+        return Err(Error::InvalidStrKeyChecksum);
+    }
+    Ok((payload[0], payload[1..].to_vec()))
 }
 
 fn encode_check(version: u8, indata: &[u8]) -> Result<String> {
     let mut data = Vec::with_capacity(35);
     data.push(version);
-    data.extend_from_slice(&indata);
+    data.extend_from_slice(indata);
     let checksum = calculate_checksum(&data);
     let data_end = data.len();
     data.resize(data_end + 2, 0);
     LittleEndian::write_u16(&mut data[data_end..], checksum);
-    Ok(base32::encode(ALPHABET, &data))
+    let encoded = base32::encode(ALPHABET, &data);
+    data.zeroize();
+    Ok(encoded)
 }
 
-fn decode_check(expected_version: u8, data: &str) -> Result<Vec<u8>> {
-    let decoded = base32::decode(ALPHABET, &data).ok_or(Error::InvalidStrKey)?;
-    let decoded_len = decoded.len();
-    let version_byte = decoded[0];
-    if version_byte != expected_version {
+fn decode_check(expected_version: u8, expected_len: usize, data: &str) -> Result<Vec<u8>> {
+    let (version, mut payload) = decode_any(data)?;
+    if version != expected_version {
+        payload.zeroize();
         return Err(Error::InvalidStrKeyVersionByte);
     }
-
-    let payload = &decoded[..decoded_len - 2];
-    let data = &payload[1..];
-    let checksum_bytes = &decoded[decoded_len - 2..];
-    let checksum = calculate_checksum(payload);
-
-    if verify_checksum(checksum, checksum_bytes) {
-        let key = data.to_vec();
-        Ok(key)
-    } else {
-        Err(Error::InvalidStrKeyChecksum)
+    if payload.len() != expected_len {
+        payload.zeroize();
+        return Err(Error::InvalidStrKeyLength);
     }
+    Ok(payload)
 }
 
 fn calculate_checksum(payload: &[u8]) -> u16 {
@@ -90,6 +410,27 @@ fn verify_checksum(checksum: u16, bytes: &[u8]) -> bool {
 mod tests {
     use super::{decode_secret_seed, encode_secret_seed};
     use super::{decode_account_id, encode_account_id};
+    use super::{SignedPayload, StrKey, StrKeyKind};
+    use error::Error;
+
+    const ACCOUNT_ID: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+    const KEY: [u8; 32] = [
+        0x3f, 0x0c, 0x34, 0xbf, 0x93, 0xad, 0x0d, 0x99, 0x71, 0xd0, 0x4c, 0xcc, 0x90, 0xf7, 0x05,
+        0x51, 0x1c, 0x83, 0x8a, 0xad, 0x97, 0x34, 0xa4, 0xa2, 0xfb, 0x0d, 0x7a, 0x03, 0xfc, 0x7f,
+        0xe8, 0x9a,
+    ];
+    const CONTRACT: [u8; 32] = [
+        0x36, 0x3e, 0xaa, 0x38, 0x67, 0x84, 0x1f, 0xba, 0xd0, 0xf4, 0xed, 0x88, 0xc7, 0x79, 0xe4,
+        0xfe, 0x66, 0xe5, 0x6a, 0x24, 0x70, 0xdc, 0x98, 0xc0, 0xec, 0x9c, 0x07, 0x3d, 0x05, 0xc7,
+        0xb1, 0x03,
+    ];
+
+    fn assert_round_trip(encoded: &str, key: StrKey, kind: StrKeyKind) {
+        let decoded: StrKey = encoded.parse().unwrap();
+        assert_eq!(decoded, key);
+        assert_eq!(decoded.kind(), kind);
+        assert_eq!(key.to_string(), encoded);
+    }
 
     #[test]
     fn test_encode_decode_secret_seed() {
@@ -113,4 +454,165 @@ mod tests {
         let result = decode_secret_seed(&addr);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_str_key_kinds() {
+        assert_round_trip(ACCOUNT_ID, StrKey::AccountId(KEY), StrKeyKind::AccountId);
+        assert_round_trip(
+            "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE",
+            StrKey::Contract(CONTRACT),
+            StrKeyKind::Contract,
+        );
+        assert_round_trip(
+            "LA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGZ5J",
+            StrKey::LiquidityPool(CONTRACT),
+            StrKeyKind::LiquidityPool,
+        );
+        assert_round_trip(
+            "BAAD6DBUX6J22DMZOHIEZTEQ64CVCHEDRKWZONFEUL5Q26QD7R76RGR4TU",
+            StrKey::ClaimableBalance(KEY),
+            StrKeyKind::ClaimableBalance,
+        );
+        let seed = "SDJHRQF4GCMIIKAAAQ6IHY42X73FQFLHUULAPSKKD4DFDM7UXWWCRHBE";
+        let decoded = StrKey::decode(seed).unwrap();
+        assert_eq!(decoded.kind(), StrKeyKind::SecretSeed);
+        assert_eq!(decoded.to_string(), seed);
+        assert_eq!(format!("{:?}", decoded), "SecretSeed(<redacted>)");
+    }
+
+    #[test]
+    fn test_str_key_muxed_account() {
+        let cases = [
+            ("MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAAAAAAAACJUQ", 0),
+            ("MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAAAAAAE2JUG6", 1234),
+            (
+                "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVAAAAAAAAAAAAAJLK",
+                9_223_372_036_854_775_808,
+            ),
+        ];
+        for &(encoded, id) in &cases {
+            assert_round_trip(encoded, StrKey::MuxedAccount { key: KEY, id }, StrKeyKind::MuxedAccount);
+        }
+    }
+
+    #[test]
+    fn test_str_key_signed_payload() {
+        assert_round_trip(
+            "PA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAQACAQDAQCQMBYIBEFAWDANBYHRAEISCMKBKFQXDAMRUGY4DUPB6IBZGM",
+            StrKey::SignedPayload(SignedPayload::new(KEY, (1..33).collect()).unwrap()),
+            StrKeyKind::SignedPayload,
+        );
+        assert_round_trip(
+            "PA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAOQCAQDAQCQMBYIBEFAWDANBYHRAEISCMKBKFQXDAMRUGY4DUAAAAFGBU",
+            StrKey::SignedPayload(SignedPayload::new(KEY, (1..30).collect()).unwrap()),
+            StrKeyKind::SignedPayload,
+        );
+
+        let max = StrKey::SignedPayload(SignedPayload::new(KEY, vec![7; 64]).unwrap());
+        let encoded = max.to_string();
+        assert_eq!(encoded.len(), super::MAX_ENCODED_LEN);
+        assert_eq!(StrKey::decode(&encoded).unwrap(), max);
+        for len in &[0, 65] {
+            match SignedPayload::new(KEY, vec![7; *len]) {
+                Err(Error::InvalidStrKeyLength) => {}
+                other => panic!("unexpected {:?}", other),
+            }
+        }
+        // A 65 bytes payload with a valid checksum does not decode either.
+        let mut payload = KEY.to_vec();
+        payload.extend_from_slice(&[0, 0, 0, 65]);
+        payload.extend_from_slice(&[7; 68]);
+        let encoded = super::encode_check(super::SIGNED_PAYLOAD_VERSION_BYTE, &payload).unwrap();
+        assert!(StrKey::decode(&encoded).is_err());
+    }
+
+    #[test]
+    fn test_str_key_invalid() {
+        assert!(StrKey::decode("").is_err());
+        assert!(StrKey::decode("GA").is_err());
+        assert!(StrKey::decode("not a key").is_err());
+        // Bad checksum.
+        assert!(StrKey::decode("GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGY").is_err());
+        // Muxed account with a truncated id.
+        let key = StrKey::MuxedAccount { key: KEY, id: 1 };
+        let payload = key.to_payload();
+        let truncated = super::encode_check(super::MUXED_ACCOUNT_VERSION_BYTE, &payload[..39]);
+        assert!(StrKey::decode(&truncated.unwrap()).is_err());
+        // Claimable balance with an unknown type.
+        let mut payload = StrKey::ClaimableBalance(KEY).to_payload();
+        payload[0] = 1;
+        let encoded = super::encode_check(super::CLAIMABLE_BALANCE_VERSION_BYTE, &payload);
+        assert!(StrKey::decode(&encoded.unwrap()).is_err());
+        // Signed payload with non-zero padding.
+        let key = StrKey::SignedPayload(SignedPayload::new(KEY, vec![1, 2, 3]).unwrap());
+        let mut payload = key.to_payload();
+        payload[39] = 1;
+        let encoded = super::encode_check(super::SIGNED_PAYLOAD_VERSION_BYTE, &payload);
+        assert!(StrKey::decode(&encoded.unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_str_key_kind_display() {
+        let key: StrKey = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE".parse().unwrap();
+        assert_eq!(key.kind().to_string(), "contract");
+        assert_eq!(
+            format!("{:?}", key),
+            "Contract(CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE)"
+        );
+    }
+
+    #[test]
+    fn test_malformed() {
+        let muxed = "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAAAAAAAACJUQ";
+        let cases = [
+            "",
+            "G",
+            "GA",
+            "GAAA",
+            "====",
+            "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSG",
+            "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZA",
+            "ga7qynf7sowq3glr2bgmzehxavirza4kvwltjjfc7mgxua74p7ujvsgz",
+            "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ========",
+            "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ\n",
+            // Non-zero trailing bit.
+            "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAAAAAAAACJUR",
+            "é",
+        ];
+        for case in &cases {
+            assert!(StrKey::decode(case).is_err(), "{}", case);
+            assert!(decode_account_id(case).is_err(), "{}", case);
+        }
+        assert!(StrKey::decode(muxed).is_ok());
+        assert!(StrKey::decode(&"A".repeat(1000)).is_err());
+        assert!(decode_account_id(muxed).is_err());
+    }
+
+    #[test]
+    fn test_precise_errors() {
+        let account_id = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+        match decode_account_id(&account_id.to_lowercase()) {
+            Err(Error::NonCanonicalEncoding) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        match decode_account_id("GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGY") {
+            Err(Error::InvalidStrKeyChecksum) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        match decode_account_id("GA") {
+            Err(Error::InvalidStrKeyLength) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        // A valid contract id is not an account id.
+        match decode_account_id("CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE") {
+            Err(Error::InvalidStrKeyVersionByte) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        // Correct version byte and checksum, 31 bytes payload.
+        let short = super::encode_check(super::ACCOUNT_ID_VERSION_BYTE, &KEY[..31]).unwrap();
+        match decode_account_id(&short) {
+            Err(Error::InvalidStrKeyLength) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
 }
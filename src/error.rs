@@ -1,8 +1,10 @@
 use std::result;
 use std::convert::From;
 use std::str;
+use std::io;
 use base64;
 use bigdecimal;
+use serde_json;
 use serde_xdr;
 
 /// The Errors that can occur.
@@ -14,6 +16,14 @@ pub enum Error {
     InvalidStrKeyVersionByte,
     /// Invalid checksum in key.
     InvalidStrKeyChecksum,
+    /// Invalid key length, e.g. a truncated key.
+    InvalidStrKeyLength,
+    /// Non-canonical base32 or base64 encoding, e.g. lowercase or with padding.
+    NonCanonicalEncoding,
+    /// Unexpected data after the end of an XDR object.
+    TrailingXdrData,
+    /// The XDR object exceeds the decode limits.
+    XdrLimitExceeded,
     /// Invalid keypair seed.
     InvalidSeed,
     /// Invalid Asset code.
@@ -26,10 +36,72 @@ pub enum Error {
     InvalidStroopsAmount,
     /// Error that can occur when converting an amount with more than 7 digits.
     InvalidAmountScale,
+    /// Invalid amount: not a plain decimal number, e.g. `1e5` or `12.`.
+    InvalidAmountFormat,
     /// Invalid network id: too long.
     InvalidNetworkId,
     /// Invalid public key.
     InvalidPublicKey,
+    /// Transaction has no operations.
+    NoOperations,
+    /// Transaction has more operations than allowed by the protocol.
+    TooManyOperations,
+    /// Unsupported operation: its type can not be encoded or decoded yet.
+    UnsupportedOperation,
+    /// Transaction envelope exceeds the maximum size.
+    EnvelopeTooLarge,
+    /// Invalid time bounds: lower bound after upper bound.
+    InvalidTimeBounds,
+    /// Invalid operation amount, e.g. negative or zero.
+    InvalidAmount,
+    /// Invalid price: numerator and denominator must be positive.
+    InvalidPrice,
+    /// Invalid ciphertext: decryption or authentication failed.
+    InvalidCiphertext,
+    /// Invalid data entry name: too long.
+    InvalidDataName,
+    /// Invalid data entry value: too long.
+    InvalidDataValue,
+    /// Invalid ledger entry or key, e.g. too many signers or a malformed claim predicate.
+    InvalidLedgerEntry,
+    /// Invalid result code: unknown for the transaction or operation.
+    InvalidResultCode,
+    /// Invalid ledger entry changes, e.g. an update of an entry without a prior state.
+    InvalidLedgerEntryChanges,
+    /// Invalid ledger header, e.g. too many upgrades.
+    InvalidLedgerHeader,
+    /// The ledger header does not hash to its recorded hash.
+    LedgerHashMismatch,
+    /// The previous ledger hash of a header does not match the previous header.
+    LedgerChainBroken,
+    /// The ledger headers are not contiguous.
+    LedgerGap,
+    /// The transaction results do not hash to the result hash of the ledger header.
+    TransactionResultHashMismatch,
+    /// Invalid XDR record: a truncated record mark or fragment.
+    InvalidXdrRecord,
+    /// Invalid history archive state, e.g. a malformed bucket hash.
+    InvalidHistoryArchiveState,
+    /// Invalid checkpoint: not the last ledger of a checkpoint.
+    InvalidCheckpoint,
+    /// Unsupported history archive url: only `file://` urls can be read.
+    UnsupportedArchiveUrl,
+    /// Unsupported transaction set: generalized or with envelopes other than version 0.
+    UnsupportedTransactionSet,
+    /// Invalid mnemonic: unknown word, wrong length or bad checksum.
+    InvalidMnemonic,
+    /// Invalid derivation path: only hardened indexes are supported.
+    InvalidDerivationPath,
+    /// Invalid keystore: malformed or unsupported format.
+    InvalidKeystore,
+    /// Invalid password: the keystore could not be decrypted.
+    InvalidPassword,
+    /// The signer failed or returned an invalid signature.
+    SignerError,
+    /// The crypto library could not be initialized.
+    InitError,
+    /// Invalid vanity address pattern.
+    InvalidPattern,
     /// Error that can occur when interpreting a sequence of `u8` as utf-8.
     Utf8Error(str::Utf8Error),
     /// Error that can occour when decoding base64 encoded data.
@@ -40,6 +112,10 @@ pub enum Error {
     SerializationError(serde_xdr::CompatSerializationError),
     /// Error that can occur when deserializing from XDR.
     DeserializationError(serde_xdr::CompatDeserializationError),
+    /// Error that can occur when reading or writing data.
+    IoError(io::Error),
+    /// Error that can occur when (de)serializing JSON.
+    JsonError(serde_json::Error),
 }
 
 /// A `Result` alias where `Error` is a `shuttle_core::Error`.
@@ -74,3 +150,15 @@ impl From<serde_xdr::CompatSerializationError> for Error {
         Error::SerializationError(err)
     }
 }
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::JsonError(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::IoError(err)
+    }
+}
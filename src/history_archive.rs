@@ -0,0 +1,451 @@
+//! History archives in local directories.
+//!
+//! An archive stores, for every checkpoint of 64 ledgers, a History Archive
+//! State JSON file and gzipped XDR files of the ledger headers, transaction
+//! sets, results and consensus messages. Files live under a path derived
+//! from the hex checkpoint number, e.g. `ledger/00/00/00/ledger-0000003f.xdr.gz`.
+use std::fmt::Write;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use serde_json;
+use error::{Error, Result};
+use history_stream::{HistoryStream, ScpHistoryEntry, TransactionHistoryEntry,
+                     TransactionHistoryResultEntry};
+use ledger_header::LedgerHeaderHistoryEntry;
+
+/// Number of ledgers in a checkpoint.
+pub const CHECKPOINT_FREQUENCY: u32 = 64;
+
+const ROOT_STATE_PATH: &str = ".well-known/stellar-history.json";
+
+/// Return the checkpoint containing `ledger_seq`, the last ledger of the checkpoint.
+pub fn checkpoint_containing(ledger_seq: u32) -> u32 {
+    ledger_seq / CHECKPOINT_FREQUENCY * CHECKPOINT_FREQUENCY + (CHECKPOINT_FREQUENCY - 1)
+}
+
+/// Return `true` if `ledger_seq` is the last ledger of a checkpoint.
+pub fn is_checkpoint(ledger_seq: u32) -> bool {
+    ledger_seq % CHECKPOINT_FREQUENCY == CHECKPOINT_FREQUENCY - 1
+}
+
+/// Return the first and last ledger of `checkpoint`.
+///
+/// The first checkpoint is one ledger shorter, as ledgers start at 1.
+pub fn checkpoint_range(checkpoint: u32) -> Result<(u32, u32)> {
+    if !is_checkpoint(checkpoint) {
+        return Err(Error::InvalidCheckpoint);
+    }
+    let first = (checkpoint - (CHECKPOINT_FREQUENCY - 1)).max(1);
+    Ok((first, checkpoint))
+}
+
+/// The kind of a checkpoint file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HistoryCategory {
+    /// The History Archive State JSON.
+    History,
+    /// Ledger headers.
+    Ledger,
+    /// Transaction sets.
+    Transactions,
+    /// Transaction results.
+    Results,
+    /// Consensus messages.
+    Scp,
+}
+
+impl HistoryCategory {
+    /// Return the category name used in paths.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            HistoryCategory::History => "history",
+            HistoryCategory::Ledger => "ledger",
+            HistoryCategory::Transactions => "transactions",
+            HistoryCategory::Results => "results",
+            HistoryCategory::Scp => "scp",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match *self {
+            HistoryCategory::History => "json",
+            _ => "xdr.gz",
+        }
+    }
+}
+
+/// Return the path of a checkpoint file relative to the archive root.
+pub fn checkpoint_path(category: HistoryCategory, checkpoint: u32) -> Result<String> {
+    if !is_checkpoint(checkpoint) {
+        return Err(Error::InvalidCheckpoint);
+    }
+    let hex = format!("{:08x}", checkpoint);
+    Ok(format!(
+        "{}/{}/{}/{}/{}-{}.{}",
+        category.name(),
+        &hex[0..2],
+        &hex[2..4],
+        &hex[4..6],
+        category.name(),
+        hex,
+        category.extension()
+    ))
+}
+
+/// Return the path of a bucket relative to the archive root.
+pub fn bucket_path(hash: &[u8; 32]) -> String {
+    let hex = to_hex(hash);
+    format!("bucket/{}/{}/{}/bucket-{}.xdr.gz", &hex[0..2], &hex[2..4], &hex[4..6], hex)
+}
+
+/// The History Archive State, a JSON summary of an archive at a checkpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryArchiveState {
+    /// Format version, 1 or 2.
+    pub version: u32,
+    /// The stellar-core version that wrote the state.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    /// The checkpoint ledger.
+    pub current_ledger: u32,
+    /// The network passphrase, present since version 2.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network_passphrase: Option<String>,
+    /// The bucket list levels, from the most recent.
+    pub current_buckets: Vec<HistoryBucketLevel>,
+}
+
+/// A level of the bucket list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryBucketLevel {
+    /// Hex hash of the current bucket.
+    pub curr: String,
+    /// The bucket being merged.
+    pub next: FutureBucket,
+    /// Hex hash of the snapshot bucket.
+    pub snap: String,
+}
+
+/// A bucket merge in progress.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FutureBucket {
+    /// 0 when clear, 1 when `output` is known, 2 when the inputs are known.
+    pub state: u32,
+    /// Hex hash of the merge output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// Hex hash of the current bucket input.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curr: Option<String>,
+    /// Hex hash of the snapshot bucket input.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snap: Option<String>,
+    /// Hex hashes of the shadow buckets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shadow: Option<Vec<String>>,
+}
+
+impl HistoryArchiveState {
+    /// Deserialize the state from JSON.
+    pub fn from_json(json: &str) -> Result<HistoryArchiveState> {
+        let state: HistoryArchiveState = serde_json::from_str(json)?;
+        state.validate()?;
+        Ok(state)
+    }
+
+    /// Deserialize the state from the JSON `reader`.
+    pub fn from_reader<R: Read>(reader: R) -> Result<HistoryArchiveState> {
+        let state: HistoryArchiveState = serde_json::from_reader(reader)?;
+        state.validate()?;
+        Ok(state)
+    }
+
+    /// Serialize the state to JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.version != 1 && self.version != 2 {
+            return Err(Error::InvalidHistoryArchiveState);
+        }
+        self.bucket_hashes().map(|_| ())
+    }
+
+    /// Return the hashes of the non-empty buckets referenced by the state,
+    /// without duplicates.
+    pub fn bucket_hashes(&self) -> Result<Vec<[u8; 32]>> {
+        let mut hashes = Vec::new();
+        for level in &self.current_buckets {
+            let next = &level.next;
+            let mut names = vec![&level.curr, &level.snap];
+            names.extend(next.output.iter());
+            names.extend(next.curr.iter());
+            names.extend(next.snap.iter());
+            names.extend(next.shadow.iter().flatten());
+            for name in names {
+                let hash = from_hex(name).ok_or(Error::InvalidHistoryArchiveState)?;
+                if hash != [0; 32] && !hashes.contains(&hash) {
+                    hashes.push(hash);
+                }
+            }
+        }
+        Ok(hashes)
+    }
+}
+
+/// A history archive in a local directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryArchive {
+    root: PathBuf,
+}
+
+impl HistoryArchive {
+    /// Create an archive rooted at the directory `root`.
+    pub fn new<P: Into<PathBuf>>(root: P) -> HistoryArchive {
+        HistoryArchive { root: root.into() }
+    }
+
+    /// Create an archive from a `file://` url, e.g. `file:///var/lib/history`.
+    pub fn from_url(url: &str) -> Result<HistoryArchive> {
+        match url.strip_prefix("file://") {
+            Some(path) if path.starts_with('/') => Ok(HistoryArchive::new(path)),
+            _ => Err(Error::UnsupportedArchiveUrl),
+        }
+    }
+
+    /// Return the root directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Read the state of the most recent checkpoint.
+    pub fn state(&self) -> Result<HistoryArchiveState> {
+        HistoryArchiveState::from_reader(File::open(self.root.join(ROOT_STATE_PATH))?)
+    }
+
+    /// Read the state at `checkpoint`.
+    pub fn checkpoint_state(&self, checkpoint: u32) -> Result<HistoryArchiveState> {
+        HistoryArchiveState::from_reader(self.open(HistoryCategory::History, checkpoint)?)
+    }
+
+    /// Read the ledger headers of `checkpoint`.
+    pub fn ledgers(&self, checkpoint: u32) -> Result<HistoryStream<File, LedgerHeaderHistoryEntry>> {
+        Ok(HistoryStream::ledgers(self.open(HistoryCategory::Ledger, checkpoint)?))
+    }
+
+    /// Read the transaction sets of `checkpoint`.
+    pub fn transactions(
+        &self,
+        checkpoint: u32,
+    ) -> Result<HistoryStream<File, TransactionHistoryEntry>> {
+        Ok(HistoryStream::transactions(self.open(HistoryCategory::Transactions, checkpoint)?))
+    }
+
+    /// Read the transaction results of `checkpoint`.
+    pub fn results(
+        &self,
+        checkpoint: u32,
+    ) -> Result<HistoryStream<File, TransactionHistoryResultEntry>> {
+        Ok(HistoryStream::results(self.open(HistoryCategory::Results, checkpoint)?))
+    }
+
+    /// Read the consensus messages of `checkpoint`.
+    pub fn scp(&self, checkpoint: u32) -> Result<HistoryStream<File, ScpHistoryEntry>> {
+        Ok(HistoryStream::scp(self.open(HistoryCategory::Scp, checkpoint)?))
+    }
+
+    fn open(&self, category: HistoryCategory, checkpoint: u32) -> Result<File> {
+        let path = checkpoint_path(category, checkpoint)?;
+        Ok(File::open(self.root.join(path))?)
+    }
+}
+
+fn to_hex(data: &[u8]) -> String {
+    let mut hex = String::with_capacity(data.len() * 2);
+    for byte in data {
+        write!(hex, "{:02x}", byte).expect("writing to a string never fails");
+    }
+    hex
+}
+
+fn from_hex(hex: &str) -> Option<[u8; 32]> {
+    if hex.len() != 64 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let mut hash = [0; 32];
+    for (i, byte) in hash.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::{self, File};
+    use std::io::Write;
+    use std::process;
+    use byteorder::{BigEndian, ByteOrder};
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use super::{bucket_path, checkpoint_containing, checkpoint_path, checkpoint_range,
+                is_checkpoint, HistoryArchive, HistoryArchiveState, HistoryCategory};
+    use amount::Stroops;
+    use error::Error;
+    use ledger_chain::LedgerChainVerifier;
+    use ledger_header::{LedgerHeader, LedgerHeaderHistoryEntry, StellarValue};
+    use ToXdr;
+
+    const STATE: &str = r#"{
+        "version": 1,
+        "server": "v19.5.0",
+        "currentLedger": 127,
+        "currentBuckets": [
+            {
+                "curr": "0101010101010101010101010101010101010101010101010101010101010101",
+                "next": { "state": 0 },
+                "snap": "0000000000000000000000000000000000000000000000000000000000000000"
+            },
+            {
+                "curr": "0202020202020202020202020202020202020202020202020202020202020202",
+                "next": {
+                    "state": 1,
+                    "output": "0101010101010101010101010101010101010101010101010101010101010101"
+                },
+                "snap": "0303030303030303030303030303030303030303030303030303030303030303"
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_checkpoints() {
+        assert_eq!(checkpoint_containing(1), 63);
+        assert_eq!(checkpoint_containing(63), 63);
+        assert_eq!(checkpoint_containing(64), 127);
+        assert_eq!(checkpoint_containing(u32::MAX), u32::MAX);
+        assert!(is_checkpoint(127));
+        assert!(!is_checkpoint(128));
+        assert_eq!(checkpoint_range(63).unwrap(), (1, 63));
+        assert_eq!(checkpoint_range(127).unwrap(), (64, 127));
+        assert_eq!(checkpoint_range(u32::MAX).unwrap(), (u32::MAX - 63, u32::MAX));
+        match checkpoint_range(64) {
+            Err(Error::InvalidCheckpoint) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(
+            checkpoint_path(HistoryCategory::Ledger, 63).unwrap(),
+            "ledger/00/00/00/ledger-0000003f.xdr.gz"
+        );
+        assert_eq!(
+            checkpoint_path(HistoryCategory::History, 0x0123_45bf).unwrap(),
+            "history/01/23/45/history-012345bf.json"
+        );
+        assert_eq!(
+            bucket_path(&[0xab; 32]),
+            format!("bucket/ab/ab/ab/bucket-{}.xdr.gz", "ab".repeat(32))
+        );
+    }
+
+    #[test]
+    fn test_history_archive_state() {
+        let state = HistoryArchiveState::from_json(STATE).unwrap();
+        assert_eq!(state.current_ledger, 127);
+        assert_eq!(state.server, Some("v19.5.0".to_string()));
+        assert_eq!(state.bucket_hashes().unwrap(), vec![[1; 32], [2; 32], [3; 32]]);
+        let json = state.to_json().unwrap();
+        assert_eq!(HistoryArchiveState::from_json(&json).unwrap(), state);
+
+        let invalid = STATE.replace("0303", "zz03");
+        match HistoryArchiveState::from_json(&invalid) {
+            Err(Error::InvalidHistoryArchiveState) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_from_url() {
+        let archive = HistoryArchive::from_url("file:///var/lib/history").unwrap();
+        assert_eq!(archive.root().to_str(), Some("/var/lib/history"));
+        match HistoryArchive::from_url("https://history.stellar.org/prd/core-live/core_live_001") {
+            Err(Error::UnsupportedArchiveUrl) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    fn header(ledger_seq: u32, previous_ledger_hash: [u8; 32]) -> LedgerHeaderHistoryEntry {
+        let header = LedgerHeader {
+            ledger_version: 19,
+            previous_ledger_hash,
+            scp_value: StellarValue {
+                tx_set_hash: [0; 32],
+                close_time: u64::from(ledger_seq),
+                upgrades: Vec::new(),
+                signature: None,
+            },
+            tx_set_result_hash: [0; 32],
+            bucket_list_hash: [0; 32],
+            ledger_seq,
+            total_coins: Stroops::new(1_000_000_000_000_000_000),
+            fee_pool: Stroops::new(0),
+            inflation_seq: 0,
+            id_pool: 0,
+            base_fee: 100,
+            base_reserve: 5_000_000,
+            max_tx_set_size: 1000,
+            skip_list: [[0; 32]; 4],
+            flags: None,
+        };
+        LedgerHeaderHistoryEntry {
+            hash: header.hash().unwrap(),
+            header,
+        }
+    }
+
+    #[test]
+    fn test_read_archive() {
+        let root = env::temp_dir().join(format!("shuttle-history-{}", process::id()));
+        let archive = HistoryArchive::new(root.clone());
+        fs::create_dir_all(root.join(".well-known")).unwrap();
+        fs::write(root.join(".well-known/stellar-history.json"), STATE).unwrap();
+
+        let first = header(126, [9; 32]);
+        let second = header(127, first.hash);
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        for entry in &[&first, &second] {
+            let mut record = Vec::new();
+            entry.to_writer(&mut record).unwrap();
+            let mut mark = [0; 4];
+            BigEndian::write_u32(&mut mark, 0x8000_0000 | record.len() as u32);
+            encoder.write_all(&mark).unwrap();
+            encoder.write_all(&record).unwrap();
+        }
+        let path = root.join(checkpoint_path(HistoryCategory::Ledger, 127).unwrap());
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        File::create(&path)
+            .unwrap()
+            .write_all(&encoder.finish().unwrap())
+            .unwrap();
+
+        let state = archive.state().unwrap();
+        let mut verifier = LedgerChainVerifier::new();
+        let mut count = 0;
+        for entry in archive.ledgers(state.current_ledger).unwrap() {
+            verifier.verify(&entry.unwrap()).unwrap();
+            count += 1;
+        }
+        assert_eq!(count, 2);
+        assert_eq!(verifier.last_ledger(), Some((127, second.hash)));
+        match archive.results(127) {
+            Err(Error::IoError(_)) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        fs::remove_dir_all(root).unwrap();
+    }
+}
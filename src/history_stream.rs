@@ -0,0 +1,378 @@
+//! Streams of history archive checkpoint files.
+//!
+//! Checkpoint files are gzipped sequences of XDR objects, each framed by a
+//! record mark: a big-endian `u32` whose high bit flags the last fragment of
+//! the record and whose other bits are the fragment length.
+//!
+//! Ledger headers, results and transaction sets are decoded. Transaction sets
+//! are limited to the envelopes this crate decodes, `ENVELOPE_TYPE_TX_V0`:
+//! generalized sets, of protocol 20 and later, and other envelope types are
+//! reported as `Error::UnsupportedTransactionSet`. Consensus messages are
+//! kept encoded.
+use std::io::{Cursor, Read};
+use byteorder::{BigEndian, ByteOrder};
+use flate2::read::GzDecoder;
+use error::{Error, Result};
+use ledger_header::LedgerHeaderHistoryEntry;
+use transaction::SignedTransaction;
+use transaction_result::TransactionResultSet;
+use xdr::{DecodeLimits, FromXdr};
+
+const LAST_FRAGMENT: u32 = 0x8000_0000;
+
+/// The results of the transactions of a ledger, as found in results files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionHistoryResultEntry {
+    /// The ledger sequence number.
+    pub ledger_seq: u32,
+    /// The transaction results.
+    pub results: TransactionResultSet,
+}
+
+/// The transaction set of a ledger, as found in transactions files.
+#[derive(Debug, Clone)]
+pub struct TransactionHistoryEntry {
+    /// The ledger sequence number.
+    pub ledger_seq: u32,
+    /// Hash of the previous ledger header.
+    pub previous_ledger_hash: [u8; 32],
+    /// The transactions, in the order of the set.
+    pub transactions: Vec<SignedTransaction>,
+}
+
+/// The consensus messages of a ledger, as found in scp files.
+///
+/// The messages are kept encoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScpHistoryEntry {
+    /// The ledger sequence number.
+    pub ledger_seq: u32,
+    /// The XDR encoded `SCPHistoryEntry`.
+    pub xdr: Vec<u8>,
+}
+
+/// Reads the records of an uncompressed, record-marked XDR stream.
+#[derive(Debug)]
+pub struct RecordReader<R> {
+    inner: R,
+    max_len: usize,
+    done: bool,
+}
+
+impl<R: Read> RecordReader<R> {
+    /// Create a reader accepting records up to the default `DecodeLimits` bytes.
+    pub fn new(inner: R) -> RecordReader<R> {
+        RecordReader {
+            inner,
+            max_len: DecodeLimits::default().max_bytes,
+            done: false,
+        }
+    }
+
+    /// Accept records up to `max_len` bytes.
+    pub fn with_max_len(mut self, max_len: usize) -> RecordReader<R> {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Read the next record, or `None` at the end of the stream.
+    pub fn read_record(&mut self) -> Result<Option<Vec<u8>>> {
+        let mut record = Vec::new();
+        loop {
+            let mark = match self.read_mark(record.is_empty())? {
+                Some(mark) => mark,
+                None => return Ok(None),
+            };
+            let len = (mark & !LAST_FRAGMENT) as usize;
+            if record.len() + len > self.max_len {
+                return Err(Error::XdrLimitExceeded);
+            }
+            let read = (&mut self.inner).take(len as u64).read_to_end(&mut record)?;
+            if read != len {
+                return Err(Error::InvalidXdrRecord);
+            }
+            if mark & LAST_FRAGMENT != 0 {
+                return Ok(Some(record));
+            }
+        }
+    }
+
+    // A clean end of stream is only accepted before the first fragment.
+    fn read_mark(&mut self, first: bool) -> Result<Option<u32>> {
+        let mut buf = [0; 4];
+        let mut filled = 0;
+        while filled < buf.len() {
+            let read = self.inner.read(&mut buf[filled..])?;
+            if read == 0 {
+                if filled == 0 && first {
+                    return Ok(None);
+                }
+                return Err(Error::InvalidXdrRecord);
+            }
+            filled += read;
+        }
+        Ok(Some(BigEndian::read_u32(&buf)))
+    }
+}
+
+impl<R: Read> Iterator for RecordReader<R> {
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Result<Vec<u8>>> {
+        if self.done {
+            return None;
+        }
+        let record = self.read_record();
+        match record {
+            Ok(Some(_)) => {}
+            _ => self.done = true,
+        }
+        record.transpose()
+    }
+}
+
+/// Reads the entries of a gzipped checkpoint file.
+#[derive(Debug)]
+pub struct HistoryStream<R: Read, T> {
+    records: RecordReader<GzDecoder<R>>,
+    limits: DecodeLimits,
+    decode: fn(&[u8], &DecodeLimits) -> Result<T>,
+}
+
+impl<R: Read, T> HistoryStream<R, T> {
+    fn new(gzipped: R, decode: fn(&[u8], &DecodeLimits) -> Result<T>) -> HistoryStream<R, T> {
+        HistoryStream {
+            records: RecordReader::new(GzDecoder::new(gzipped)),
+            limits: DecodeLimits::default(),
+            decode,
+        }
+    }
+
+    /// Decode every entry within `limits`, the default `DecodeLimits` otherwise.
+    pub fn with_limits(mut self, limits: DecodeLimits) -> HistoryStream<R, T> {
+        self.records = self.records.with_max_len(limits.max_bytes);
+        self.limits = limits;
+        self
+    }
+}
+
+impl<R: Read> HistoryStream<R, LedgerHeaderHistoryEntry> {
+    /// Read a gzipped ledger file.
+    pub fn ledgers(gzipped: R) -> HistoryStream<R, LedgerHeaderHistoryEntry> {
+        HistoryStream::new(gzipped, |record, limits| {
+            decode_record(record, |cursor| {
+                LedgerHeaderHistoryEntry::from_reader_with_limits(cursor, limits)
+            })
+        })
+    }
+}
+
+impl<R: Read> HistoryStream<R, TransactionHistoryEntry> {
+    /// Read a gzipped transactions file.
+    pub fn transactions(gzipped: R) -> HistoryStream<R, TransactionHistoryEntry> {
+        HistoryStream::new(gzipped, |record, limits| {
+            decode_record(record, |cursor| decode_transaction_set(cursor, limits))
+        })
+    }
+}
+
+impl<R: Read> HistoryStream<R, TransactionHistoryResultEntry> {
+    /// Read a gzipped results file.
+    pub fn results(gzipped: R) -> HistoryStream<R, TransactionHistoryResultEntry> {
+        HistoryStream::new(gzipped, |record, limits| {
+            decode_record(record, |cursor| {
+                TransactionHistoryResultEntry::from_reader_with_limits(cursor, limits)
+            })
+        })
+    }
+}
+
+impl<R: Read> HistoryStream<R, ScpHistoryEntry> {
+    /// Read a gzipped scp file.
+    pub fn scp(gzipped: R) -> HistoryStream<R, ScpHistoryEntry> {
+        HistoryStream::new(gzipped, |record, _| {
+            // Only version 0 of the `SCPHistoryEntry` union exists.
+            if read_u32(record, 0)? != 0 {
+                return Err(Error::InvalidXdrRecord);
+            }
+            Ok(ScpHistoryEntry {
+                ledger_seq: read_u32(record, 4)?,
+                xdr: record.to_vec(),
+            })
+        })
+    }
+}
+
+impl<R: Read, T> Iterator for HistoryStream<R, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        let record = match self.records.next()? {
+            Ok(record) => record,
+            Err(err) => return Some(Err(err)),
+        };
+        Some((self.decode)(&record, &self.limits))
+    }
+}
+
+fn decode_record<T, F>(record: &[u8], decode: F) -> Result<T>
+where
+    F: FnOnce(&mut Cursor<&[u8]>) -> Result<T>,
+{
+    let mut cursor = Cursor::new(record);
+    let x = decode(&mut cursor)?;
+    if cursor.position() != record.len() as u64 {
+        return Err(Error::TrailingXdrData);
+    }
+    Ok(x)
+}
+
+// A `TransactionHistoryEntry` with a `TransactionSet` of version 0 envelopes,
+// which encode like the envelopes of `SignedTransaction`.
+fn decode_transaction_set(
+    cursor: &mut Cursor<&[u8]>,
+    limits: &DecodeLimits,
+) -> Result<TransactionHistoryEntry> {
+    let ledger_seq = next_u32(cursor)?;
+    let mut previous_ledger_hash = [0; 32];
+    cursor.read_exact(&mut previous_ledger_hash).map_err(|_| Error::InvalidXdrRecord)?;
+    let count = next_u32(cursor)?;
+    let mut transactions = Vec::new();
+    for _ in 0..count {
+        if read_u32(cursor.get_ref(), cursor.position() as usize)? != 0 {
+            return Err(Error::UnsupportedTransactionSet);
+        }
+        transactions.push(SignedTransaction::from_reader_with_limits(cursor, limits)?);
+    }
+    // The extension holds the generalized set.
+    if next_u32(cursor)? != 0 {
+        return Err(Error::UnsupportedTransactionSet);
+    }
+    Ok(TransactionHistoryEntry {
+        ledger_seq,
+        previous_ledger_hash,
+        transactions,
+    })
+}
+
+fn next_u32(cursor: &mut Cursor<&[u8]>) -> Result<u32> {
+    let value = read_u32(cursor.get_ref(), cursor.position() as usize)?;
+    cursor.set_position(cursor.position() + 4);
+    Ok(value)
+}
+
+fn read_u32(record: &[u8], offset: usize) -> Result<u32> {
+    match record.get(offset..offset + 4) {
+        Some(buf) => Ok(BigEndian::read_u32(buf)),
+        None => Err(Error::InvalidXdrRecord),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+    use base64;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use super::{HistoryStream, RecordReader};
+    use error::Error;
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_record_fragments() {
+        let data = [
+            0, 0, 0, 2, 1, 2, 0x80, 0, 0, 1, 3, // one record in two fragments
+            0x80, 0, 0, 0, // an empty record
+        ];
+        let records: Vec<Vec<u8>> = RecordReader::new(Cursor::new(&data[..]))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(records, vec![vec![1, 2, 3], vec![]]);
+    }
+
+    #[test]
+    fn test_truncated_record() {
+        let mut records = RecordReader::new(Cursor::new(&[0x80, 0, 0, 4, 1, 2][..]));
+        match records.next() {
+            Some(Err(Error::InvalidXdrRecord)) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(records.next().is_none());
+
+        let mut records = RecordReader::new(Cursor::new(&[0x80, 0, 0, 4][..])).with_max_len(3);
+        match records.next() {
+            Some(Err(Error::XdrLimitExceeded)) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_results_stream() {
+        let set = base64::decode(
+            "AAAAAQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFAAAAAAAAAGQAAAAAAAAAAQAAAAAAAAAB\
+             AAAAAAAAAAA=",
+        ).unwrap();
+        let mut data = vec![0x80, 0, 0, (set.len() + 8) as u8, 0, 0, 0, 64];
+        data.extend_from_slice(&set);
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        let entries = HistoryStream::results(Cursor::new(gzip(&data)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].ledger_seq, 64);
+        assert_eq!(entries[0].results.results[0].transaction_hash, [5; 32]);
+    }
+
+    #[test]
+    fn test_transactions_stream() {
+        // A version 0 envelope with an inflation operation.
+        let envelope = base64::decode(
+            "AAAAAGPgfnO/mUw71s3yUF9r1CS8IOgym90QLaqUHgG8D4URAAAAZAAAAAAAAAPoAAAAAAAAAAAAAAABAAAA\
+             AAAAAAkAAAAAAAAAAbwPhREAAABAkqlNirgebGCMoc0kdl7FLMl/k2q36LZN1EI7+kfY5xiGg9Mb0txYsIZY\
+             3zx1RREQywp/wgpLTpfHqIcnDs2HAg==",
+        ).unwrap();
+        let record = |envelope_type: u8, ext: u8| {
+            let mut record = vec![0, 0, 0, 64];
+            record.extend_from_slice(&[2; 32]);
+            record.extend_from_slice(&[0, 0, 0, 1]);
+            record.extend_from_slice(&envelope);
+            record[40 + 3] = envelope_type;
+            record.extend_from_slice(&[0, 0, 0, ext]);
+            let mut data = vec![0x80, 0, 0, record.len() as u8];
+            data.extend_from_slice(&record);
+            gzip(&data)
+        };
+
+        let entries = HistoryStream::transactions(Cursor::new(record(0, 0)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(entries[0].ledger_seq, 64);
+        assert_eq!(entries[0].previous_ledger_hash, [2; 32]);
+        assert_eq!(entries[0].transactions.len(), 1);
+        assert_eq!(entries[0].transactions[0].transaction().sequence(), 1000);
+        assert_eq!(entries[0].transactions[0].signatures().len(), 1);
+
+        // A fee bump envelope and a generalized set.
+        for &(envelope_type, ext) in &[(5, 0), (0, 1)] {
+            match HistoryStream::transactions(Cursor::new(record(envelope_type, ext))).next() {
+                Some(Err(Error::UnsupportedTransactionSet)) => {}
+                other => panic!("unexpected {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_scp_stream() {
+        let data = [0x80, 0, 0, 12, 0, 0, 0, 0, 0, 0, 0, 65, 0, 0, 0, 0];
+        let entries = HistoryStream::scp(Cursor::new(gzip(&data)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(entries[0].ledger_seq, 65);
+        assert_eq!(entries[0].xdr, &data[4..]);
+    }
+}
@@ -0,0 +1,187 @@
+use crypto;
+use error::{Error, Result};
+use ledger_header::LedgerHeaderHistoryEntry;
+use transaction_result::TransactionResultSet;
+
+/// Verifies that ledger headers form an unbroken hash chain.
+///
+/// Headers are given in ledger order. Each header must hash to its recorded
+/// hash, follow the previous header by one ledger and link to it through
+/// `previous_ledger_hash`. On error the verifier keeps the last verified
+/// ledger, see `last_ledger`.
+#[derive(Debug, Clone, Default)]
+pub struct LedgerChainVerifier {
+    last: Option<(u32, [u8; 32])>,
+}
+
+impl LedgerChainVerifier {
+    /// Create a verifier that accepts any first header.
+    pub fn new() -> LedgerChainVerifier {
+        LedgerChainVerifier::default()
+    }
+
+    /// Create a verifier whose first header must follow a trusted ledger.
+    pub fn trusting(ledger_seq: u32, hash: [u8; 32]) -> LedgerChainVerifier {
+        LedgerChainVerifier {
+            last: Some((ledger_seq, hash)),
+        }
+    }
+
+    /// Return the sequence number and hash of the last verified ledger.
+    pub fn last_ledger(&self) -> Option<(u32, [u8; 32])> {
+        self.last
+    }
+
+    /// Verify the next header.
+    pub fn verify(&mut self, entry: &LedgerHeaderHistoryEntry) -> Result<()> {
+        let hash = self.check(entry)?;
+        self.last = Some((entry.header.ledger_seq, hash));
+        Ok(())
+    }
+
+    fn check(&self, entry: &LedgerHeaderHistoryEntry) -> Result<[u8; 32]> {
+        let hash = entry.header.hash()?;
+        if hash != entry.hash {
+            return Err(Error::LedgerHashMismatch);
+        }
+        if let Some((last_seq, last_hash)) = self.last {
+            if last_seq.checked_add(1) != Some(entry.header.ledger_seq) {
+                return Err(Error::LedgerGap);
+            }
+            if entry.header.previous_ledger_hash != last_hash {
+                return Err(Error::LedgerChainBroken);
+            }
+        }
+        Ok(hash)
+    }
+
+    /// Verify the next header and the results of its transactions.
+    ///
+    /// `None` stands for a ledger without transactions, whose results are
+    /// omitted from history archives.
+    pub fn verify_with_results(
+        &mut self,
+        entry: &LedgerHeaderHistoryEntry,
+        results: Option<&TransactionResultSet>,
+    ) -> Result<()> {
+        let hash = self.check(entry)?;
+        let results_hash = match results {
+            Some(results) => results.hash().to_vec(),
+            // The encoding of an empty result set is its zero length.
+            None => crypto::hash(&[0; 4]),
+        };
+        if results_hash[..] != entry.header.tx_set_result_hash[..] {
+            return Err(Error::TransactionResultHashMismatch);
+        }
+        self.last = Some((entry.header.ledger_seq, hash));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LedgerChainVerifier;
+    use amount::Stroops;
+    use crypto;
+    use error::Error;
+    use ledger_header::{LedgerHeader, LedgerHeaderHistoryEntry, StellarValue};
+    use transaction_result::TransactionResultSet;
+    use xdr::FromXdr;
+
+    fn entry(ledger_seq: u32, previous_ledger_hash: [u8; 32]) -> LedgerHeaderHistoryEntry {
+        let mut tx_set_result_hash = [0; 32];
+        tx_set_result_hash.copy_from_slice(&crypto::hash(&[0; 4]));
+        let header = LedgerHeader {
+            ledger_version: 19,
+            previous_ledger_hash,
+            scp_value: StellarValue {
+                tx_set_hash: [0; 32],
+                close_time: 1_700_000_000 + u64::from(ledger_seq) * 5,
+                upgrades: Vec::new(),
+                signature: None,
+            },
+            tx_set_result_hash,
+            bucket_list_hash: [0; 32],
+            ledger_seq,
+            total_coins: Stroops::new(1_000_000_000_000_000_000),
+            fee_pool: Stroops::new(0),
+            inflation_seq: 0,
+            id_pool: 0,
+            base_fee: 100,
+            base_reserve: 5_000_000,
+            max_tx_set_size: 1000,
+            skip_list: [[0; 32]; 4],
+            flags: None,
+        };
+        LedgerHeaderHistoryEntry {
+            hash: header.hash().unwrap(),
+            header,
+        }
+    }
+
+    fn chain(len: u32) -> Vec<LedgerHeaderHistoryEntry> {
+        let mut entries: Vec<LedgerHeaderHistoryEntry> = Vec::new();
+        for ledger_seq in 10..10 + len {
+            let previous = entries.last().map(|e| e.hash).unwrap_or([0; 32]);
+            entries.push(entry(ledger_seq, previous));
+        }
+        entries
+    }
+
+    #[test]
+    fn test_unbroken_chain() {
+        let entries = chain(3);
+        let mut verifier = LedgerChainVerifier::new();
+        for entry in &entries {
+            verifier.verify_with_results(entry, None).unwrap();
+        }
+        assert_eq!(verifier.last_ledger(), Some((12, entries[2].hash)));
+    }
+
+    #[test]
+    fn test_gap() {
+        let entries = chain(3);
+        let mut verifier = LedgerChainVerifier::trusting(10, entries[0].hash);
+        match verifier.verify(&entries[2]) {
+            Err(Error::LedgerGap) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(verifier.last_ledger(), Some((10, entries[0].hash)));
+    }
+
+    #[test]
+    fn test_broken_chain() {
+        let entries = chain(2);
+        let mut verifier = LedgerChainVerifier::trusting(10, [1; 32]);
+        match verifier.verify(&entries[1]) {
+            Err(Error::LedgerChainBroken) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_tampered_header() {
+        let mut entries = chain(1);
+        entries[0].header.fee_pool = Stroops::new(1);
+        match LedgerChainVerifier::new().verify(&entries[0]) {
+            Err(Error::LedgerHashMismatch) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_result_hash_mismatch() {
+        let entries = chain(1);
+        // Not the empty set the headers of `chain` record.
+        let results = TransactionResultSet::from_base64(
+            "AAAAAQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFAAAAAAAAAGQAAAAAAAAAAQAAAAAAAAAB\
+             AAAAAAAAAAA=",
+        ).unwrap();
+        let mut verifier = LedgerChainVerifier::new();
+        match verifier.verify_with_results(&entries[0], Some(&results)) {
+            Err(Error::TransactionResultHashMismatch) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(verifier.last_ledger(), None);
+    }
+}
@@ -0,0 +1,467 @@
+use amount::{Price, Stroops};
+use asset::{Asset, CreditAsset};
+use crypto::strkey::{SignedPayload, StrKey};
+use ed25519_dalek::PublicKey;
+use error::Result;
+
+/// Maximum number of signers of an account.
+pub const MAX_SIGNERS: usize = 20;
+
+/// An entry in the ledger, as found in Horizon `_xdr` fields and history
+/// archive buckets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerEntry {
+    /// Ledger sequence number of the last change to the entry.
+    pub last_modified_ledger_seq: u32,
+    /// The entry.
+    pub data: LedgerEntryData,
+    /// Sponsorship extension, if present.
+    pub extension: Option<LedgerEntryExtensionV1>,
+}
+
+impl LedgerEntry {
+    /// Return the account sponsoring the entry reserve, if any.
+    pub fn sponsoring_id(&self) -> Option<&PublicKey> {
+        self.extension
+            .as_ref()
+            .and_then(|ext| ext.sponsoring_id.as_ref())
+    }
+
+    /// Return the key identifying the entry.
+    pub fn ledger_key(&self) -> LedgerKey {
+        self.data.ledger_key()
+    }
+}
+
+/// Sponsorship extension of a ledger entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerEntryExtensionV1 {
+    /// The account sponsoring the entry reserve.
+    pub sponsoring_id: Option<PublicKey>,
+}
+
+/// The different kinds of ledger entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LedgerEntryData {
+    /// An account.
+    Account(AccountEntry),
+    /// A trust line of an account.
+    TrustLine(TrustLineEntry),
+    /// An offer on the decentralized exchange.
+    Offer(OfferEntry),
+    /// A data entry of an account.
+    Data(DataEntry),
+    /// A claimable balance.
+    ClaimableBalance(ClaimableBalanceEntry),
+    /// A liquidity pool.
+    LiquidityPool(LiquidityPoolEntry),
+}
+
+impl LedgerEntryData {
+    /// Return the key identifying the entry.
+    pub fn ledger_key(&self) -> LedgerKey {
+        match *self {
+            LedgerEntryData::Account(ref entry) => LedgerKey::Account {
+                account_id: entry.account_id,
+            },
+            LedgerEntryData::TrustLine(ref entry) => LedgerKey::TrustLine {
+                account_id: entry.account_id,
+                asset: entry.asset.clone(),
+            },
+            LedgerEntryData::Offer(ref entry) => LedgerKey::Offer {
+                seller_id: entry.seller_id,
+                offer_id: entry.offer_id,
+            },
+            LedgerEntryData::Data(ref entry) => LedgerKey::Data {
+                account_id: entry.account_id,
+                name: entry.name.clone(),
+            },
+            LedgerEntryData::ClaimableBalance(ref entry) => LedgerKey::ClaimableBalance {
+                balance_id: entry.balance_id,
+            },
+            LedgerEntryData::LiquidityPool(ref entry) => LedgerKey::LiquidityPool {
+                liquidity_pool_id: entry.liquidity_pool_id,
+            },
+        }
+    }
+}
+
+/// The key identifying a ledger entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LedgerKey {
+    /// Key of an account.
+    Account {
+        /// The account id.
+        account_id: PublicKey,
+    },
+    /// Key of a trust line.
+    TrustLine {
+        /// The account holding the trust line.
+        account_id: PublicKey,
+        /// The trusted asset.
+        asset: TrustLineAsset,
+    },
+    /// Key of an offer.
+    Offer {
+        /// The account that created the offer.
+        seller_id: PublicKey,
+        /// The offer id.
+        offer_id: i64,
+    },
+    /// Key of a data entry.
+    Data {
+        /// The account holding the data entry.
+        account_id: PublicKey,
+        /// The data entry name.
+        name: String,
+    },
+    /// Key of a claimable balance.
+    ClaimableBalance {
+        /// The claimable balance id.
+        balance_id: [u8; 32],
+    },
+    /// Key of a liquidity pool.
+    LiquidityPool {
+        /// The liquidity pool id.
+        liquidity_pool_id: [u8; 32],
+    },
+}
+
+/// An account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountEntry {
+    /// The account id.
+    pub account_id: PublicKey,
+    /// Balance in stroops.
+    pub balance: Stroops,
+    /// Last used sequence number.
+    pub sequence: u64,
+    /// Number of sub entries (trust lines, offers, signers, data entries).
+    pub num_sub_entries: u32,
+    /// Account receiving the inflation payout.
+    pub inflation_dest: Option<PublicKey>,
+    /// Account flags, see the `AUTH_*` constants.
+    pub flags: u32,
+    /// Home domain, at most 32 bytes.
+    pub home_domain: String,
+    /// Signing thresholds.
+    pub thresholds: Thresholds,
+    /// Additional signers, at most `MAX_SIGNERS`.
+    pub signers: Vec<AccountSigner>,
+    /// Liabilities extension, if present.
+    pub extension: Option<AccountEntryExtensionV1>,
+}
+
+impl AccountEntry {
+    /// Trust lines must be authorized by the issuer.
+    pub const AUTH_REQUIRED: u32 = 0x1;
+    /// The issuer can revoke trust line authorization.
+    pub const AUTH_REVOCABLE: u32 = 0x2;
+    /// Account flags and signers can never change.
+    pub const AUTH_IMMUTABLE: u32 = 0x4;
+    /// The issuer can claw back its assets.
+    pub const AUTH_CLAWBACK_ENABLED: u32 = 0x8;
+
+    /// Return the account liabilities, if present.
+    pub fn liabilities(&self) -> Option<&Liabilities> {
+        self.extension.as_ref().map(|ext| &ext.liabilities)
+    }
+
+    /// Return the sponsorship extension, if present.
+    pub fn sponsorship(&self) -> Option<&AccountEntryExtensionV2> {
+        self.extension.as_ref().and_then(|ext| ext.v2.as_ref())
+    }
+}
+
+/// Account extension with liabilities.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountEntryExtensionV1 {
+    /// Buying and selling liabilities.
+    pub liabilities: Liabilities,
+    /// Sponsorship extension, if present.
+    pub v2: Option<AccountEntryExtensionV2>,
+}
+
+/// Account extension with sponsorship counters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountEntryExtensionV2 {
+    /// Number of reserves sponsored for this account.
+    pub num_sponsored: u32,
+    /// Number of reserves this account sponsors.
+    pub num_sponsoring: u32,
+    /// Sponsor of each signer, in the same order as the signers.
+    pub signer_sponsoring_ids: Vec<Option<PublicKey>>,
+    /// Sequence number extension, if present.
+    pub v3: Option<AccountEntryExtensionV3>,
+}
+
+/// Account extension with the ledger and time of the last sequence bump.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountEntryExtensionV3 {
+    /// Ledger sequence number of the last sequence bump.
+    pub seq_ledger: u32,
+    /// Close time of the last sequence bump.
+    pub seq_time: u64,
+}
+
+/// Buying and selling liabilities.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Liabilities {
+    /// Liabilities of buy offers.
+    pub buying: Stroops,
+    /// Liabilities of sell offers.
+    pub selling: Stroops,
+}
+
+/// Account signing thresholds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thresholds {
+    /// Weight of the master key.
+    pub master_weight: u8,
+    /// Low threshold.
+    pub low: u8,
+    /// Medium threshold.
+    pub medium: u8,
+    /// High threshold.
+    pub high: u8,
+}
+
+/// An additional signer of an account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountSigner {
+    /// The signer key.
+    pub key: SignerKey,
+    /// The signer weight.
+    pub weight: u32,
+}
+
+/// The key of an account signer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignerKey {
+    /// An ed25519 public key.
+    Ed25519([u8; 32]),
+    /// The hash of a pre-authorized transaction.
+    PreAuthTx([u8; 32]),
+    /// The sha256 hash of a preimage.
+    HashX([u8; 32]),
+    /// An ed25519 public key that signs `payload`.
+    Ed25519SignedPayload {
+        /// The ed25519 public key.
+        key: [u8; 32],
+        /// The signed payload, at most 64 bytes.
+        payload: Vec<u8>,
+    },
+}
+
+impl SignerKey {
+    /// Return the strkey representation, e.g. `G...` for ed25519 keys.
+    ///
+    /// Fails for a signed payload that is empty or longer than 64 bytes.
+    pub fn to_str_key(&self) -> Result<StrKey> {
+        match *self {
+            SignerKey::Ed25519(key) => Ok(StrKey::AccountId(key)),
+            SignerKey::PreAuthTx(hash) => Ok(StrKey::PreAuthTx(hash)),
+            SignerKey::HashX(hash) => Ok(StrKey::Sha256Hash(hash)),
+            SignerKey::Ed25519SignedPayload { key, ref payload } => {
+                Ok(StrKey::SignedPayload(SignedPayload::new(key, payload.clone())?))
+            }
+        }
+    }
+}
+
+/// A trust line of an account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustLineEntry {
+    /// The account holding the trust line.
+    pub account_id: PublicKey,
+    /// The trusted asset.
+    pub asset: TrustLineAsset,
+    /// Balance in stroops.
+    pub balance: Stroops,
+    /// Maximum balance in stroops.
+    pub limit: Stroops,
+    /// Trust line flags, see the `AUTHORIZED*` constants.
+    pub flags: u32,
+    /// Liabilities extension, if present.
+    pub extension: Option<TrustLineEntryExtensionV1>,
+}
+
+impl TrustLineEntry {
+    /// The issuer authorized the account to hold the asset.
+    pub const AUTHORIZED: u32 = 0x1;
+    /// The account can maintain its offers but not create new ones.
+    pub const AUTHORIZED_TO_MAINTAIN_LIABILITIES: u32 = 0x2;
+    /// The issuer can claw back the asset.
+    pub const CLAWBACK_ENABLED: u32 = 0x4;
+}
+
+/// Trust line extension with liabilities.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustLineEntryExtensionV1 {
+    /// Buying and selling liabilities.
+    pub liabilities: Liabilities,
+    /// Number of liquidity pools using the trust line, if present.
+    pub liquidity_pool_use_count: Option<i32>,
+}
+
+/// The asset of a trust line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrustLineAsset {
+    /// The native asset, never held in a trust line but valid in keys.
+    Native,
+    /// A non-native asset.
+    Credit(CreditAsset),
+    /// Shares of the liquidity pool with the given id.
+    PoolShare([u8; 32]),
+}
+
+impl From<Asset> for TrustLineAsset {
+    fn from(asset: Asset) -> TrustLineAsset {
+        match asset {
+            Asset::Native => TrustLineAsset::Native,
+            Asset::Credit(credit) => TrustLineAsset::Credit(credit),
+        }
+    }
+}
+
+/// An offer on the decentralized exchange.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfferEntry {
+    /// The account that created the offer.
+    pub seller_id: PublicKey,
+    /// The offer id.
+    pub offer_id: i64,
+    /// The asset sold.
+    pub selling: Asset,
+    /// The asset bought.
+    pub buying: Asset,
+    /// Amount of `selling` for sale.
+    pub amount: Stroops,
+    /// Price of 1 unit of `selling` in terms of `buying`.
+    pub price: Price,
+    /// Offer flags, see `PASSIVE`.
+    pub flags: u32,
+}
+
+impl OfferEntry {
+    /// The offer does not take offers with the same price.
+    pub const PASSIVE: u32 = 0x1;
+}
+
+/// A data entry of an account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataEntry {
+    /// The account holding the data entry.
+    pub account_id: PublicKey,
+    /// The name, at most 64 bytes.
+    pub name: String,
+    /// The value, at most 64 bytes.
+    pub value: Vec<u8>,
+}
+
+/// A claimable balance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClaimableBalanceEntry {
+    /// The claimable balance id.
+    pub balance_id: [u8; 32],
+    /// Accounts that can claim the balance, at most 10.
+    pub claimants: Vec<Claimant>,
+    /// The asset claimed.
+    pub asset: Asset,
+    /// Amount claimed in stroops.
+    pub amount: Stroops,
+    /// Claimable balance flags, present with the v1 extension.
+    pub flags: Option<u32>,
+}
+
+impl ClaimableBalanceEntry {
+    /// The issuer can claw back the balance.
+    pub const CLAWBACK_ENABLED: u32 = 0x1;
+}
+
+/// An account that can claim a claimable balance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Claimant {
+    /// The account that can claim.
+    pub destination: PublicKey,
+    /// When the account can claim.
+    pub predicate: ClaimPredicate,
+}
+
+/// The condition under which a claimant can claim a balance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClaimPredicate {
+    /// Always.
+    Unconditional,
+    /// When both predicates hold.
+    And(Box<ClaimPredicate>, Box<ClaimPredicate>),
+    /// When either predicate holds.
+    Or(Box<ClaimPredicate>, Box<ClaimPredicate>),
+    /// When the predicate does not hold.
+    Not(Box<ClaimPredicate>),
+    /// Before the unix timestamp.
+    BeforeAbsoluteTime(i64),
+    /// Before this many seconds after the balance creation.
+    BeforeRelativeTime(i64),
+}
+
+/// A constant product liquidity pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiquidityPoolEntry {
+    /// The liquidity pool id.
+    pub liquidity_pool_id: [u8; 32],
+    /// The first asset of the pool.
+    pub asset_a: Asset,
+    /// The second asset of the pool.
+    pub asset_b: Asset,
+    /// Fee in basis points.
+    pub fee: i32,
+    /// Reserve of `asset_a` in stroops.
+    pub reserve_a: Stroops,
+    /// Reserve of `asset_b` in stroops.
+    pub reserve_b: Stroops,
+    /// Total pool shares in stroops.
+    pub total_pool_shares: Stroops,
+    /// Number of trust lines holding pool shares.
+    pub pool_shares_trust_line_count: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DataEntry, LedgerEntryData, LedgerKey, SignerKey};
+    use crypto::keypair::from_account_id;
+
+    #[test]
+    fn test_data_ledger_key() {
+        let account_id =
+            from_account_id("GCEAKB6W342KSAQ6SVJYROF5W5FJTPZDDOSIOT3Y6CNQ3U2ZBAH7AQN3").unwrap();
+        let data = LedgerEntryData::Data(DataEntry {
+            account_id,
+            name: "config".to_string(),
+            value: vec![1, 2, 3],
+        });
+        assert_eq!(
+            data.ledger_key(),
+            LedgerKey::Data {
+                account_id,
+                name: "config".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_signer_key_str_key() {
+        let account_id =
+            from_account_id("GCEAKB6W342KSAQ6SVJYROF5W5FJTPZDDOSIOT3Y6CNQ3U2ZBAH7AQN3").unwrap();
+        let key = SignerKey::Ed25519(account_id.to_bytes());
+        assert_eq!(
+            key.to_str_key().unwrap().to_string(),
+            "GCEAKB6W342KSAQ6SVJYROF5W5FJTPZDDOSIOT3Y6CNQ3U2ZBAH7AQN3"
+        );
+        let key = SignerKey::Ed25519SignedPayload {
+            key: account_id.to_bytes(),
+            payload: Vec::new(),
+        };
+        assert!(key.to_str_key().is_err());
+    }
+}
@@ -0,0 +1,85 @@
+use amount::Stroops;
+use crypto;
+use ed25519_dalek::PublicKey;
+use error::Result;
+use xdr::ToXdr;
+
+/// Maximum number of upgrades in a `StellarValue`.
+pub const MAX_UPGRADES: usize = 6;
+
+/// The header of a closed ledger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerHeader {
+    /// Protocol version of the ledger.
+    pub ledger_version: u32,
+    /// Hash of the previous ledger header.
+    pub previous_ledger_hash: [u8; 32],
+    /// The value agreed on by consensus.
+    pub scp_value: StellarValue,
+    /// Hash of the transaction result set of the ledger.
+    pub tx_set_result_hash: [u8; 32],
+    /// Hash of the bucket list.
+    pub bucket_list_hash: [u8; 32],
+    /// The ledger sequence number.
+    pub ledger_seq: u32,
+    /// Total number of stroops in existence.
+    pub total_coins: Stroops,
+    /// Fees burned since the last inflation run.
+    pub fee_pool: Stroops,
+    /// Number of inflation runs.
+    pub inflation_seq: u32,
+    /// Last used offer and claimable balance id.
+    pub id_pool: u64,
+    /// Base fee per operation in stroops.
+    pub base_fee: u32,
+    /// Base reserve per entry in stroops.
+    pub base_reserve: u32,
+    /// Maximum number of operations in a transaction set.
+    pub max_tx_set_size: u32,
+    /// Hashes of older ledgers, to speed up catching up.
+    pub skip_list: [[u8; 32]; 4],
+    /// Ledger flags, if the extension is present.
+    pub flags: Option<u32>,
+}
+
+impl LedgerHeader {
+    /// Return the ledger hash, the sha256 hash of the XDR encoded header.
+    pub fn hash(&self) -> Result<[u8; 32]> {
+        let mut buf = Vec::new();
+        self.to_writer(&mut buf)?;
+        let mut hash = [0; 32];
+        hash.copy_from_slice(&crypto::hash(&buf));
+        Ok(hash)
+    }
+}
+
+/// The value agreed on by consensus for a ledger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StellarValue {
+    /// Hash of the transaction set.
+    pub tx_set_hash: [u8; 32],
+    /// Close time as a unix timestamp.
+    pub close_time: u64,
+    /// XDR encoded `LedgerUpgrade`s, at most `MAX_UPGRADES` of 128 bytes.
+    pub upgrades: Vec<Vec<u8>>,
+    /// Signature of the validator that nominated the value, if signed.
+    pub signature: Option<LedgerCloseValueSignature>,
+}
+
+/// Signature of a validator over a `StellarValue`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerCloseValueSignature {
+    /// The validator.
+    pub node_id: PublicKey,
+    /// The signature, at most 64 bytes.
+    pub signature: Vec<u8>,
+}
+
+/// A ledger header with its hash, as found in history archive ledger files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerHeaderHistoryEntry {
+    /// The recorded hash of `header`.
+    pub hash: [u8; 32],
+    /// The header.
+    pub header: LedgerHeader,
+}
@@ -19,11 +19,11 @@
 //!
 //! ```ignore
 //! let random_keypair = KeyPair::random().unwrap();
-//! let keypair = KeyPair::from_secret("SDFRU2NGDPXYIY67BVS6L6W4OY33HCFCEJQ73TZZPR3IDYVVI7BVPV5Q").unwrap();
+//! let keypair = KeyPair::from_secret_seed("SDFRU2NGDPXYIY67BVS6L6W4OY33HCFCEJQ73TZZPR3IDYVVI7BVPV5Q").unwrap();
 //! let public_key = keypair.public_key();
 //!
 //! // Create public key only
-//! let address = PublicKey::from_account_id("GBR6A7TTX6MUYO6WZXZFAX3L2QSLYIHIGKN52EBNVKKB4AN4B6CRD22T").unwrap();
+//! let address = keypair::from_account_id("GBR6A7TTX6MUYO6WZXZFAX3L2QSLYIHIGKN52EBNVKKB4AN4B6CRD22T").unwrap();
 //!
 //! // Create account
 //! let account = Account::new(address, 0);
@@ -52,32 +52,58 @@
 //! of the transaction envelope and submit it to the network.
 //! Alternatively, you can inspect it in the [Stellar Laboraty](https://www.stellar.org/laboratory/).
 //!
+//! The transaction sequence number comes from a `SequenceProvider`: a
+//! `&mut Account`, a `SharedSequence` counter, or an explicit `FixedSequence`.
+//!
 //! ```ignore
 //! let tx = TransactionBuilder::new(&mut source_account)
 //!     .operation(
 //!         OperationBuilder::payment(destination_address, asset, amount).build()
 //!     )
 //!     .with_memo(memo)
-//!     .build();
-//! let signed_tx = tx.sign(&source_keypair, &network).unwrap();
+//!     .build()
+//!     .unwrap();
+//! let signed_tx = tx.sign(source_keypair.inner(), &network).unwrap();
 //! let encoded = signed_tx.to_base64().unwrap();
 //!
 //! // You can decode a transaction as well
 //! let new_signed_tx = SignedTransaction::from_base64(&encode).unwrap();
 //! ```
+#[cfg(not(any(feature = "sodium", feature = "pure-rust")))]
+compile_error!("one of the features `sodium` or `pure-rust` must be enabled");
+
 extern crate base32;
 extern crate base64;
 extern crate bigdecimal;
 extern crate byteorder;
 extern crate crc16;
+extern crate flate2;
 extern crate num_bigint;
 extern crate num_traits;
+#[cfg(all(feature = "sodium", not(feature = "pure-rust")))]
 extern crate sodiumoxide;
+#[cfg(feature = "pure-rust")]
+extern crate crypto_box;
+#[cfg(feature = "pure-rust")]
+extern crate crypto_secretbox;
+#[cfg(feature = "pure-rust")]
+extern crate getrandom;
+#[cfg(feature = "pure-rust")]
+extern crate hmac;
+#[cfg(feature = "pure-rust")]
+extern crate scrypt;
+#[cfg(feature = "pure-rust")]
+extern crate sha2;
 extern crate curve25519_dalek;
 extern crate ed25519_dalek;
+extern crate subtle;
+extern crate zeroize;
+extern crate bip39;
+extern crate regex;
 
 extern crate serde;
 extern crate serde_bytes;
+extern crate serde_json;
 #[macro_use]
 extern crate serde_derive;
 extern crate serde_xdr;
@@ -89,22 +115,59 @@ pub mod crypto;
 mod amount;
 mod account;
 mod asset;
+mod balance_changes;
+mod batch_verifier;
+mod channel_pool;
+mod clock;
+mod history_archive;
+mod history_stream;
+mod ledger_chain;
+mod ledger_entry;
+mod ledger_header;
 mod memo;
 mod network;
 mod time_bounds;
 mod operation;
 mod operation_builder;
+mod sequence;
 mod signature;
+mod signer;
 mod transaction;
+mod transaction_batcher;
 mod transaction_builder;
+mod transaction_meta;
+mod transaction_result;
+mod vanity;
 
 mod xdr;
 
 pub use self::crypto::{init};
+pub use self::crypto::keypair::KeyPair;
+pub use self::crypto::strkey::{SignedPayload, StrKey, StrKeyKind};
 pub use self::error::{Error, Result};
 pub use self::amount::{Amount, Price, Stroops};
 pub use self::account::Account;
 pub use self::asset::{Asset, CreditAsset};
+pub use self::balance_changes::{BalanceAnalyzer, BalanceChange, BalanceChanges, SequenceChange};
+pub use self::batch_verifier::BatchVerifier;
+pub use self::channel_pool::{Channel, ChannelPool};
+pub use self::clock::{Clock, FixedClock, SystemClock};
+pub use self::ledger_entry::{AccountEntry, AccountEntryExtensionV1, AccountEntryExtensionV2,
+                             AccountEntryExtensionV3, AccountSigner, ClaimPredicate,
+                             ClaimableBalanceEntry, Claimant, DataEntry, LedgerEntry,
+                             LedgerEntryData, LedgerEntryExtensionV1, LedgerKey, Liabilities,
+                             LiquidityPoolEntry, OfferEntry, SignerKey, Thresholds,
+                             TrustLineAsset, TrustLineEntry, TrustLineEntryExtensionV1,
+                             MAX_SIGNERS};
+pub use self::history_archive::{bucket_path, checkpoint_containing, checkpoint_path,
+                                checkpoint_range, is_checkpoint, FutureBucket, HistoryArchive,
+                                HistoryArchiveState, HistoryBucketLevel, HistoryCategory,
+                                CHECKPOINT_FREQUENCY};
+pub use self::history_stream::{HistoryStream, RecordReader, ScpHistoryEntry,
+                               TransactionHistoryEntry, TransactionHistoryResultEntry};
+pub use self::ledger_chain::LedgerChainVerifier;
+pub use self::ledger_header::{LedgerCloseValueSignature, LedgerHeader, LedgerHeaderHistoryEntry,
+                              StellarValue, MAX_UPGRADES};
 pub use self::memo::Memo;
 pub use self::network::Network;
 pub use self::time_bounds::{TimeBounds, UnixTimestamp};
@@ -117,8 +180,38 @@ pub use self::operation_builder::{CreateAccountOperationBuilder,
                                   ManageDataOperationBuilder, ManageOfferOperationBuilder,
                                   OperationBuilder, PathPaymentOperationBuilder,
                                   PaymentOperationBuilder};
+pub use self::sequence::{FixedSequence, SequenceProvider, SharedSequence};
 pub use self::signature::{DecoratedSignature, SignatureHint};
+pub use self::signer::{serve, AsyncSigner, KeystoreSigner, SignFuture, Signer, StreamSigner,
+                       ThreadSigner};
 pub use self::transaction::{SignedTransaction, Transaction};
-pub use self::transaction_builder::TransactionBuilder;
+pub use self::transaction_batcher::TransactionBatcher;
+pub use self::transaction_builder::{TransactionBuilder, MAX_OPERATIONS};
+pub use self::transaction_meta::{LedgerEntryChange, OperationMeta, TransactionMeta};
+pub use self::transaction_result::{AccountMergeResult, AccountMergeResultCode,
+                                   AllowTrustResultCode, BeginSponsoringFutureReservesResultCode,
+                                   BumpSequenceResultCode, ChangeTrustResultCode, ClaimAtom,
+                                   ClaimClaimableBalanceResultCode, ClaimLiquidityAtom,
+                                   ClaimOfferAtom, ClawbackClaimableBalanceResultCode,
+                                   ClawbackResultCode, CreateAccountResultCode,
+                                   CreateClaimableBalanceResult,
+                                   CreateClaimableBalanceResultCode,
+                                   EndSponsoringFutureReservesResultCode,
+                                   ExtendFootprintTtlResultCode, InflationPayout, InflationResult,
+                                   InflationResultCode, InnerTransactionResult,
+                                   InnerTransactionResultPair, InvokeHostFunctionResult,
+                                   InvokeHostFunctionResultCode, LiquidityPoolDepositResultCode,
+                                   LiquidityPoolWithdrawResultCode, ManageDataResultCode,
+                                   ManageOfferEffect, ManageOfferResult, ManageOfferResultCode,
+                                   ManageOfferSuccessResult, OperationResult, OperationResultCode,
+                                   PathPaymentStrictReceiveResult,
+                                   PathPaymentStrictReceiveResultCode, PathPaymentStrictSendResult,
+                                   PathPaymentStrictSendResultCode, PathPaymentSuccessResult,
+                                   PaymentResultCode, RestoreFootprintResultCode,
+                                   RevokeSponsorshipResultCode, SetOptionsResultCode,
+                                   SetTrustLineFlagsResultCode, SimplePaymentResult,
+                                   TransactionResult, TransactionResultCode,
+                                   TransactionResultPair, TransactionResultSet};
+pub use self::vanity::{VanityPattern, VanityProgress, VanitySearch};
 
-pub use self::xdr::{FromXdr, ToXdr};
+pub use self::xdr::{DecodeLimits, FromXdr, ToXdr};
@@ -0,0 +1,145 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use ed25519_dalek::PublicKey;
+use account::Account;
+
+/// A source of sequence numbers for transactions.
+pub trait SequenceProvider {
+    /// Return the account the sequence numbers belong to.
+    fn account_id(&self) -> PublicKey;
+
+    /// Consume and return the sequence number of the next transaction.
+    fn next_sequence(&mut self) -> u64;
+}
+
+impl SequenceProvider for Account {
+    fn account_id(&self) -> PublicKey {
+        *Account::account_id(self)
+    }
+
+    fn next_sequence(&mut self) -> u64 {
+        self.increment_sequence()
+    }
+}
+
+impl<S: SequenceProvider + ?Sized> SequenceProvider for &mut S {
+    fn account_id(&self) -> PublicKey {
+        (**self).account_id()
+    }
+
+    fn next_sequence(&mut self) -> u64 {
+        (**self).next_sequence()
+    }
+}
+
+/// A sequence number counter that can be shared between threads.
+///
+/// Clones share the same counter.
+#[derive(Debug, Clone)]
+pub struct SharedSequence {
+    account_id: PublicKey,
+    sequence: Arc<AtomicU64>,
+}
+
+impl SharedSequence {
+    /// Create with `account_id` and current `sequence` number.
+    pub fn new(account_id: PublicKey, sequence: u64) -> SharedSequence {
+        SharedSequence {
+            account_id,
+            sequence: Arc::new(AtomicU64::new(sequence)),
+        }
+    }
+
+    /// Returns the current sequence number.
+    pub fn sequence(&self) -> u64 {
+        self.sequence.load(Ordering::SeqCst)
+    }
+}
+
+impl SequenceProvider for SharedSequence {
+    fn account_id(&self) -> PublicKey {
+        self.account_id
+    }
+
+    fn next_sequence(&mut self) -> u64 {
+        self.sequence.fetch_add(1, Ordering::SeqCst) + 1
+    }
+}
+
+/// An explicit sequence number, used as is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedSequence {
+    account_id: PublicKey,
+    sequence: u64,
+}
+
+impl FixedSequence {
+    /// Create with `account_id` and the transaction `sequence` number.
+    pub fn new(account_id: PublicKey, sequence: u64) -> FixedSequence {
+        FixedSequence {
+            account_id,
+            sequence,
+        }
+    }
+}
+
+impl SequenceProvider for FixedSequence {
+    fn account_id(&self) -> PublicKey {
+        self.account_id
+    }
+
+    fn next_sequence(&mut self) -> u64 {
+        self.sequence
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use Account;
+    use crypto::keypair::from_secret_seed;
+    use super::{FixedSequence, SequenceProvider, SharedSequence};
+
+    #[test]
+    fn test_account_sequence() {
+        fn next<S: SequenceProvider>(mut provider: S) -> u64 {
+            provider.next_sequence()
+        }
+
+        let kp = from_secret_seed("SDFRU2NGDPXYIY67BVS6L6W4OY33HCFCEJQ73TZZPR3IDYVVI7BVPV5Q")
+            .unwrap();
+        let mut account = Account::new(kp.public, 10);
+        assert_eq!(next(&mut account), 11);
+        assert_eq!(account.next_sequence(), 12);
+        assert_eq!(account.sequence(), 12);
+    }
+
+    #[test]
+    fn test_fixed_sequence() {
+        let kp = from_secret_seed("SDFRU2NGDPXYIY67BVS6L6W4OY33HCFCEJQ73TZZPR3IDYVVI7BVPV5Q")
+            .unwrap();
+        let mut seq = FixedSequence::new(kp.public, 42);
+        assert_eq!(seq.next_sequence(), 42);
+        assert_eq!(seq.account_id(), kp.public);
+    }
+
+    #[test]
+    fn test_shared_sequence() {
+        let kp = from_secret_seed("SDFRU2NGDPXYIY67BVS6L6W4OY33HCFCEJQ73TZZPR3IDYVVI7BVPV5Q")
+            .unwrap();
+        let seq = SharedSequence::new(kp.public, 0);
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let mut seq = seq.clone();
+                thread::spawn(move || (0..100).map(|_| seq.next_sequence()).collect::<Vec<_>>())
+            })
+            .collect();
+        let mut all: Vec<u64> = handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect();
+        all.sort();
+        assert_eq!(all, (1..401).collect::<Vec<_>>());
+        assert_eq!(seq.sequence(), 400);
+    }
+}
@@ -1,3 +1,6 @@
+use crypto;
+use error::{Error, Result};
+
 
 /// Last 4 bytes of a public key.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -19,17 +22,47 @@ impl SignatureHint {
     }
 }
 
-/// A `Signature` together with the last 4 bytes of the public key.
+/// Maximum length of a signature, including hash-x preimages.
+pub const MAX_SIGNATURE_LEN: usize = 64;
+
+/// A signature together with the last 4 bytes of the signer key.
+///
+/// The signature is usually an ed25519 signature, but can be the preimage
+/// of a hash-x signer.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DecoratedSignature {
     hint: SignatureHint,
-    signature: ed25519_dalek::Signature,
+    signature: Vec<u8>,
 }
 
 impl DecoratedSignature {
     /// Create a new `DecoratedSignature` with `hint` and `signature`.
     pub fn new(hint: SignatureHint, signature: ed25519_dalek::Signature) -> DecoratedSignature {
-        DecoratedSignature { hint, signature }
+        DecoratedSignature {
+            hint,
+            signature: signature.to_bytes().to_vec(),
+        }
+    }
+
+    /// Create a new `DecoratedSignature` with `hint` and the raw `signature`.
+    ///
+    /// Return `Error::InvalidSignature` if the signature is longer than 64 bytes.
+    pub fn from_bytes(hint: SignatureHint, signature: &[u8]) -> Result<DecoratedSignature> {
+        if signature.len() > MAX_SIGNATURE_LEN {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(DecoratedSignature {
+            hint,
+            signature: signature.to_vec(),
+        })
+    }
+
+    /// Create the signature of a hash-x signer, revealing its `preimage`.
+    pub fn hash_x(preimage: &[u8]) -> Result<DecoratedSignature> {
+        let hash = crypto::hash(preimage);
+        let mut hint = [0; 4];
+        hint.copy_from_slice(&hash[hash.len() - 4..]);
+        DecoratedSignature::from_bytes(SignatureHint(hint), preimage)
     }
 
     /// Return the decorated signature `hint`.
@@ -37,8 +70,40 @@ impl DecoratedSignature {
         &self.hint
     }
 
-    /// Return the decorated signature `signature`.
-    pub fn signature(&self) -> &ed25519_dalek::Signature {
+    /// Return the raw decorated signature.
+    pub fn signature(&self) -> &[u8] {
         &self.signature
     }
+
+    /// Return the ed25519 signature, or `None` if the signature is not a
+    /// well formed ed25519 signature, e.g. a hash-x preimage.
+    pub fn ed25519_signature(&self) -> Option<ed25519_dalek::Signature> {
+        ed25519_dalek::Signature::from_bytes(&self.signature).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DecoratedSignature, SignatureHint};
+    use KeyPair;
+
+    #[test]
+    fn test_ed25519_signature() {
+        let keypair = KeyPair::random().unwrap();
+        let signature = keypair.sign(b"message");
+        let decorated = keypair.sign_decorated(b"message");
+        assert_eq!(decorated.signature(), &signature.to_bytes()[..]);
+        assert_eq!(decorated.ed25519_signature(), Some(signature));
+    }
+
+    #[test]
+    fn test_hash_x() {
+        // sha256("abc") ends with f20015ad.
+        let decorated = DecoratedSignature::hash_x(b"abc").unwrap();
+        assert_eq!(*decorated.hint(), SignatureHint([0xf2, 0x00, 0x15, 0xad]));
+        assert_eq!(decorated.signature(), b"abc");
+        assert_eq!(decorated.ed25519_signature(), None);
+        assert!(DecoratedSignature::hash_x(&[0; 64]).is_ok());
+        assert!(DecoratedSignature::hash_x(&[0; 65]).is_err());
+    }
 }
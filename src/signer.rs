@@ -0,0 +1,591 @@
+use std::fmt;
+use std::future::{self, Future};
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::path::Path;
+use std::pin::Pin;
+use std::process::{Child, ChildStdin, ChildStdout};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use base64;
+use ed25519_dalek::{self, PublicKey};
+use serde_json;
+use zeroize::Zeroize;
+use crypto::keypair::{self, KeyPair};
+use crypto::keystore::Keystore;
+use error::{Error, Result};
+use signature::{DecoratedSignature, SignatureHint};
+
+/// A key that signs transaction hashes.
+pub trait Signer {
+    /// Return the public key of the signing key.
+    fn public_key(&self) -> PublicKey;
+
+    /// Sign the transaction `hash`.
+    fn sign_hash(&self, hash: &[u8]) -> Result<DecoratedSignature>;
+}
+
+/// The future returned by `AsyncSigner::sign_hash_async`.
+pub type SignFuture<'a> = Pin<Box<dyn Future<Output = Result<DecoratedSignature>> + Send + 'a>>;
+
+/// A key that signs transaction hashes asynchronously.
+///
+/// Every `Signer` that is `Sync` is also an `AsyncSigner`, that signs when the
+/// future is first polled: the call blocks the polling thread. Wrap signers
+/// that do I/O, e.g. a `StreamSigner`, in a `ThreadSigner` instead.
+pub trait AsyncSigner {
+    /// Return the public key of the signing key.
+    fn public_key(&self) -> PublicKey;
+
+    /// Sign the transaction `hash`.
+    fn sign_hash_async<'a>(&'a self, hash: &'a [u8]) -> SignFuture<'a>;
+}
+
+impl<S: Signer + Sync> AsyncSigner for S {
+    fn public_key(&self) -> PublicKey {
+        Signer::public_key(self)
+    }
+
+    fn sign_hash_async<'a>(&'a self, hash: &'a [u8]) -> SignFuture<'a> {
+        Box::pin(future::poll_fn(move |_| Poll::Ready(self.sign_hash(hash))))
+    }
+}
+
+impl Signer for ed25519_dalek::Keypair {
+    fn public_key(&self) -> PublicKey {
+        self.public
+    }
+
+    fn sign_hash(&self, hash: &[u8]) -> Result<DecoratedSignature> {
+        Ok(keypair::sign_decorated(self, hash))
+    }
+}
+
+impl Signer for KeyPair {
+    fn public_key(&self) -> PublicKey {
+        *KeyPair::public_key(self)
+    }
+
+    fn sign_hash(&self, hash: &[u8]) -> Result<DecoratedSignature> {
+        Ok(self.sign_decorated(hash))
+    }
+}
+
+/// Sign with a key stored in a `Keystore`.
+///
+/// The key is decrypted for each signature and never kept in memory, at the
+/// price of running the password key derivation every time.
+pub struct KeystoreSigner {
+    keystore: Keystore,
+    public_key: PublicKey,
+    password: String,
+}
+
+impl KeystoreSigner {
+    /// Create the signer, checking that `password` decrypts the `keystore`.
+    pub fn new(keystore: Keystore, password: &str) -> Result<KeystoreSigner> {
+        let public_key = *keystore.decrypt_keypair(password)?.public_key();
+        Ok(KeystoreSigner {
+            keystore,
+            public_key,
+            password: password.to_string(),
+        })
+    }
+}
+
+impl Signer for KeystoreSigner {
+    fn public_key(&self) -> PublicKey {
+        self.public_key
+    }
+
+    fn sign_hash(&self, hash: &[u8]) -> Result<DecoratedSignature> {
+        let keypair = self.keystore.decrypt_keypair(&self.password)?;
+        Ok(keypair.sign_decorated(hash))
+    }
+}
+
+impl fmt::Debug for KeystoreSigner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("KeystoreSigner")
+            .field("public_key", &self.keystore.public_key())
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+impl Drop for KeystoreSigner {
+    fn drop(&mut self) {
+        self.password.zeroize();
+    }
+}
+
+/// Request sent to an out-of-process signer, one JSON object per line.
+#[derive(Debug, Serialize, Deserialize)]
+struct SignRequest {
+    public_key: String,
+    hash: String,
+}
+
+/// Response of an out-of-process signer, one JSON object per line.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SignResponse {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Sign with an out-of-process signer, e.g. a daemon backed by an HSM.
+///
+/// The signer receives a line with `{"public_key": "G...", "hash": "<base64>"}`
+/// and answers with a line containing either `{"signature": "<base64>"}` or
+/// `{"error": "<message>"}`. The returned signature is verified before use.
+/// See `serve` for the other end of the protocol.
+pub struct StreamSigner<R, W> {
+    public_key: PublicKey,
+    stream: Mutex<(R, W)>,
+}
+
+impl<R: BufRead, W: Write> StreamSigner<R, W> {
+    /// Create the signer for `public_key`, reading responses from `reader`
+    /// and writing requests to `writer`.
+    pub fn new(public_key: PublicKey, reader: R, writer: W) -> StreamSigner<R, W> {
+        StreamSigner {
+            public_key,
+            stream: Mutex::new((reader, writer)),
+        }
+    }
+
+    fn request(&self, hash: &[u8]) -> Result<Vec<u8>> {
+        let request = SignRequest {
+            public_key: keypair::account_id(&self.public_key)?,
+            hash: base64::encode(hash),
+        };
+        let mut stream = self.stream.lock().map_err(|_| Error::SignerError)?;
+        let (ref mut reader, ref mut writer) = *stream;
+        writeln!(writer, "{}", serde_json::to_string(&request)?)?;
+        writer.flush()?;
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(Error::SignerError);
+        }
+        let response: SignResponse = serde_json::from_str(&line)?;
+        match response.signature {
+            Some(ref signature) if response.error.is_none() => Ok(base64::decode(signature)?),
+            _ => Err(Error::SignerError),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl StreamSigner<BufReader<UnixStream>, UnixStream> {
+    /// Connect to the signer listening on the Unix socket at `path`.
+    pub fn connect<P: AsRef<Path>>(
+        path: P,
+        public_key: PublicKey,
+    ) -> Result<StreamSigner<BufReader<UnixStream>, UnixStream>> {
+        let writer = UnixStream::connect(path)?;
+        let reader = BufReader::new(writer.try_clone()?);
+        Ok(StreamSigner::new(public_key, reader, writer))
+    }
+}
+
+impl StreamSigner<BufReader<ChildStdout>, ChildStdin> {
+    /// Talk to the signer `child` process over its stdin and stdout, which
+    /// must be piped.
+    pub fn from_child(
+        child: &mut Child,
+        public_key: PublicKey,
+    ) -> Result<StreamSigner<BufReader<ChildStdout>, ChildStdin>> {
+        let writer = child.stdin.take().ok_or(Error::SignerError)?;
+        let reader = child.stdout.take().ok_or(Error::SignerError)?;
+        Ok(StreamSigner::new(public_key, BufReader::new(reader), writer))
+    }
+}
+
+impl<R: BufRead, W: Write> Signer for StreamSigner<R, W> {
+    fn public_key(&self) -> PublicKey {
+        self.public_key
+    }
+
+    fn sign_hash(&self, hash: &[u8]) -> Result<DecoratedSignature> {
+        let bytes = self.request(hash)?;
+        let signature =
+            ed25519_dalek::Signature::from_bytes(&bytes).map_err(|_| Error::SignerError)?;
+        self.public_key
+            .verify(hash, &signature)
+            .map_err(|_| Error::SignerError)?;
+        let hint = SignatureHint::from_public_key(&self.public_key);
+        Ok(DecoratedSignature::new(hint, signature))
+    }
+}
+
+impl<R, W> fmt::Debug for StreamSigner<R, W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StreamSigner")
+            .field("public_key", &self.public_key)
+            .finish()
+    }
+}
+
+/// Sign asynchronously with a blocking `Signer` running on its own thread.
+///
+/// Requests are signed one at a time, in order, and the futures complete once
+/// the thread has signed, so slow signers such as a `StreamSigner` talking to
+/// an HSM daemon never block the executor. The thread exits when the
+/// `ThreadSigner` is dropped and the pending requests are signed.
+pub struct ThreadSigner {
+    public_key: PublicKey,
+    requests: Mutex<Sender<SignJob>>,
+}
+
+impl ThreadSigner {
+    /// Move `signer` to a new thread.
+    pub fn new<S: Signer + Send + 'static>(signer: S) -> Result<ThreadSigner> {
+        let public_key = signer.public_key();
+        let (requests, jobs) = mpsc::channel::<SignJob>();
+        thread::Builder::new()
+            .name("shuttle-signer".to_string())
+            .spawn(move || {
+                for job in jobs {
+                    let signature = signer.sign_hash(&job.hash);
+                    job.complete(signature);
+                }
+            })?;
+        Ok(ThreadSigner {
+            public_key,
+            requests: Mutex::new(requests),
+        })
+    }
+}
+
+impl AsyncSigner for ThreadSigner {
+    fn public_key(&self) -> PublicKey {
+        self.public_key
+    }
+
+    fn sign_hash_async<'a>(&'a self, hash: &'a [u8]) -> SignFuture<'a> {
+        let slot = Arc::new(Mutex::new(SignSlot::default()));
+        let job = SignJob {
+            hash: hash.to_vec(),
+            slot: slot.clone(),
+        };
+        // A job that is not sent completes with an error when dropped.
+        if let Ok(requests) = self.requests.lock() {
+            let _ = requests.send(job);
+        }
+        Box::pin(SignReceiver { slot })
+    }
+}
+
+impl fmt::Debug for ThreadSigner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ThreadSigner")
+            .field("public_key", &self.public_key)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+struct SignSlot {
+    signature: Option<Result<DecoratedSignature>>,
+    waker: Option<Waker>,
+}
+
+struct SignJob {
+    hash: Vec<u8>,
+    slot: Arc<Mutex<SignSlot>>,
+}
+
+impl SignJob {
+    fn complete(&self, signature: Result<DecoratedSignature>) {
+        if let Ok(mut slot) = self.slot.lock() {
+            if slot.signature.is_none() {
+                slot.signature = Some(signature);
+                if let Some(waker) = slot.waker.take() {
+                    waker.wake();
+                }
+            }
+        }
+    }
+}
+
+impl Drop for SignJob {
+    // Fail the request if the signer thread is gone or panicked.
+    fn drop(&mut self) {
+        self.complete(Err(Error::SignerError));
+    }
+}
+
+struct SignReceiver {
+    slot: Arc<Mutex<SignSlot>>,
+}
+
+impl Future for SignReceiver {
+    type Output = Result<DecoratedSignature>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<DecoratedSignature>> {
+        let mut slot = match self.slot.lock() {
+            Ok(slot) => slot,
+            Err(_) => return Poll::Ready(Err(Error::SignerError)),
+        };
+        match slot.signature.take() {
+            Some(signature) => Poll::Ready(signature),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Answer the requests of a `StreamSigner` with `signer`, until `reader` is closed.
+///
+/// Useful to run a signer in a separate process, or as a local stub.
+pub fn serve<S, R, W>(signer: &S, reader: R, mut writer: W) -> Result<()>
+where
+    S: Signer + ?Sized,
+    R: BufRead,
+    W: Write,
+{
+    let account_id = keypair::account_id(&signer.public_key())?;
+    for line in reader.lines() {
+        let line = line?;
+        let response = match serde_json::from_str::<SignRequest>(&line) {
+            Err(err) => SignResponse {
+                error: Some(err.to_string()),
+                ..Default::default()
+            },
+            Ok(ref request) if request.public_key != account_id => SignResponse {
+                error: Some("unknown public key".to_string()),
+                ..Default::default()
+            },
+            Ok(request) => match base64::decode(&request.hash)
+                .map_err(Error::from)
+                .and_then(|hash| signer.sign_hash(&hash))
+            {
+                Ok(signature) => SignResponse {
+                    signature: Some(base64::encode(signature.signature())),
+                    ..Default::default()
+                },
+                Err(err) => SignResponse {
+                    error: Some(format!("{:?}", err)),
+                    ..Default::default()
+                },
+            },
+        };
+        writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::Future;
+    use std::io::{BufReader, Cursor};
+    use std::sync::mpsc::{self, Receiver};
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::thread::{self, Thread};
+    use ed25519_dalek::PublicKey;
+    use super::{serve, AsyncSigner, KeystoreSigner, Signer, StreamSigner, ThreadSigner};
+    use crypto::keystore::Keystore;
+    use error::{Error, Result};
+    use signature::DecoratedSignature;
+    use {Account, KeyPair, Network, OperationBuilder, TransactionBuilder};
+
+    const SEED: &str = "SDFRU2NGDPXYIY67BVS6L6W4OY33HCFCEJQ73TZZPR3IDYVVI7BVPV5Q";
+    const HASH: [u8; 32] = [7; 32];
+
+    struct NoopWake;
+
+    impl Wake for NoopWake {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    struct ThreadWake(Thread);
+
+    impl Wake for ThreadWake {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Waker::from(Arc::new(ThreadWake(thread::current())));
+        let mut context = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        loop {
+            match future.as_mut().poll(&mut context) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    // Signs once the gate is opened.
+    struct GatedSigner {
+        keypair: KeyPair,
+        gate: Mutex<Receiver<()>>,
+    }
+
+    impl Signer for GatedSigner {
+        fn public_key(&self) -> PublicKey {
+            *self.keypair.public_key()
+        }
+
+        fn sign_hash(&self, hash: &[u8]) -> Result<DecoratedSignature> {
+            self.gate.lock().unwrap().recv().map_err(|_| Error::SignerError)?;
+            self.keypair.sign_hash(hash)
+        }
+    }
+
+    #[test]
+    fn test_keypair_signer() {
+        let keypair = KeyPair::from_secret_seed(SEED).unwrap();
+        let signature = keypair.sign_hash(&HASH).unwrap();
+        assert_eq!(*signature.hint(), keypair.signature_hint());
+        assert!(keypair.verify(&HASH, &signature.ed25519_signature().unwrap()));
+        let inner_signature = keypair.inner().sign_hash(&HASH).unwrap();
+        assert_eq!(inner_signature, signature);
+    }
+
+    #[test]
+    fn test_sign_transaction_with() {
+        let keypair = KeyPair::from_secret_seed(SEED).unwrap();
+        let mut account = Account::new(*keypair.public_key(), 999);
+        let tx = TransactionBuilder::new(&mut account)
+            .operation(OperationBuilder::inflation().build())
+            .build()
+            .unwrap();
+        let network = Network::public_network();
+        let expected = tx.clone().sign(keypair.inner(), &network).unwrap();
+        let signed_tx = tx.sign_with(&keypair, &network).unwrap();
+        assert_eq!(signed_tx.signatures(), expected.signatures());
+    }
+
+    #[test]
+    fn test_async_signer() {
+        let keypair = KeyPair::from_secret_seed(SEED).unwrap();
+        let waker = Waker::from(Arc::new(NoopWake));
+        let mut context = Context::from_waker(&waker);
+        let mut future = keypair.sign_hash_async(&HASH);
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(signature) => {
+                assert_eq!(signature.unwrap(), keypair.sign_decorated(&HASH))
+            }
+            Poll::Pending => panic!("expected ready signature"),
+        }
+    }
+
+    #[test]
+    fn test_thread_signer() {
+        let keypair = KeyPair::from_secret_seed(SEED).unwrap();
+        let (open, gate) = mpsc::channel();
+        let signer = ThreadSigner::new(GatedSigner {
+            keypair: KeyPair::from_secret_seed(SEED).unwrap(),
+            gate: Mutex::new(gate),
+        }).unwrap();
+        assert_eq!(AsyncSigner::public_key(&signer), *keypair.public_key());
+
+        // The future is pending until the signer thread has signed.
+        let waker = Waker::from(Arc::new(NoopWake));
+        let mut context = Context::from_waker(&waker);
+        let mut future = signer.sign_hash_async(&HASH);
+        assert!(future.as_mut().poll(&mut context).is_pending());
+        open.send(()).unwrap();
+        assert_eq!(block_on(future).unwrap(), keypair.sign_decorated(&HASH));
+
+        // Errors of the signer are returned.
+        drop(open);
+        assert!(block_on(signer.sign_hash_async(&HASH)).is_err());
+    }
+
+    #[test]
+    fn test_thread_stream_signer() {
+        let keypair = KeyPair::from_secret_seed(SEED).unwrap();
+        let error = Cursor::new(b"{\"error\":\"unavailable\"}\n".to_vec());
+        let signer = StreamSigner::new(*keypair.public_key(), error, Vec::new());
+        let signer = ThreadSigner::new(signer).unwrap();
+        match block_on(signer.sign_hash_async(&HASH)) {
+            Err(Error::SignerError) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_keystore_signer() {
+        let keystore = Keystore::encrypt_seed(SEED, "password").unwrap();
+        assert!(KeystoreSigner::new(keystore.clone(), "wrong").is_err());
+        let signer = KeystoreSigner::new(keystore, "password").unwrap();
+        let keypair = KeyPair::from_secret_seed(SEED).unwrap();
+        assert_eq!(Signer::public_key(&signer), *keypair.public_key());
+        assert_eq!(signer.sign_hash(&HASH).unwrap(), keypair.sign_decorated(&HASH));
+        assert!(format!("{:?}", signer).contains("password: \"<redacted>\""));
+    }
+
+    #[test]
+    fn test_stream_signer() {
+        let keypair = KeyPair::from_secret_seed(SEED).unwrap();
+        let mut requests = Vec::new();
+        let request = format!(
+            "{{\"public_key\":\"{}\",\"hash\":\"{}\"}}\n",
+            keypair.account_id().unwrap(),
+            ::base64::encode(&HASH)
+        );
+        serve(&keypair, Cursor::new(request), &mut requests).unwrap();
+
+        let signer = StreamSigner::new(*keypair.public_key(), Cursor::new(requests), Vec::new());
+        assert_eq!(signer.sign_hash(&HASH).unwrap(), keypair.sign_decorated(&HASH));
+        // No more responses.
+        assert!(signer.sign_hash(&HASH).is_err());
+    }
+
+    #[test]
+    fn test_stream_signer_rejects_bad_signature() {
+        let keypair = KeyPair::from_secret_seed(SEED).unwrap();
+        let other = KeyPair::random().unwrap();
+        let mut responses = Vec::new();
+        let request = format!(
+            "{{\"public_key\":\"{}\",\"hash\":\"{}\"}}\n",
+            other.account_id().unwrap(),
+            ::base64::encode(&HASH)
+        );
+        serve(&other, Cursor::new(request), &mut responses).unwrap();
+        let signer = StreamSigner::new(*keypair.public_key(), Cursor::new(responses), Vec::new());
+        assert!(signer.sign_hash(&HASH).is_err());
+
+        let error = Cursor::new(b"{\"error\":\"unknown public key\"}\n".to_vec());
+        let signer = StreamSigner::new(*keypair.public_key(), error, Vec::new());
+        assert!(signer.sign_hash(&HASH).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_socket_signer() {
+        use std::os::unix::net::UnixStream;
+        use std::thread;
+
+        let keypair = KeyPair::from_secret_seed(SEED).unwrap();
+        let public_key = *keypair.public_key();
+        let (client, server) = UnixStream::pair().unwrap();
+        let handle = thread::spawn(move || {
+            let reader = BufReader::new(server.try_clone().unwrap());
+            serve(&keypair, reader, server).unwrap();
+        });
+        let reader = BufReader::new(client.try_clone().unwrap());
+        let signer = StreamSigner::new(public_key, reader, client);
+        for i in 0..3 {
+            let hash = [i; 32];
+            let signature = signer.sign_hash(&hash).unwrap();
+            assert!(public_key.verify(&hash, &signature.ed25519_signature().unwrap()).is_ok());
+        }
+        drop(signer);
+        handle.join().unwrap();
+    }
+}
@@ -1,7 +1,39 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use clock::Clock;
+
 /// Unix timestamp. Number of seconds since epoch.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct UnixTimestamp(pub i64);
 
+impl UnixTimestamp {
+    /// Create from `time`, truncating to the second.
+    pub fn from_system_time(time: SystemTime) -> UnixTimestamp {
+        match time.duration_since(UNIX_EPOCH) {
+            Ok(d) => UnixTimestamp(d.as_secs() as i64),
+            Err(e) => UnixTimestamp(-(e.duration().as_secs() as i64)),
+        }
+    }
+
+    /// Convert to `SystemTime`.
+    pub fn to_system_time(&self) -> SystemTime {
+        if self.0 >= 0 {
+            UNIX_EPOCH + Duration::from_secs(self.0 as u64)
+        } else {
+            UNIX_EPOCH - Duration::from_secs(self.0.wrapping_neg() as u64)
+        }
+    }
+
+    /// Return the timestamp `duration` after this one.
+    pub fn after(&self, duration: Duration) -> UnixTimestamp {
+        let secs = duration.as_secs();
+        if secs > i64::MAX as u64 {
+            UnixTimestamp(i64::MAX)
+        } else {
+            UnixTimestamp(self.0.saturating_add(secs as i64))
+        }
+    }
+}
+
 /// A time range for the validity of an operation.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TimeBounds {
@@ -18,6 +50,19 @@ impl TimeBounds {
         TimeBounds { lower, upper }
     }
 
+    /// Create new time bounds from `SystemTime`s.
+    pub fn from_system_time(lower: Option<SystemTime>, upper: Option<SystemTime>) -> TimeBounds {
+        let lower = lower.map(UnixTimestamp::from_system_time);
+        let upper = upper.map(UnixTimestamp::from_system_time);
+        TimeBounds::new(lower, upper)
+    }
+
+    /// Create time bounds valid from now until `timeout` has elapsed, according to `clock`.
+    pub fn timeout<C: Clock + ?Sized>(clock: &C, timeout: Duration) -> TimeBounds {
+        let upper = clock.now().after(timeout);
+        TimeBounds::new(None, Some(upper))
+    }
+
     /// The lower time bound.
     pub fn lower(&self) -> &Option<UnixTimestamp> {
         &self.lower
@@ -27,4 +72,66 @@ impl TimeBounds {
     pub fn upper(&self) -> &Option<UnixTimestamp> {
         &self.upper
     }
+
+    /// Return `true` if `ts` is within the time bounds. Both bounds are inclusive.
+    pub fn contains(&self, ts: &UnixTimestamp) -> bool {
+        let after_lower = match self.lower {
+            None => true,
+            Some(ref lower) => ts >= lower,
+        };
+        let before_upper = match self.upper {
+            None => true,
+            Some(ref upper) => ts <= upper,
+        };
+        after_lower && before_upper
+    }
+
+    /// Return `true` if `ts` is after the upper time bound.
+    pub fn is_expired_at(&self, ts: &UnixTimestamp) -> bool {
+        match self.upper {
+            None => false,
+            Some(ref upper) => ts > upper,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+    use super::{TimeBounds, UnixTimestamp};
+    use clock::FixedClock;
+
+    #[test]
+    fn test_contains() {
+        let bounds = TimeBounds::new(Some(UnixTimestamp(100)), Some(UnixTimestamp(200)));
+        assert!(!bounds.contains(&UnixTimestamp(99)));
+        assert!(bounds.contains(&UnixTimestamp(100)));
+        assert!(bounds.contains(&UnixTimestamp(200)));
+        assert!(!bounds.contains(&UnixTimestamp(201)));
+
+        let unbounded = TimeBounds::new(None, None);
+        assert!(unbounded.contains(&UnixTimestamp(0)));
+        assert!(!unbounded.is_expired_at(&UnixTimestamp(i64::MAX)));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let clock = FixedClock::new(UnixTimestamp(1000));
+        let bounds = TimeBounds::timeout(&clock, Duration::from_secs(30));
+        assert_eq!(*bounds.lower(), None);
+        assert_eq!(*bounds.upper(), Some(UnixTimestamp(1030)));
+        assert!(!bounds.is_expired_at(&UnixTimestamp(1030)));
+        assert!(bounds.is_expired_at(&UnixTimestamp(1031)));
+    }
+
+    #[test]
+    fn test_from_system_time() {
+        let lower = UNIX_EPOCH + Duration::from_millis(1_000_500);
+        let bounds = TimeBounds::from_system_time(Some(lower), None);
+        assert_eq!(*bounds.lower(), Some(UnixTimestamp(1000)));
+        assert_eq!(
+            UnixTimestamp(1000).to_system_time(),
+            UNIX_EPOCH + Duration::from_secs(1000)
+        );
+    }
 }
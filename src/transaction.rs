@@ -1,4 +1,5 @@
 use amount::Stroops;
+use clock::Clock;
 use time_bounds::TimeBounds;
 use memo::Memo;
 use network::Network;
@@ -9,6 +10,7 @@ use error::Result;
 use xdr::ToXdr;
 use crypto;
 use crypto::keypair::sign_decorated;
+use signer::Signer;
 const BASE_FEE: Stroops = Stroops(100);
 
 /// A transaction containing operations that change the ledger state.
@@ -63,6 +65,16 @@ impl Transaction {
         &self.time_bounds
     }
 
+    /// Return `true` if the transaction upper time bound is in the past, according to `clock`.
+    ///
+    /// A transaction without an upper time bound never expires.
+    pub fn is_expired<C: Clock + ?Sized>(&self, clock: &C) -> bool {
+        match self.time_bounds {
+            None => false,
+            Some(ref time_bounds) => time_bounds.is_expired_at(&clock.now()),
+        }
+    }
+
     /// The memo attached to the transaction.
     pub fn memo(&self) -> &Memo {
         &self.memo
@@ -84,6 +96,18 @@ impl Transaction {
         sig.sign(keypair)?;
         Ok(sig)
     }
+
+    /// Sign the transaction with `signer`, returning a signed transaction that can be
+    /// submitted to the `network`.
+    pub fn sign_with<S: Signer + ?Sized>(
+        self,
+        signer: &S,
+        network: &Network,
+    ) -> Result<SignedTransaction> {
+        let mut sig = SignedTransaction::new(self, network)?;
+        sig.sign_with(signer)?;
+        Ok(sig)
+    }
 }
 
 /// A transaction that was signed.
@@ -124,6 +148,19 @@ impl SignedTransaction {
         Ok(())
     }
 
+    /// Add one more signature to the transaction, created by `signer`.
+    pub fn sign_with<S: Signer + ?Sized>(&mut self, signer: &S) -> Result<()> {
+        let payload = self.hash()?;
+        let new_signature = signer.sign_hash(&payload)?;
+        self.signatures.push(new_signature);
+        Ok(())
+    }
+
+    /// Add a signature of the transaction hash, e.g. one created by an `AsyncSigner`.
+    pub fn add_signature(&mut self, signature: DecoratedSignature) {
+        self.signatures.push(signature);
+    }
+
     /// Return the transaction hash, suitable for signing.
     pub fn hash(&self) -> Result<Vec<u8>> {
         let payload = self.signature_base()?;
@@ -0,0 +1,251 @@
+use account::Account;
+use error::{Error, Result};
+use memo::Memo;
+use operation::Operation;
+use time_bounds::TimeBounds;
+use transaction::{SignedTransaction, Transaction};
+use transaction_builder::{TransactionBuilder, MAX_OPERATIONS};
+use xdr::ToXdr;
+
+/// Size of a decorated signature in the transaction envelope.
+const DECORATED_SIGNATURE_SIZE: usize = 72;
+
+/// Split operations into multiple transactions with consecutive sequence numbers.
+///
+/// Operations can be grouped together, for example when they must be
+/// submitted atomically, in which case the batcher never splits a group
+/// across two transactions.
+#[derive(Debug)]
+pub struct TransactionBatcher<'a> {
+    source: &'a mut Account,
+    time_bounds: Option<TimeBounds>,
+    memo: Memo,
+    max_operations: usize,
+    max_envelope_size: Option<usize>,
+    signatures: usize,
+}
+
+impl<'a> TransactionBatcher<'a> {
+    /// Create a transaction batcher with `source` account.
+    pub fn new(source: &'a mut Account) -> TransactionBatcher<'a> {
+        TransactionBatcher {
+            source,
+            time_bounds: None,
+            memo: Memo::None,
+            max_operations: MAX_OPERATIONS,
+            max_envelope_size: None,
+            signatures: 1,
+        }
+    }
+
+    /// Set the time bounds of all transactions.
+    pub fn with_time_bounds(mut self, time_bounds: TimeBounds) -> Self {
+        self.time_bounds = Some(time_bounds);
+        self
+    }
+
+    /// Set the memo of all transactions.
+    pub fn with_memo(mut self, memo: Memo) -> Self {
+        self.memo = memo;
+        self
+    }
+
+    /// Set the maximum number of operations in each transaction.
+    ///
+    /// Values greater than `MAX_OPERATIONS` are capped.
+    pub fn with_max_operations(mut self, max_operations: usize) -> Self {
+        self.max_operations = max_operations.min(MAX_OPERATIONS);
+        self
+    }
+
+    /// Set the maximum size, in bytes, of each transaction envelope.
+    pub fn with_max_envelope_size(mut self, size: usize) -> Self {
+        self.max_envelope_size = Some(size);
+        self
+    }
+
+    /// Set the number of signatures each envelope is expected to carry. Defaults to 1.
+    pub fn with_signatures(mut self, signatures: usize) -> Self {
+        self.signatures = signatures;
+        self
+    }
+
+    /// Return the transactions containing `operations`.
+    pub fn batch<I>(self, operations: I) -> Result<Vec<Transaction>>
+    where
+        I: IntoIterator<Item = Operation>,
+    {
+        self.batch_groups(operations.into_iter().map(|op| vec![op]))
+    }
+
+    /// Return the transactions containing the operation `groups`.
+    ///
+    /// The operations of a group are always in the same transaction.
+    /// The source account sequence number is updated only if all
+    /// transactions are built successfully.
+    pub fn batch_groups<I, G>(self, groups: I) -> Result<Vec<Transaction>>
+    where
+        I: IntoIterator<Item = G>,
+        G: IntoIterator<Item = Operation>,
+    {
+        let mut account = self.source.clone();
+        let base_size = self.base_envelope_size(&account)?;
+        let max_size = self.max_envelope_size.unwrap_or(usize::MAX);
+
+        let mut transactions = Vec::new();
+        let mut current = Vec::new();
+        let mut current_size = base_size;
+        for group in groups {
+            let group: Vec<Operation> = group.into_iter().collect();
+            if group.is_empty() {
+                continue;
+            }
+            let mut group_size = 0;
+            for op in &group {
+                group_size += operation_size(op)?;
+            }
+            if group.len() > self.max_operations {
+                return Err(Error::TooManyOperations);
+            }
+            if base_size + group_size > max_size {
+                return Err(Error::EnvelopeTooLarge);
+            }
+            if current.len() + group.len() > self.max_operations
+                || current_size + group_size > max_size
+            {
+                let tx = self.build(&mut account, current)?;
+                transactions.push(tx);
+                current = Vec::new();
+                current_size = base_size;
+            }
+            current.extend(group);
+            current_size += group_size;
+        }
+        if !current.is_empty() {
+            let tx = self.build(&mut account, current)?;
+            transactions.push(tx);
+        }
+
+        *self.source = account;
+        Ok(transactions)
+    }
+
+    fn build(&self, account: &mut Account, operations: Vec<Operation>) -> Result<Transaction> {
+        let mut builder = TransactionBuilder::new(account).with_memo(self.memo.clone());
+        if let Some(ref time_bounds) = self.time_bounds {
+            builder = builder.with_time_bounds(time_bounds.clone());
+        }
+        for op in operations {
+            builder = builder.operation(op);
+        }
+        builder.build()
+    }
+
+    /// Size of a signed envelope without operations.
+    fn base_envelope_size(&self, account: &Account) -> Result<usize> {
+        let tx = Transaction::new(
+            *account.account_id(),
+            account.sequence(),
+            self.time_bounds.clone(),
+            self.memo.clone(),
+            Vec::new(),
+        );
+        let envelope = SignedTransaction::new_without_network(tx, Vec::new());
+        let mut buf = Vec::new();
+        envelope.to_writer(&mut buf)?;
+        Ok(buf.len() + self.signatures * DECORATED_SIGNATURE_SIZE)
+    }
+}
+
+fn operation_size(op: &Operation) -> Result<usize> {
+    let mut buf = Vec::new();
+    op.to_writer(&mut buf)?;
+    Ok(buf.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use {Account, Amount, Asset, Operation, OperationBuilder};
+    use {Network, ToXdr};
+    use crypto::keypair::from_secret_seed;
+    use super::TransactionBatcher;
+
+    fn payments(n: usize) -> Vec<Operation> {
+        let kp = from_secret_seed("SDFRU2NGDPXYIY67BVS6L6W4OY33HCFCEJQ73TZZPR3IDYVVI7BVPV5Q")
+            .unwrap();
+        (0..n)
+            .map(|_| {
+                OperationBuilder::payment(
+                    kp.public,
+                    Asset::native(),
+                    Amount::from_str("1.0").unwrap(),
+                ).build()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_batch_operation_limit() {
+        let kp = from_secret_seed("SDFRU2NGDPXYIY67BVS6L6W4OY33HCFCEJQ73TZZPR3IDYVVI7BVPV5Q")
+            .unwrap();
+        let mut account = Account::new(kp.public, 999);
+        let txs = TransactionBatcher::new(&mut account)
+            .batch(payments(250))
+            .unwrap();
+        assert_eq!(txs.len(), 3);
+        assert_eq!(txs[0].operations().len(), 100);
+        assert_eq!(txs[1].operations().len(), 100);
+        assert_eq!(txs[2].operations().len(), 50);
+        assert_eq!(txs[0].sequence(), 1000);
+        assert_eq!(txs[1].sequence(), 1001);
+        assert_eq!(txs[2].sequence(), 1002);
+        assert_eq!(account.sequence(), 1002);
+    }
+
+    #[test]
+    fn test_batch_keeps_groups_together() {
+        let kp = from_secret_seed("SDFRU2NGDPXYIY67BVS6L6W4OY33HCFCEJQ73TZZPR3IDYVVI7BVPV5Q")
+            .unwrap();
+        let mut account = Account::new(kp.public, 999);
+        let groups: Vec<Vec<Operation>> = (0..40).map(|_| payments(3)).collect();
+        let txs = TransactionBatcher::new(&mut account)
+            .batch_groups(groups)
+            .unwrap();
+        assert_eq!(txs.len(), 2);
+        assert_eq!(txs[0].operations().len(), 99);
+        assert_eq!(txs[1].operations().len(), 21);
+    }
+
+    #[test]
+    fn test_batch_envelope_size() {
+        let kp = from_secret_seed("SDFRU2NGDPXYIY67BVS6L6W4OY33HCFCEJQ73TZZPR3IDYVVI7BVPV5Q")
+            .unwrap();
+        let mut account = Account::new(kp.public, 999);
+        let max_size = 1000;
+        let txs = TransactionBatcher::new(&mut account)
+            .with_max_envelope_size(max_size)
+            .batch(payments(30))
+            .unwrap();
+        assert!(txs.len() > 1);
+        let network = Network::test_network();
+        for tx in txs {
+            let signed = tx.sign(&kp, &network).unwrap();
+            let mut buf = Vec::new();
+            signed.to_writer(&mut buf).unwrap();
+            assert!(buf.len() <= max_size);
+        }
+    }
+
+    #[test]
+    fn test_batch_group_too_large() {
+        let kp = from_secret_seed("SDFRU2NGDPXYIY67BVS6L6W4OY33HCFCEJQ73TZZPR3IDYVVI7BVPV5Q")
+            .unwrap();
+        let mut account = Account::new(kp.public, 999);
+        let res = TransactionBatcher::new(&mut account)
+            .with_max_operations(10)
+            .batch_groups(vec![payments(5), payments(11)]);
+        assert!(res.is_err());
+        assert_eq!(account.sequence(), 999);
+    }
+}
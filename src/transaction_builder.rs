@@ -1,24 +1,38 @@
-use account::Account;
+use std::time::Duration;
+use amount::{Amount, Price};
+use clock::{Clock, SystemClock};
 use time_bounds::TimeBounds;
 use memo::Memo;
 use transaction::Transaction;
 use operation::Operation;
+use sequence::SequenceProvider;
+use error::{Error, Result};
+
+/// Maximum number of operations in a single transaction.
+pub const MAX_OPERATIONS: usize = 100;
 
 /// `Transaction` builder.
+///
+/// The transaction source account and sequence number come from `source`,
+/// which can be a `&mut Account` or any other `SequenceProvider`.
 #[derive(Debug)]
-pub struct TransactionBuilder<'a> {
-    source: &'a mut Account,
+pub struct TransactionBuilder<S> {
+    source: S,
     time_bounds: Option<TimeBounds>,
+    timeout: Option<Duration>,
+    clock: Box<dyn Clock>,
     memo: Memo,
     operations: Vec<Operation>,
 }
 
-impl<'a> TransactionBuilder<'a> {
+impl<S: SequenceProvider> TransactionBuilder<S> {
     /// Create a transaction builder with `source` account.
-    pub fn new(source: &'a mut Account) -> TransactionBuilder<'a> {
+    pub fn new(source: S) -> TransactionBuilder<S> {
         TransactionBuilder {
-            source: source,
+            source,
             time_bounds: None,
+            timeout: None,
+            clock: Box::new(SystemClock),
             memo: Memo::None,
             operations: Vec::new(),
         }
@@ -30,6 +44,21 @@ impl<'a> TransactionBuilder<'a> {
         self
     }
 
+    /// Set the transaction upper time bound to `timeout` after the time
+    /// the transaction is built.
+    ///
+    /// The lower time bound, if any, is preserved.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the clock used to compute the timeout. Defaults to the system clock.
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
     /// Set the transaction memo.
     pub fn with_memo(mut self, memo: Memo) -> Self {
         self.memo = memo;
@@ -48,26 +77,110 @@ impl<'a> TransactionBuilder<'a> {
     }
 
     /// Return the transaction.
-    pub fn build(self) -> Transaction {
-        let keypair = self.source.account_id().clone();
-        let sequence = self.source.increment_sequence();
-        Transaction::new(
+    ///
+    /// Fails if the transaction would be rejected by the network, in which
+    /// case the source account sequence number is left untouched.
+    pub fn build(mut self) -> Result<Transaction> {
+        let time_bounds = match self.timeout {
+            None => self.time_bounds,
+            Some(timeout) => {
+                let upper = self.clock.now().after(timeout);
+                let lower = self.time_bounds.and_then(|t| t.lower().clone());
+                Some(TimeBounds::new(lower, Some(upper)))
+            }
+        };
+        validate_operations(&self.operations)?;
+        if let Some(ref time_bounds) = time_bounds {
+            validate_time_bounds(time_bounds)?;
+        }
+        let keypair = self.source.account_id();
+        let sequence = self.source.next_sequence();
+        Ok(Transaction::new(
             keypair,
             sequence,
-            self.time_bounds,
+            time_bounds,
             self.memo,
             self.operations,
-        )
+        ))
+    }
+}
+
+fn validate_time_bounds(time_bounds: &TimeBounds) -> Result<()> {
+    match (time_bounds.lower(), time_bounds.upper()) {
+        (Some(lower), Some(upper)) if lower > upper => Err(Error::InvalidTimeBounds),
+        _ => Ok(()),
+    }
+}
+
+fn validate_operations(operations: &[Operation]) -> Result<()> {
+    if operations.is_empty() {
+        return Err(Error::NoOperations);
+    }
+    if operations.len() > MAX_OPERATIONS {
+        return Err(Error::TooManyOperations);
+    }
+    for op in operations {
+        validate_operation(op)?;
+    }
+    Ok(())
+}
+
+fn validate_operation(op: &Operation) -> Result<()> {
+    match *op {
+        Operation::CreateAccount(ref op) => validate_positive_amount(&op.balance),
+        Operation::Payment(ref op) => validate_positive_amount(&op.amount),
+        Operation::PathPayment(ref op) => {
+            validate_positive_amount(&op.send_max)?;
+            validate_positive_amount(&op.dest_amount)
+        }
+        Operation::ManageOffer(ref op) => {
+            validate_non_negative_amount(&op.amount)?;
+            validate_price(&op.price)
+        }
+        Operation::CreatePassiveOffer(ref op) => {
+            validate_positive_amount(&op.amount)?;
+            validate_price(&op.price)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn validate_positive_amount(amount: &Amount) -> Result<()> {
+    if amount.as_stroops()?.0 > 0 {
+        Ok(())
+    } else {
+        Err(Error::InvalidAmount)
+    }
+}
+
+fn validate_non_negative_amount(amount: &Amount) -> Result<()> {
+    if amount.as_stroops()?.0 >= 0 {
+        Ok(())
+    } else {
+        Err(Error::InvalidAmount)
+    }
+}
+
+fn validate_price(price: &Price) -> Result<()> {
+    if price.numerator() > 0 && price.denominator() > 0 {
+        Ok(())
+    } else {
+        Err(Error::InvalidPrice)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use Account;
+    use std::str::FromStr;
+    use std::time::Duration;
+    use {FixedClock, FixedSequence, SharedSequence};
+    use {Account, Amount, Asset, Price};
+    use {TimeBounds, UnixTimestamp};
     use Memo;
     use TransactionBuilder;
     use OperationBuilder;
     use crypto;
+    use super::MAX_OPERATIONS;
     #[test]
     fn test_builder_success() {
         let seed = crypto::random_bytes(32);
@@ -78,11 +191,13 @@ mod tests {
 
         let tx0 = TransactionBuilder::new(&mut account)
             .operation(OperationBuilder::inflation().build())
-            .build();
+            .build()
+            .unwrap();
 
         let tx1 = TransactionBuilder::new(&mut account)
             .operation(OperationBuilder::inflation().build())
-            .build();
+            .build()
+            .unwrap();
 
         assert_eq!(tx0.operations().len(), 1);
         assert_eq!(tx0.sequence(), 1000);
@@ -100,7 +215,120 @@ mod tests {
         let tx = TransactionBuilder::new(&mut account)
             .operation(OperationBuilder::inflation().build())
             .with_memo(Memo::text("TEST STRING").unwrap())
-            .build();
+            .build()
+            .unwrap();
         assert_eq!(*tx.memo(), Memo::Text("TEST STRING".to_string()));
     }
+
+    #[test]
+    fn test_builder_no_operations() {
+        let seed = crypto::random_bytes(32);
+        let secret = ed25519_dalek::SecretKey::from_bytes(&seed).unwrap();
+        let public = ed25519_dalek::PublicKey::from(&secret);
+        let mut account = Account::new(public, 999);
+
+        let res = TransactionBuilder::new(&mut account).build();
+        assert!(res.is_err());
+        assert_eq!(account.sequence(), 999);
+    }
+
+    #[test]
+    fn test_builder_too_many_operations() {
+        let seed = crypto::random_bytes(32);
+        let secret = ed25519_dalek::SecretKey::from_bytes(&seed).unwrap();
+        let public = ed25519_dalek::PublicKey::from(&secret);
+        let mut account = Account::new(public, 999);
+
+        let mut builder = TransactionBuilder::new(&mut account);
+        for _ in 0..MAX_OPERATIONS + 1 {
+            builder = builder.operation(OperationBuilder::inflation().build());
+        }
+        assert!(builder.build().is_err());
+        assert_eq!(account.sequence(), 999);
+    }
+
+    #[test]
+    fn test_builder_invalid_time_bounds() {
+        let seed = crypto::random_bytes(32);
+        let secret = ed25519_dalek::SecretKey::from_bytes(&seed).unwrap();
+        let public = ed25519_dalek::PublicKey::from(&secret);
+        let mut account = Account::new(public, 999);
+
+        let time_bounds = TimeBounds::new(Some(UnixTimestamp(200)), Some(UnixTimestamp(100)));
+        let res = TransactionBuilder::new(&mut account)
+            .operation(OperationBuilder::inflation().build())
+            .with_time_bounds(time_bounds)
+            .build();
+        assert!(res.is_err());
+        assert_eq!(account.sequence(), 999);
+    }
+
+    #[test]
+    fn test_builder_invalid_amount_and_price() {
+        let seed = crypto::random_bytes(32);
+        let secret = ed25519_dalek::SecretKey::from_bytes(&seed).unwrap();
+        let public = ed25519_dalek::PublicKey::from(&secret);
+        let mut account = Account::new(public, 999);
+
+        let negative = Amount::from_str("-1.0").unwrap();
+        let res = TransactionBuilder::new(&mut account)
+            .operation(OperationBuilder::payment(public, Asset::native(), negative).build())
+            .build();
+        assert!(res.is_err());
+
+        let amount = Amount::from_str("1.0").unwrap();
+        let price = Price::new(1, 0);
+        let res = TransactionBuilder::new(&mut account)
+            .operation(
+                OperationBuilder::manage_offer(Asset::native(), Asset::native(), amount, price)
+                    .build(),
+            )
+            .build();
+        assert!(res.is_err());
+        assert_eq!(account.sequence(), 999);
+    }
+
+    #[test]
+    fn test_builder_timeout() {
+        let seed = crypto::random_bytes(32);
+        let secret = ed25519_dalek::SecretKey::from_bytes(&seed).unwrap();
+        let public = ed25519_dalek::PublicKey::from(&secret);
+        let mut account = Account::new(public, 999);
+
+        let tx = TransactionBuilder::new(&mut account)
+            .operation(OperationBuilder::inflation().build())
+            .with_time_bounds(TimeBounds::new(Some(UnixTimestamp(500)), None))
+            .with_timeout(Duration::from_secs(60))
+            .with_clock(FixedClock::new(UnixTimestamp(1000)))
+            .build()
+            .unwrap();
+        let expected = TimeBounds::new(Some(UnixTimestamp(500)), Some(UnixTimestamp(1060)));
+        assert_eq!(*tx.time_bounds(), Some(expected));
+        assert!(!tx.is_expired(&FixedClock::new(UnixTimestamp(1060))));
+        assert!(tx.is_expired(&FixedClock::new(UnixTimestamp(1061))));
+    }
+
+    #[test]
+    fn test_builder_sequence_providers() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let seed = crypto::random_bytes(32);
+        let secret = ed25519_dalek::SecretKey::from_bytes(&seed).unwrap();
+        let public = ed25519_dalek::PublicKey::from(&secret);
+
+        let shared = SharedSequence::new(public, 999);
+        let builder = TransactionBuilder::new(shared.clone())
+            .operation(OperationBuilder::inflation().build());
+        assert_send(&builder);
+        let tx = builder.build().unwrap();
+        assert_eq!(tx.sequence(), 1000);
+        assert_eq!(shared.sequence(), 1000);
+
+        let tx = TransactionBuilder::new(FixedSequence::new(public, 42))
+            .operation(OperationBuilder::inflation().build())
+            .build()
+            .unwrap();
+        assert_eq!(tx.sequence(), 42);
+        assert_eq!(*tx.source(), public);
+    }
 }
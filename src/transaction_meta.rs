@@ -0,0 +1,49 @@
+use ledger_entry::{LedgerEntry, LedgerKey};
+
+/// A change to a ledger entry made by a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LedgerEntryChange {
+    /// The entry was created.
+    Created(LedgerEntry),
+    /// The entry was updated to this value.
+    Updated(LedgerEntry),
+    /// The entry was removed.
+    Removed(LedgerKey),
+    /// The value of the entry before the next change.
+    State(LedgerEntry),
+    /// The archived entry was restored.
+    Restored(LedgerEntry),
+}
+
+/// The changes made by an operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationMeta {
+    /// The ledger entry changes, in order.
+    pub changes: Vec<LedgerEntryChange>,
+}
+
+/// The ledger entry changes made by a transaction, excluding the fee.
+///
+/// Versions 0 to 3 are decoded. Version 3 meta with soroban data is not
+/// supported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionMeta {
+    /// The meta version.
+    pub version: u32,
+    /// Changes made before the operations, e.g. the sequence number bump.
+    pub tx_changes_before: Vec<LedgerEntryChange>,
+    /// Changes made by each operation.
+    pub operations: Vec<OperationMeta>,
+    /// Changes made after the operations, e.g. fee refunds.
+    pub tx_changes_after: Vec<LedgerEntryChange>,
+}
+
+impl TransactionMeta {
+    /// Iterate over all changes in the order they were applied.
+    pub fn changes(&self) -> impl Iterator<Item = &LedgerEntryChange> {
+        self.tx_changes_before
+            .iter()
+            .chain(self.operations.iter().flat_map(|op| op.changes.iter()))
+            .chain(self.tx_changes_after.iter())
+    }
+}
@@ -0,0 +1,920 @@
+use std::fmt;
+use amount::Stroops;
+use asset::Asset;
+use ed25519_dalek::PublicKey;
+use ledger_entry::OfferEntry;
+
+macro_rules! result_code {
+    ($(#[$attr:meta])* $name:ident { $($variant:ident = $code:literal => $text:literal,)* }) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $(
+                #[doc = $text]
+                $variant,
+            )*
+        }
+
+        impl $name {
+            /// Return the code with the XDR value `code`, if known.
+            pub fn from_code(code: i32) -> Option<$name> {
+                match code {
+                    $($code => Some($name::$variant),)*
+                    _ => None,
+                }
+            }
+
+            /// Return the XDR value of the code.
+            pub fn code(&self) -> i32 {
+                match *self {
+                    $($name::$variant => $code,)*
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let text = match *self {
+                    $($name::$variant => $text,)*
+                };
+                f.write_str(text)
+            }
+        }
+    };
+}
+
+result_code!(
+    /// Result code of a transaction.
+    TransactionResultCode {
+        FeeBumpInnerSuccess = 1 => "fee bump inner transaction succeeded",
+        Success = 0 => "all operations succeeded",
+        Failed = -1 => "one of the operations failed",
+        TooEarly = -2 => "ledger close time before the lower time bound",
+        TooLate = -3 => "ledger close time after the upper time bound",
+        MissingOperation = -4 => "no operation was specified",
+        BadSeq = -5 => "sequence number does not match the source account",
+        BadAuth = -6 => "too few valid signatures or wrong network",
+        InsufficientBalance = -7 => "fee would bring the account below the reserve",
+        NoAccount = -8 => "source account not found",
+        InsufficientFee = -9 => "fee is too small",
+        BadAuthExtra = -10 => "unused signatures attached to the transaction",
+        InternalError = -11 => "an unknown error occurred",
+        NotSupported = -12 => "transaction type not supported",
+        FeeBumpInnerFailed = -13 => "fee bump inner transaction failed",
+        BadSponsorship = -14 => "sponsorship not confirmed",
+        BadMinSeqAgeOrGap = -15 => "minimum sequence age or gap precondition not met",
+        Malformed = -16 => "precondition is invalid",
+        SorobanInvalid = -17 => "soroban-specific preconditions were not met",
+    }
+);
+
+result_code!(
+    /// Result code of an operation that could not be applied.
+    OperationResultCode {
+        BadAuth = -1 => "too few valid signatures or wrong network",
+        NoAccount = -2 => "source account not found",
+        NotSupported = -3 => "operation not supported at this time",
+        TooManySubentries = -4 => "maximum number of subentries already reached",
+        ExceededWorkLimit = -5 => "operation did too much work",
+        TooManySponsoring = -6 => "account is sponsoring too many entries",
+    }
+);
+
+result_code!(
+    /// Result code of a create account operation.
+    CreateAccountResultCode {
+        Success = 0 => "account created",
+        Malformed = -1 => "invalid destination",
+        Underfunded = -2 => "not enough funds in source account",
+        LowReserve = -3 => "would create an account below the minimum reserve",
+        AlreadyExist = -4 => "account already exists",
+    }
+);
+
+result_code!(
+    /// Result code of a payment operation.
+    PaymentResultCode {
+        Success = 0 => "payment successfully completed",
+        Malformed = -1 => "bad input",
+        Underfunded = -2 => "not enough funds in source account",
+        SrcNoTrust = -3 => "no trust line on source account",
+        SrcNotAuthorized = -4 => "source not authorized to transfer",
+        NoDestination = -5 => "destination account does not exist",
+        NoTrust = -6 => "destination missing a trust line for asset",
+        NotAuthorized = -7 => "destination not authorized to hold asset",
+        LineFull = -8 => "destination would go above their limit",
+        NoIssuer = -9 => "missing issuer on asset",
+    }
+);
+
+result_code!(
+    /// Result code of a path payment strict receive operation.
+    PathPaymentStrictReceiveResultCode {
+        Success = 0 => "path payment successfully completed",
+        Malformed = -1 => "bad input",
+        Underfunded = -2 => "not enough funds in source account",
+        SrcNoTrust = -3 => "no trust line on source account",
+        SrcNotAuthorized = -4 => "source not authorized to transfer",
+        NoDestination = -5 => "destination account does not exist",
+        NoTrust = -6 => "destination missing a trust line for asset",
+        NotAuthorized = -7 => "destination not authorized to hold asset",
+        LineFull = -8 => "destination would go above their limit",
+        NoIssuer = -9 => "missing issuer on one asset",
+        TooFewOffers = -10 => "not enough offers to satisfy path",
+        OfferCrossSelf = -11 => "would cross one of its own offers",
+        OverSendmax = -12 => "could not satisfy sendmax",
+    }
+);
+
+result_code!(
+    /// Result code of a path payment strict send operation.
+    PathPaymentStrictSendResultCode {
+        Success = 0 => "path payment successfully completed",
+        Malformed = -1 => "bad input",
+        Underfunded = -2 => "not enough funds in source account",
+        SrcNoTrust = -3 => "no trust line on source account",
+        SrcNotAuthorized = -4 => "source not authorized to transfer",
+        NoDestination = -5 => "destination account does not exist",
+        NoTrust = -6 => "destination missing a trust line for asset",
+        NotAuthorized = -7 => "destination not authorized to hold asset",
+        LineFull = -8 => "destination would go above their limit",
+        NoIssuer = -9 => "missing issuer on one asset",
+        TooFewOffers = -10 => "not enough offers to satisfy path",
+        OfferCrossSelf = -11 => "would cross one of its own offers",
+        UnderDestmin = -12 => "could not satisfy destination minimum",
+    }
+);
+
+result_code!(
+    /// Result code of a manage sell offer, manage buy offer or create passive
+    /// sell offer operation.
+    ManageOfferResultCode {
+        Success = 0 => "offer successfully managed",
+        Malformed = -1 => "generated offer would be invalid",
+        SellNoTrust = -2 => "no trust line for what we're selling",
+        BuyNoTrust = -3 => "no trust line for what we're buying",
+        SellNotAuthorized = -4 => "not authorized to sell",
+        BuyNotAuthorized = -5 => "not authorized to buy",
+        LineFull = -6 => "can't receive more of what it's buying",
+        Underfunded = -7 => "doesn't hold what it's trying to sell",
+        CrossSelf = -8 => "would cross an offer from the same user",
+        SellNoIssuer = -9 => "no issuer for what we're selling",
+        BuyNoIssuer = -10 => "no issuer for what we're buying",
+        NotFound = -11 => "offer id does not match an existing offer",
+        LowReserve = -12 => "not enough funds to create a new offer",
+    }
+);
+
+result_code!(
+    /// Result code of a set options operation.
+    SetOptionsResultCode {
+        Success = 0 => "options successfully set",
+        LowReserve = -1 => "not enough funds to add a signer",
+        TooManySigners = -2 => "max number of signers already reached",
+        BadFlags = -3 => "invalid combination of clear and set flags",
+        InvalidInflation = -4 => "inflation account does not exist",
+        CantChange = -5 => "can no longer change this option",
+        UnknownFlag = -6 => "can't set an unknown flag",
+        ThresholdOutOfRange = -7 => "bad value for weight or threshold",
+        BadSigner = -8 => "signer cannot be the master key",
+        InvalidHomeDomain = -9 => "malformed home domain",
+        AuthRevocableRequired = -10 => "auth revocable is required for clawback",
+    }
+);
+
+result_code!(
+    /// Result code of a change trust operation.
+    ChangeTrustResultCode {
+        Success = 0 => "trust line successfully changed",
+        Malformed = -1 => "bad input",
+        NoIssuer = -2 => "could not find issuer",
+        InvalidLimit = -3 => "cannot drop limit below balance",
+        LowReserve = -4 => "not enough funds to create a new trust line",
+        SelfNotAllowed = -5 => "trusting self is not allowed",
+        TrustLineMissing = -6 => "asset trust line is missing for pool",
+        CannotDelete = -7 => "asset trust line is still referenced by a pool",
+        NotAuthMaintainLiabilities = -8 => "asset trust line is deauthorized",
+    }
+);
+
+result_code!(
+    /// Result code of an allow trust operation.
+    AllowTrustResultCode {
+        Success = 0 => "trust line authorization successfully changed",
+        Malformed = -1 => "asset is not an asset code",
+        NoTrustLine = -2 => "trustor does not have a trust line",
+        TrustNotRequired = -3 => "source account does not require trust",
+        CantRevoke = -4 => "source account can't revoke trust",
+        SelfNotAllowed = -5 => "trusting self is not allowed",
+        LowReserve = -6 => "claimable balances can't be created on revoke",
+    }
+);
+
+result_code!(
+    /// Result code of an account merge operation.
+    AccountMergeResultCode {
+        Success = 0 => "account successfully merged",
+        Malformed = -1 => "can't merge onto itself",
+        NoAccount = -2 => "destination does not exist",
+        ImmutableSet = -3 => "source account has the immutable flag set",
+        HasSubEntries = -4 => "account has trust lines or offers",
+        SeqnumTooFar = -5 => "sequence number is over the maximum allowed",
+        DestFull = -6 => "can't add source balance to destination balance",
+        IsSponsor = -7 => "can't merge an account that is a sponsor",
+    }
+);
+
+result_code!(
+    /// Result code of an inflation operation.
+    InflationResultCode {
+        Success = 0 => "inflation successfully run",
+        NotTime = -1 => "it is not time for inflation yet",
+    }
+);
+
+result_code!(
+    /// Result code of a manage data operation.
+    ManageDataResultCode {
+        Success = 0 => "data entry successfully managed",
+        NotSupportedYet = -1 => "the network hasn't moved to this protocol yet",
+        NameNotFound = -2 => "trying to remove a data entry that isn't there",
+        LowReserve = -3 => "not enough funds to create a new data entry",
+        InvalidName = -4 => "name not a valid string",
+    }
+);
+
+result_code!(
+    /// Result code of a bump sequence operation.
+    BumpSequenceResultCode {
+        Success = 0 => "sequence number successfully bumped",
+        BadSeq = -1 => "bump to is out of range",
+    }
+);
+
+result_code!(
+    /// Result code of a create claimable balance operation.
+    CreateClaimableBalanceResultCode {
+        Success = 0 => "claimable balance successfully created",
+        Malformed = -1 => "bad input",
+        LowReserve = -2 => "not enough funds to create a claimable balance",
+        NoTrust = -3 => "no trust line for the asset",
+        NotAuthorized = -4 => "not authorized to hold the asset",
+        Underfunded = -5 => "not enough funds in source account",
+    }
+);
+
+result_code!(
+    /// Result code of a claim claimable balance operation.
+    ClaimClaimableBalanceResultCode {
+        Success = 0 => "claimable balance successfully claimed",
+        DoesNotExist = -1 => "claimable balance does not exist",
+        CannotClaim = -2 => "not a claimant or predicate not satisfied",
+        LineFull = -3 => "destination would go above their limit",
+        NoTrust = -4 => "no trust line for the asset",
+        NotAuthorized = -5 => "not authorized to hold the asset",
+    }
+);
+
+result_code!(
+    /// Result code of a begin sponsoring future reserves operation.
+    BeginSponsoringFutureReservesResultCode {
+        Success = 0 => "sponsorship successfully started",
+        Malformed = -1 => "can't sponsor self",
+        AlreadySponsored = -2 => "account is already sponsored",
+        Recursive = -3 => "sponsoring account is itself sponsored",
+    }
+);
+
+result_code!(
+    /// Result code of an end sponsoring future reserves operation.
+    EndSponsoringFutureReservesResultCode {
+        Success = 0 => "sponsorship successfully ended",
+        NotSponsored = -1 => "account is not sponsored",
+    }
+);
+
+result_code!(
+    /// Result code of a revoke sponsorship operation.
+    RevokeSponsorshipResultCode {
+        Success = 0 => "sponsorship successfully revoked",
+        DoesNotExist = -1 => "ledger entry or signer does not exist",
+        NotSponsor = -2 => "source account is not the sponsor",
+        LowReserve = -3 => "not enough funds to take over the reserve",
+        OnlyTransferable = -4 => "sponsorship can only be transferred",
+        Malformed = -5 => "bad input",
+    }
+);
+
+result_code!(
+    /// Result code of a clawback operation.
+    ClawbackResultCode {
+        Success = 0 => "asset successfully clawed back",
+        Malformed = -1 => "bad input",
+        NotClawbackEnabled = -2 => "trust line does not allow clawback",
+        NoTrust = -3 => "account has no trust line for the asset",
+        Underfunded = -4 => "account does not hold enough of the asset",
+    }
+);
+
+result_code!(
+    /// Result code of a clawback claimable balance operation.
+    ClawbackClaimableBalanceResultCode {
+        Success = 0 => "claimable balance successfully clawed back",
+        DoesNotExist = -1 => "claimable balance does not exist",
+        NotIssuer = -2 => "source account is not the asset issuer",
+        NotClawbackEnabled = -3 => "claimable balance does not allow clawback",
+    }
+);
+
+result_code!(
+    /// Result code of a set trust line flags operation.
+    SetTrustLineFlagsResultCode {
+        Success = 0 => "trust line flags successfully set",
+        Malformed = -1 => "bad input",
+        NoTrustLine = -2 => "trustor does not have a trust line",
+        CantRevoke = -3 => "source account can't revoke authorization",
+        InvalidState = -4 => "resulting flags are invalid",
+        LowReserve = -5 => "claimable balances can't be created on revoke",
+    }
+);
+
+result_code!(
+    /// Result code of a liquidity pool deposit operation.
+    LiquidityPoolDepositResultCode {
+        Success = 0 => "deposit successfully completed",
+        Malformed = -1 => "bad input",
+        NoTrust = -2 => "no trust line for one of the assets",
+        NotAuthorized = -3 => "not authorized for one of the assets",
+        Underfunded = -4 => "not enough balance for one of the assets",
+        LineFull = -5 => "pool share trust line doesn't have sufficient limit",
+        BadPrice = -6 => "deposit price outside bounds",
+        PoolFull = -7 => "pool reserves are full",
+    }
+);
+
+result_code!(
+    /// Result code of a liquidity pool withdraw operation.
+    LiquidityPoolWithdrawResultCode {
+        Success = 0 => "withdrawal successfully completed",
+        Malformed = -1 => "bad input",
+        NoTrust = -2 => "no trust line for one of the assets",
+        Underfunded = -3 => "not enough pool shares",
+        LineFull = -4 => "would go above the limit for one of the assets",
+        UnderMinimum = -5 => "didn't withdraw enough",
+    }
+);
+
+result_code!(
+    /// Result code of an invoke host function operation.
+    InvokeHostFunctionResultCode {
+        Success = 0 => "host function successfully invoked",
+        Malformed = -1 => "bad input",
+        Trapped = -2 => "the contract trapped",
+        ResourceLimitExceeded = -3 => "a resource limit was exceeded",
+        EntryArchived = -4 => "a footprint entry is archived",
+        InsufficientRefundableFee = -5 => "refundable fee is too small",
+    }
+);
+
+result_code!(
+    /// Result code of an extend footprint TTL operation.
+    ExtendFootprintTtlResultCode {
+        Success = 0 => "footprint TTL successfully extended",
+        Malformed = -1 => "bad input",
+        ResourceLimitExceeded = -2 => "a resource limit was exceeded",
+        InsufficientRefundableFee = -3 => "refundable fee is too small",
+    }
+);
+
+result_code!(
+    /// Result code of a restore footprint operation.
+    RestoreFootprintResultCode {
+        Success = 0 => "footprint successfully restored",
+        Malformed = -1 => "bad input",
+        ResourceLimitExceeded = -2 => "a resource limit was exceeded",
+        InsufficientRefundableFee = -3 => "refundable fee is too small",
+    }
+);
+
+/// The result of a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionResult {
+    /// The fee charged.
+    pub fee_charged: Stroops,
+    /// The result code.
+    pub code: TransactionResultCode,
+    /// The operation results, present when `code` is `Success` or `Failed`.
+    pub operations: Vec<OperationResult>,
+    /// The inner transaction result of a fee bump transaction.
+    pub inner: Option<InnerTransactionResultPair>,
+}
+
+impl TransactionResult {
+    /// Return true if the transaction, or the fee bumped transaction, succeeded.
+    pub fn is_success(&self) -> bool {
+        matches!(
+            self.code,
+            TransactionResultCode::Success | TransactionResultCode::FeeBumpInnerSuccess
+        )
+    }
+}
+
+impl fmt::Display for TransactionResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code)?;
+        if let Some(ref inner) = self.inner {
+            write!(f, ": {}", inner.result)?;
+        }
+        fmt_failed_operations(f, &self.operations)
+    }
+}
+
+/// The hash of a transaction and its result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionResultPair {
+    /// The transaction hash.
+    pub transaction_hash: [u8; 32],
+    /// The transaction result.
+    pub result: TransactionResult,
+}
+
+/// The results of the transactions of a ledger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionResultSet {
+    /// The results, in application order.
+    pub results: Vec<TransactionResultPair>,
+    pub(crate) hash: [u8; 32],
+}
+
+impl TransactionResultSet {
+    /// Return the sha256 hash of the XDR encoded set, as read, which ledger
+    /// headers record as `tx_set_result_hash`.
+    pub fn hash(&self) -> &[u8; 32] {
+        &self.hash
+    }
+}
+
+/// The result of the transaction wrapped by a fee bump transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InnerTransactionResult {
+    /// The fee charged.
+    pub fee_charged: Stroops,
+    /// The result code, never one of the fee bump codes.
+    pub code: TransactionResultCode,
+    /// The operation results, present when `code` is `Success` or `Failed`.
+    pub operations: Vec<OperationResult>,
+}
+
+impl fmt::Display for InnerTransactionResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code)?;
+        fmt_failed_operations(f, &self.operations)
+    }
+}
+
+/// The hash of the transaction wrapped by a fee bump transaction and its result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InnerTransactionResultPair {
+    /// The inner transaction hash.
+    pub transaction_hash: [u8; 32],
+    /// The inner transaction result.
+    pub result: InnerTransactionResult,
+}
+
+fn fmt_failed_operations(f: &mut fmt::Formatter, operations: &[OperationResult]) -> fmt::Result {
+    for (index, operation) in operations.iter().enumerate() {
+        if !operation.is_success() {
+            write!(f, "; operation {}: {}", index, operation)?;
+        }
+    }
+    Ok(())
+}
+
+/// The result of an operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OperationResult {
+    /// The operation could not be applied.
+    Failed(OperationResultCode),
+    /// Result of a create account operation.
+    CreateAccount(CreateAccountResultCode),
+    /// Result of a payment operation.
+    Payment(PaymentResultCode),
+    /// Result of a path payment strict receive operation.
+    PathPaymentStrictReceive(PathPaymentStrictReceiveResult),
+    /// Result of a manage sell offer operation.
+    ManageSellOffer(ManageOfferResult),
+    /// Result of a create passive sell offer operation.
+    CreatePassiveSellOffer(ManageOfferResult),
+    /// Result of a set options operation.
+    SetOptions(SetOptionsResultCode),
+    /// Result of a change trust operation.
+    ChangeTrust(ChangeTrustResultCode),
+    /// Result of an allow trust operation.
+    AllowTrust(AllowTrustResultCode),
+    /// Result of an account merge operation.
+    AccountMerge(AccountMergeResult),
+    /// Result of an inflation operation.
+    Inflation(InflationResult),
+    /// Result of a manage data operation.
+    ManageData(ManageDataResultCode),
+    /// Result of a bump sequence operation.
+    BumpSequence(BumpSequenceResultCode),
+    /// Result of a manage buy offer operation.
+    ManageBuyOffer(ManageOfferResult),
+    /// Result of a path payment strict send operation.
+    PathPaymentStrictSend(PathPaymentStrictSendResult),
+    /// Result of a create claimable balance operation.
+    CreateClaimableBalance(CreateClaimableBalanceResult),
+    /// Result of a claim claimable balance operation.
+    ClaimClaimableBalance(ClaimClaimableBalanceResultCode),
+    /// Result of a begin sponsoring future reserves operation.
+    BeginSponsoringFutureReserves(BeginSponsoringFutureReservesResultCode),
+    /// Result of an end sponsoring future reserves operation.
+    EndSponsoringFutureReserves(EndSponsoringFutureReservesResultCode),
+    /// Result of a revoke sponsorship operation.
+    RevokeSponsorship(RevokeSponsorshipResultCode),
+    /// Result of a clawback operation.
+    Clawback(ClawbackResultCode),
+    /// Result of a clawback claimable balance operation.
+    ClawbackClaimableBalance(ClawbackClaimableBalanceResultCode),
+    /// Result of a set trust line flags operation.
+    SetTrustLineFlags(SetTrustLineFlagsResultCode),
+    /// Result of a liquidity pool deposit operation.
+    LiquidityPoolDeposit(LiquidityPoolDepositResultCode),
+    /// Result of a liquidity pool withdraw operation.
+    LiquidityPoolWithdraw(LiquidityPoolWithdrawResultCode),
+    /// Result of an invoke host function operation.
+    InvokeHostFunction(InvokeHostFunctionResult),
+    /// Result of an extend footprint TTL operation.
+    ExtendFootprintTtl(ExtendFootprintTtlResultCode),
+    /// Result of a restore footprint operation.
+    RestoreFootprint(RestoreFootprintResultCode),
+}
+
+impl OperationResult {
+    /// Return the XDR value of the operation result code, 0 on success.
+    pub fn code(&self) -> i32 {
+        match *self {
+            OperationResult::Failed(ref code) => code.code(),
+            OperationResult::CreateAccount(ref code) => code.code(),
+            OperationResult::Payment(ref code) => code.code(),
+            OperationResult::PathPaymentStrictReceive(ref result) => result.code().code(),
+            OperationResult::ManageSellOffer(ref result) => result.code().code(),
+            OperationResult::CreatePassiveSellOffer(ref result) => result.code().code(),
+            OperationResult::SetOptions(ref code) => code.code(),
+            OperationResult::ChangeTrust(ref code) => code.code(),
+            OperationResult::AllowTrust(ref code) => code.code(),
+            OperationResult::AccountMerge(ref result) => result.code().code(),
+            OperationResult::Inflation(ref result) => result.code().code(),
+            OperationResult::ManageData(ref code) => code.code(),
+            OperationResult::BumpSequence(ref code) => code.code(),
+            OperationResult::ManageBuyOffer(ref result) => result.code().code(),
+            OperationResult::PathPaymentStrictSend(ref result) => result.code().code(),
+            OperationResult::CreateClaimableBalance(ref result) => result.code().code(),
+            OperationResult::ClaimClaimableBalance(ref code) => code.code(),
+            OperationResult::BeginSponsoringFutureReserves(ref code) => code.code(),
+            OperationResult::EndSponsoringFutureReserves(ref code) => code.code(),
+            OperationResult::RevokeSponsorship(ref code) => code.code(),
+            OperationResult::Clawback(ref code) => code.code(),
+            OperationResult::ClawbackClaimableBalance(ref code) => code.code(),
+            OperationResult::SetTrustLineFlags(ref code) => code.code(),
+            OperationResult::LiquidityPoolDeposit(ref code) => code.code(),
+            OperationResult::LiquidityPoolWithdraw(ref code) => code.code(),
+            OperationResult::InvokeHostFunction(ref result) => result.code().code(),
+            OperationResult::ExtendFootprintTtl(ref code) => code.code(),
+            OperationResult::RestoreFootprint(ref code) => code.code(),
+        }
+    }
+
+    /// Return true if the operation succeeded.
+    pub fn is_success(&self) -> bool {
+        match *self {
+            OperationResult::Failed(_) => false,
+            _ => self.code() == 0,
+        }
+    }
+}
+
+impl fmt::Display for OperationResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            OperationResult::Failed(ref code) => write!(f, "{}", code),
+            OperationResult::CreateAccount(ref code) => write!(f, "create account: {}", code),
+            OperationResult::Payment(ref code) => write!(f, "payment: {}", code),
+            OperationResult::PathPaymentStrictReceive(ref result) => {
+                write!(f, "path payment strict receive: {}", result.code())
+            }
+            OperationResult::ManageSellOffer(ref result) => {
+                write!(f, "manage sell offer: {}", result.code())
+            }
+            OperationResult::CreatePassiveSellOffer(ref result) => {
+                write!(f, "create passive sell offer: {}", result.code())
+            }
+            OperationResult::SetOptions(ref code) => write!(f, "set options: {}", code),
+            OperationResult::ChangeTrust(ref code) => write!(f, "change trust: {}", code),
+            OperationResult::AllowTrust(ref code) => write!(f, "allow trust: {}", code),
+            OperationResult::AccountMerge(ref result) => {
+                write!(f, "account merge: {}", result.code())
+            }
+            OperationResult::Inflation(ref result) => write!(f, "inflation: {}", result.code()),
+            OperationResult::ManageData(ref code) => write!(f, "manage data: {}", code),
+            OperationResult::BumpSequence(ref code) => write!(f, "bump sequence: {}", code),
+            OperationResult::ManageBuyOffer(ref result) => {
+                write!(f, "manage buy offer: {}", result.code())
+            }
+            OperationResult::PathPaymentStrictSend(ref result) => {
+                write!(f, "path payment strict send: {}", result.code())
+            }
+            OperationResult::CreateClaimableBalance(ref result) => {
+                write!(f, "create claimable balance: {}", result.code())
+            }
+            OperationResult::ClaimClaimableBalance(ref code) => {
+                write!(f, "claim claimable balance: {}", code)
+            }
+            OperationResult::BeginSponsoringFutureReserves(ref code) => {
+                write!(f, "begin sponsoring future reserves: {}", code)
+            }
+            OperationResult::EndSponsoringFutureReserves(ref code) => {
+                write!(f, "end sponsoring future reserves: {}", code)
+            }
+            OperationResult::RevokeSponsorship(ref code) => {
+                write!(f, "revoke sponsorship: {}", code)
+            }
+            OperationResult::Clawback(ref code) => write!(f, "clawback: {}", code),
+            OperationResult::ClawbackClaimableBalance(ref code) => {
+                write!(f, "clawback claimable balance: {}", code)
+            }
+            OperationResult::SetTrustLineFlags(ref code) => {
+                write!(f, "set trust line flags: {}", code)
+            }
+            OperationResult::LiquidityPoolDeposit(ref code) => {
+                write!(f, "liquidity pool deposit: {}", code)
+            }
+            OperationResult::LiquidityPoolWithdraw(ref code) => {
+                write!(f, "liquidity pool withdraw: {}", code)
+            }
+            OperationResult::InvokeHostFunction(ref result) => {
+                write!(f, "invoke host function: {}", result.code())
+            }
+            OperationResult::ExtendFootprintTtl(ref code) => {
+                write!(f, "extend footprint TTL: {}", code)
+            }
+            OperationResult::RestoreFootprint(ref code) => {
+                write!(f, "restore footprint: {}", code)
+            }
+        }
+    }
+}
+
+/// An offer or liquidity pool taken by an operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClaimAtom {
+    /// An offer from the order book.
+    OrderBook(ClaimOfferAtom),
+    /// A liquidity pool.
+    LiquidityPool(ClaimLiquidityAtom),
+}
+
+/// An offer taken from the order book.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClaimOfferAtom {
+    /// The account that created the offer.
+    pub seller_id: PublicKey,
+    /// The offer id.
+    pub offer_id: i64,
+    /// The asset taken from the seller.
+    pub asset_sold: Asset,
+    /// Amount of `asset_sold` in stroops.
+    pub amount_sold: Stroops,
+    /// The asset sent to the seller.
+    pub asset_bought: Asset,
+    /// Amount of `asset_bought` in stroops.
+    pub amount_bought: Stroops,
+}
+
+/// A trade against a liquidity pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClaimLiquidityAtom {
+    /// The liquidity pool id.
+    pub liquidity_pool_id: [u8; 32],
+    /// The asset taken from the pool.
+    pub asset_sold: Asset,
+    /// Amount of `asset_sold` in stroops.
+    pub amount_sold: Stroops,
+    /// The asset sent to the pool.
+    pub asset_bought: Asset,
+    /// Amount of `asset_bought` in stroops.
+    pub amount_bought: Stroops,
+}
+
+/// The last payment of a successful path payment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimplePaymentResult {
+    /// The destination account.
+    pub destination: PublicKey,
+    /// The asset received.
+    pub asset: Asset,
+    /// The amount received in stroops.
+    pub amount: Stroops,
+}
+
+/// The offers taken and final payment of a successful path payment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathPaymentSuccessResult {
+    /// The offers and liquidity pools taken along the path.
+    pub offers: Vec<ClaimAtom>,
+    /// The payment to the destination.
+    pub last: SimplePaymentResult,
+}
+
+/// The result of a path payment strict receive operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathPaymentStrictReceiveResult {
+    /// The path payment succeeded.
+    Success(Box<PathPaymentSuccessResult>),
+    /// The issuer of the asset does not exist.
+    NoIssuer(Box<Asset>),
+    /// The path payment failed for another reason.
+    Failure(PathPaymentStrictReceiveResultCode),
+}
+
+impl PathPaymentStrictReceiveResult {
+    /// Return the result code.
+    pub fn code(&self) -> PathPaymentStrictReceiveResultCode {
+        match *self {
+            PathPaymentStrictReceiveResult::Success(_) => {
+                PathPaymentStrictReceiveResultCode::Success
+            }
+            PathPaymentStrictReceiveResult::NoIssuer(_) => {
+                PathPaymentStrictReceiveResultCode::NoIssuer
+            }
+            PathPaymentStrictReceiveResult::Failure(code) => code,
+        }
+    }
+}
+
+/// The result of a path payment strict send operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathPaymentStrictSendResult {
+    /// The path payment succeeded.
+    Success(Box<PathPaymentSuccessResult>),
+    /// The issuer of the asset does not exist.
+    NoIssuer(Box<Asset>),
+    /// The path payment failed for another reason.
+    Failure(PathPaymentStrictSendResultCode),
+}
+
+impl PathPaymentStrictSendResult {
+    /// Return the result code.
+    pub fn code(&self) -> PathPaymentStrictSendResultCode {
+        match *self {
+            PathPaymentStrictSendResult::Success(_) => PathPaymentStrictSendResultCode::Success,
+            PathPaymentStrictSendResult::NoIssuer(_) => PathPaymentStrictSendResultCode::NoIssuer,
+            PathPaymentStrictSendResult::Failure(code) => code,
+        }
+    }
+}
+
+/// What happened to the offer of a successful manage offer operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManageOfferEffect {
+    /// The remaining amount created a new offer.
+    Created(OfferEntry),
+    /// The existing offer was updated.
+    Updated(OfferEntry),
+    /// The offer was deleted or fully taken.
+    Deleted,
+}
+
+/// The offers taken and the resulting offer of a manage offer operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManageOfferSuccessResult {
+    /// The offers and liquidity pools taken.
+    pub offers_claimed: Vec<ClaimAtom>,
+    /// What happened to the offer.
+    pub effect: ManageOfferEffect,
+}
+
+/// The result of a manage sell offer, manage buy offer or create passive sell
+/// offer operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManageOfferResult {
+    /// The offer was managed.
+    Success(Box<ManageOfferSuccessResult>),
+    /// The operation failed.
+    Failure(ManageOfferResultCode),
+}
+
+impl ManageOfferResult {
+    /// Return the result code.
+    pub fn code(&self) -> ManageOfferResultCode {
+        match *self {
+            ManageOfferResult::Success(_) => ManageOfferResultCode::Success,
+            ManageOfferResult::Failure(code) => code,
+        }
+    }
+}
+
+/// The result of an account merge operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountMergeResult {
+    /// The account was merged, with the balance transferred.
+    Success(Stroops),
+    /// The operation failed.
+    Failure(AccountMergeResultCode),
+}
+
+impl AccountMergeResult {
+    /// Return the result code.
+    pub fn code(&self) -> AccountMergeResultCode {
+        match *self {
+            AccountMergeResult::Success(_) => AccountMergeResultCode::Success,
+            AccountMergeResult::Failure(code) => code,
+        }
+    }
+}
+
+/// A payout of an inflation operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InflationPayout {
+    /// The account receiving the payout.
+    pub destination: PublicKey,
+    /// The payout in stroops.
+    pub amount: Stroops,
+}
+
+/// The result of an inflation operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InflationResult {
+    /// Inflation ran with the payouts.
+    Success(Vec<InflationPayout>),
+    /// The operation failed.
+    Failure(InflationResultCode),
+}
+
+impl InflationResult {
+    /// Return the result code.
+    pub fn code(&self) -> InflationResultCode {
+        match *self {
+            InflationResult::Success(_) => InflationResultCode::Success,
+            InflationResult::Failure(code) => code,
+        }
+    }
+}
+
+/// The result of a create claimable balance operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CreateClaimableBalanceResult {
+    /// The claimable balance was created with the id.
+    Success([u8; 32]),
+    /// The operation failed.
+    Failure(CreateClaimableBalanceResultCode),
+}
+
+impl CreateClaimableBalanceResult {
+    /// Return the result code.
+    pub fn code(&self) -> CreateClaimableBalanceResultCode {
+        match *self {
+            CreateClaimableBalanceResult::Success(_) => CreateClaimableBalanceResultCode::Success,
+            CreateClaimableBalanceResult::Failure(code) => code,
+        }
+    }
+}
+
+/// The result of an invoke host function operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvokeHostFunctionResult {
+    /// The function was invoked, with the hash of its return value and events.
+    Success([u8; 32]),
+    /// The operation failed.
+    Failure(InvokeHostFunctionResultCode),
+}
+
+impl InvokeHostFunctionResult {
+    /// Return the result code.
+    pub fn code(&self) -> InvokeHostFunctionResultCode {
+        match *self {
+            InvokeHostFunctionResult::Success(_) => InvokeHostFunctionResultCode::Success,
+            InvokeHostFunctionResult::Failure(code) => code,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ManageOfferResultCode, OperationResult, PaymentResultCode, TransactionResultCode};
+
+    #[test]
+    fn test_result_codes() {
+        assert_eq!(PaymentResultCode::from_code(-2), Some(PaymentResultCode::Underfunded));
+        assert_eq!(PaymentResultCode::from_code(-10), None);
+        assert_eq!(TransactionResultCode::FeeBumpInnerFailed.code(), -13);
+        assert_eq!(
+            ManageOfferResultCode::CrossSelf.to_string(),
+            "would cross an offer from the same user"
+        );
+        let result = OperationResult::Payment(PaymentResultCode::LineFull);
+        assert!(!result.is_success());
+        assert_eq!(result.to_string(), "payment: destination would go above their limit");
+    }
+}
@@ -0,0 +1,307 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use regex::Regex;
+use zeroize::Zeroize;
+use crypto;
+use crypto::keypair::KeyPair;
+use error::{Error, Result};
+
+/// Number of attempts a worker makes before updating the shared counter.
+const ATTEMPTS_PER_UPDATE: usize = 64;
+
+/// The base32 alphabet used by account ids.
+const ALPHABET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// A pattern that account ids are matched against.
+#[derive(Debug, Clone)]
+pub enum VanityPattern {
+    /// The account id starts with the given characters, e.g. `GBANK`.
+    Prefix(String),
+    /// The account id ends with the given characters.
+    Suffix(String),
+    /// The account id matches the regular expression.
+    Regex(Regex),
+}
+
+impl VanityPattern {
+    /// Create a prefix pattern, including the leading `G`.
+    ///
+    /// The second character of an account id is always one of `A`, `B`, `C` or `D`.
+    pub fn prefix(prefix: &str) -> Result<VanityPattern> {
+        let prefix = prefix.to_uppercase();
+        if !prefix.starts_with('G') || !is_base32(&prefix) || prefix.len() > 56 {
+            return Err(Error::InvalidPattern);
+        }
+        match prefix.chars().nth(1) {
+            Some('A') | Some('B') | Some('C') | Some('D') | None => {}
+            Some(_) => return Err(Error::InvalidPattern),
+        }
+        Ok(VanityPattern::Prefix(prefix))
+    }
+
+    /// Create a suffix pattern.
+    pub fn suffix(suffix: &str) -> Result<VanityPattern> {
+        let suffix = suffix.to_uppercase();
+        if !is_base32(&suffix) || suffix.len() > 55 {
+            return Err(Error::InvalidPattern);
+        }
+        Ok(VanityPattern::Suffix(suffix))
+    }
+
+    /// Create a regular expression pattern, e.g. `^G.*STELLAR$`.
+    pub fn regex(regex: &str) -> Result<VanityPattern> {
+        let regex = Regex::new(regex).map_err(|_| Error::InvalidPattern)?;
+        Ok(VanityPattern::Regex(regex))
+    }
+
+    /// Return `true` if `account_id` matches the pattern.
+    pub fn matches(&self, account_id: &str) -> bool {
+        match *self {
+            VanityPattern::Prefix(ref prefix) => account_id.starts_with(prefix.as_str()),
+            VanityPattern::Suffix(ref suffix) => account_id.ends_with(suffix.as_str()),
+            VanityPattern::Regex(ref regex) => regex.is_match(account_id),
+        }
+    }
+
+    /// Return the expected number of attempts to find a match, if it can be estimated.
+    ///
+    /// Regular expressions can not be estimated.
+    pub fn expected_attempts(&self) -> Option<f64> {
+        match *self {
+            // Skip the leading `G`, the following character has only 4 choices.
+            VanityPattern::Prefix(ref prefix) => match prefix.len() {
+                0 | 1 => Some(1.0),
+                n => Some(4.0 * 32f64.powi(n as i32 - 2)),
+            },
+            VanityPattern::Suffix(ref suffix) => Some(32f64.powi(suffix.len() as i32)),
+            VanityPattern::Regex(_) => None,
+        }
+    }
+}
+
+fn is_base32(s: &str) -> bool {
+    s.chars().all(|c| ALPHABET.contains(c))
+}
+
+/// Progress of a running search.
+#[derive(Debug, Clone, Copy)]
+pub struct VanityProgress {
+    /// Number of keys tried so far.
+    pub attempts: usize,
+    /// Time since the search started.
+    pub elapsed: Duration,
+    /// Expected number of attempts to find a match.
+    pub expected_attempts: Option<f64>,
+}
+
+impl VanityProgress {
+    /// Return the number of attempts per second.
+    pub fn rate(&self) -> f64 {
+        let secs = self.elapsed.as_secs() as f64 + f64::from(self.elapsed.subsec_nanos()) * 1e-9;
+        if secs > 0.0 {
+            self.attempts as f64 / secs
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Search random seeds for an account id matching a pattern, using all cores.
+///
+/// Keys are created from their secret seed, exactly like `KeyPair::from_secret_seed`.
+#[derive(Debug, Clone)]
+pub struct VanitySearch {
+    pattern: Arc<VanityPattern>,
+    threads: usize,
+    cancelled: Arc<AtomicBool>,
+    attempts: Arc<AtomicUsize>,
+}
+
+impl VanitySearch {
+    /// Create a search for `pattern`, with one thread per core.
+    pub fn new(pattern: VanityPattern) -> VanitySearch {
+        let threads = thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        VanitySearch {
+            pattern: Arc::new(pattern),
+            threads,
+            cancelled: Arc::new(AtomicBool::new(false)),
+            attempts: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Use `threads` threads.
+    pub fn with_threads(mut self, threads: usize) -> VanitySearch {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Return the pattern.
+    pub fn pattern(&self) -> &VanityPattern {
+        &self.pattern
+    }
+
+    /// Return the expected number of attempts to find a match.
+    pub fn expected_attempts(&self) -> Option<f64> {
+        self.pattern.expected_attempts()
+    }
+
+    /// Return the number of keys tried so far.
+    pub fn attempts(&self) -> usize {
+        self.attempts.load(Ordering::Relaxed)
+    }
+
+    /// Stop the search. Clones share the same search and can be used to
+    /// cancel it from another thread. A cancelled or finished search can not run again.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Return `true` if the search was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Run the search until a match is found or it is cancelled.
+    pub fn run(&self) -> Option<KeyPair> {
+        self.run_with_progress(Duration::from_secs(1), |_| {})
+    }
+
+    /// Run the search, calling `progress` every `interval`.
+    ///
+    /// Return `None` if the search was cancelled.
+    pub fn run_with_progress<F>(&self, interval: Duration, mut progress: F) -> Option<KeyPair>
+    where
+        F: FnMut(VanityProgress),
+    {
+        let start = Instant::now();
+        let (sender, receiver) = mpsc::channel();
+        let workers: Vec<_> = (0..self.threads)
+            .map(|_| {
+                let search = self.clone();
+                let sender = sender.clone();
+                thread::spawn(move || search.work(&sender))
+            })
+            .collect();
+        drop(sender);
+
+        let result = loop {
+            match receiver.recv_timeout(interval) {
+                Ok(keypair) => break Some(keypair),
+                Err(mpsc::RecvTimeoutError::Disconnected) => break None,
+                Err(mpsc::RecvTimeoutError::Timeout) => progress(VanityProgress {
+                    attempts: self.attempts(),
+                    elapsed: start.elapsed(),
+                    expected_attempts: self.expected_attempts(),
+                }),
+            }
+        };
+        self.cancel();
+        for worker in workers {
+            let _ = worker.join();
+        }
+        result
+    }
+
+    fn work(&self, sender: &mpsc::Sender<KeyPair>) {
+        while !self.is_cancelled() {
+            for _ in 0..ATTEMPTS_PER_UPDATE {
+                if let Some(keypair) = self.attempt() {
+                    let _ = sender.send(keypair);
+                    self.cancel();
+                    return;
+                }
+            }
+            self.attempts.fetch_add(ATTEMPTS_PER_UPDATE, Ordering::Relaxed);
+        }
+    }
+
+    fn attempt(&self) -> Option<KeyPair> {
+        let mut seed = crypto::random_bytes(32);
+        let keypair = KeyPair::from_seed_bytes(&seed).ok();
+        seed.zeroize();
+        let keypair = keypair?;
+        let account_id = keypair.account_id().ok()?;
+        if !self.pattern.matches(&account_id) {
+            return None;
+        }
+        // Go through the secret seed, so that the result is reproducible from it.
+        let mut secret_seed = keypair.secret_seed().ok()?;
+        let restored = KeyPair::from_secret_seed(&secret_seed).ok();
+        secret_seed.zeroize();
+        restored.filter(|restored| *restored == keypair)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use super::{VanityPattern, VanitySearch};
+    use crypto::strkey;
+    use KeyPair;
+
+    #[test]
+    fn test_patterns() {
+        assert!(VanityPattern::prefix("GA").is_ok());
+        assert!(VanityPattern::prefix("gdab").is_ok());
+        assert!(VanityPattern::prefix("GX").is_err());
+        assert!(VanityPattern::prefix("ABC").is_err());
+        assert!(VanityPattern::prefix("GA1").is_err());
+        assert!(VanityPattern::suffix("XLM").is_ok());
+        assert!(VanityPattern::suffix("X0").is_err());
+        assert!(VanityPattern::regex("^GA.*Z$").is_ok());
+        assert!(VanityPattern::regex("(").is_err());
+
+        let pattern = VanityPattern::prefix("GAB").unwrap();
+        assert!(pattern.matches("GABCDEF"));
+        assert!(!pattern.matches("GACDEF"));
+        assert_eq!(pattern.expected_attempts(), Some(128.0));
+        assert_eq!(VanityPattern::suffix("AB").unwrap().expected_attempts(), Some(1024.0));
+        assert_eq!(VanityPattern::regex("A").unwrap().expected_attempts(), None);
+    }
+
+    #[test]
+    fn test_search_prefix() {
+        let search = VanitySearch::new(VanityPattern::prefix("GAB").unwrap()).with_threads(2);
+        let keypair = search.run().unwrap();
+        let account_id = keypair.account_id().unwrap();
+        assert!(account_id.starts_with("GAB"));
+        assert!(strkey::decode_account_id(&account_id).is_ok());
+        let seed = keypair.secret_seed().unwrap();
+        assert_eq!(KeyPair::from_secret_seed(&seed).unwrap(), keypair);
+        assert!(search.attempts() > 0 || search.is_cancelled());
+    }
+
+    #[test]
+    fn test_search_regex() {
+        let search = VanitySearch::new(VanityPattern::regex("^G[A-D]A").unwrap());
+        let keypair = search.run().unwrap();
+        assert_eq!(&keypair.account_id().unwrap()[2..3], "A");
+    }
+
+    #[test]
+    fn test_cancel() {
+        // Practically impossible to find.
+        let search = VanitySearch::new(VanityPattern::suffix("AAAAAAAAAAAAAAAAAAAA").unwrap())
+            .with_threads(2);
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let canceller = search.clone();
+        let seen = reports.clone();
+        let result = search.run_with_progress(Duration::from_millis(10), move |progress| {
+            seen.lock().unwrap().push(progress);
+            if seen.lock().unwrap().len() == 3 {
+                canceller.cancel();
+            }
+        });
+        assert!(result.is_none());
+        let reports = reports.lock().unwrap();
+        assert!(reports.len() >= 3);
+        assert!(reports[2].expected_attempts.unwrap() > 1e29);
+        assert!(reports[2].attempts >= reports[0].attempts);
+    }
+}
@@ -1,6 +1,6 @@
 use std::str;
 use serde_xdr::opaque_data;
-use error::Result;
+use error::{Error, Result};
 use xdr::keypair::PublicKey;
 use xdr::{FromXdr, ToXdr};
 
@@ -77,6 +77,10 @@ fn alphanum_to_credit(code: &[u8], issuer: PublicKey) -> Result<::CreditAsset> {
         }
         pos += 1;
     }
+    // Codes are zero padded, a non-zero byte after the padding is invalid
+    if code[pos..].iter().any(|b| *b != 0) {
+        return Err(Error::InvalidAssetCode);
+    }
     let code_ = str::from_utf8(&code[..pos])?;
     let issuer_ = ed25519_dalek::PublicKey::from_xdr(issuer)?;
     Ok(::CreditAsset::new(code_.to_string(), issuer_)?)
@@ -0,0 +1,33 @@
+use std::io::Read;
+use error::Result;
+use xdr::ledger_entry::ExtensionPoint;
+use xdr::limits::{self, DecodeLimits};
+use xdr::transaction_result::TransactionResultSet;
+use xdr::FromXdr;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionHistoryResultEntry {
+    pub ledger_seq: u32,
+    pub tx_result_set: TransactionResultSet,
+    pub ext: ExtensionPoint,
+}
+
+impl<'de> FromXdr<'de, TransactionHistoryResultEntry> for ::TransactionHistoryResultEntry {
+    fn from_xdr(_: TransactionHistoryResultEntry) -> Result<::TransactionHistoryResultEntry> {
+        // The result set is hashed as read, only `from_reader_with_limits` sees the bytes.
+        unreachable!("result entries are decoded from a reader")
+    }
+
+    fn from_reader_with_limits<R: Read>(
+        r: &mut R,
+        limits: &DecodeLimits,
+    ) -> Result<::TransactionHistoryResultEntry> {
+        let ledger_seq: u32 = limits::from_reader(r, limits)?;
+        let results = ::TransactionResultSet::from_reader_with_limits(r, limits)?;
+        let _: ExtensionPoint = limits::from_reader(r, limits)?;
+        Ok(::TransactionHistoryResultEntry {
+            ledger_seq,
+            results,
+        })
+    }
+}
@@ -32,7 +32,9 @@ impl ToXdr<PublicKey> for ed25519_dalek::PublicKey {
 impl<'de> FromXdr<'de, PublicKey> for ed25519_dalek::PublicKey {
     fn from_xdr(x: PublicKey) -> Result<ed25519_dalek::PublicKey> {
         match x {
-            PublicKey::Ed25519(Ed25519 { key }) => Ok(ed25519_dalek::PublicKey::from_bytes(&key).unwrap()),
+            PublicKey::Ed25519(Ed25519 { key }) => {
+                ed25519_dalek::PublicKey::from_bytes(&key).map_err(|_| Error::InvalidPublicKey)
+            }
         }
     }
 }
@@ -42,6 +44,8 @@ mod tests {
     use ed25519_dalek::PublicKey;
     use crypto::keypair::from_account_id;
     use {FromXdr, ToXdr};
+    use base64;
+    use error::Error;
 
     #[test]
     fn test_public_key() {
@@ -53,4 +57,23 @@ mod tests {
         let decoded = PublicKey::from_base64(&encoded).unwrap();
         assert_eq!(decoded, pk);
     }
+
+    #[test]
+    fn test_public_key_malformed() {
+        match PublicKey::from_base64("AAAAAIgFB9bfNKkCHpVTiLi9t0qZvyMbpIdPePCbDdNZCA/wAAAAAA") {
+            Err(Error::NonCanonicalEncoding) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        match PublicKey::from_base64("AAAAAIgFB9bfNKkCHpVTiLi9t0qZvyMbpIdPePCbDdNZCA/wAAAAAA==") {
+            Err(Error::TrailingXdrData) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        // Truncated.
+        assert!(PublicKey::from_base64("AAAAAIgFB9bfNKkCHpVTiLi9t0qZvyMbpIdPePCbDdNZCA==").is_err());
+        assert!(PublicKey::from_base64("").is_err());
+        // Not a point on the curve.
+        let mut buf = vec![0; 36];
+        buf[4] = 2;
+        assert!(PublicKey::from_base64(&base64::encode(&buf)).is_err());
+    }
 }
@@ -0,0 +1,1064 @@
+use serde_xdr::opaque_data;
+use amount::{Price, Stroops};
+use error::{Error, Result};
+use ledger_entry::MAX_SIGNERS;
+use xdr::asset::{Alphanum12, Alphanum4};
+use xdr::{Asset, DataValue, FromXdr, Payload, PublicKey, String32, String64, ToXdr};
+
+const MAX_CLAIMANTS: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hash(#[serde(with = "opaque_data::fixed_length")] pub [u8; 32]);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ExtensionPoint {
+    V0,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub last_modified_ledger_seq: u32,
+    pub data: LedgerEntryData,
+    pub ext: LedgerEntryExt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LedgerEntryData {
+    Account(AccountEntry),
+    TrustLine(TrustLineEntry),
+    Offer(OfferEntry),
+    Data(DataEntry),
+    ClaimableBalance(ClaimableBalanceEntry),
+    LiquidityPool(LiquidityPoolEntry),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LedgerEntryExt {
+    V0,
+    V1(LedgerEntryExtensionV1),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerEntryExtensionV1 {
+    pub sponsoring_id: Option<PublicKey>,
+    pub ext: ExtensionPoint,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LedgerKey {
+    Account(LedgerKeyAccount),
+    TrustLine(LedgerKeyTrustLine),
+    Offer(LedgerKeyOffer),
+    Data(LedgerKeyData),
+    ClaimableBalance(ClaimableBalanceId),
+    LiquidityPool(Hash),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerKeyAccount {
+    pub account_id: PublicKey,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerKeyTrustLine {
+    pub account_id: PublicKey,
+    pub asset: TrustLineAsset,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerKeyOffer {
+    pub seller_id: PublicKey,
+    pub offer_id: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerKeyData {
+    pub account_id: PublicKey,
+    pub data_name: String64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountEntry {
+    pub account_id: PublicKey,
+    pub balance: Stroops,
+    pub seq_num: u64,
+    pub num_sub_entries: u32,
+    pub inflation_dest: Option<PublicKey>,
+    pub flags: u32,
+    pub home_domain: String32,
+    #[serde(with = "opaque_data::fixed_length")] pub thresholds: [u8; 4],
+    pub signers: Vec<Signer>,
+    pub ext: AccountEntryExt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum AccountEntryExt {
+    V0,
+    V1(AccountEntryExtensionV1),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountEntryExtensionV1 {
+    pub liabilities: Liabilities,
+    pub ext: AccountEntryExtensionV1Ext,
+}
+
+xdr_union! {
+    pub enum AccountEntryExtensionV1Ext {
+        V0 = 0,
+        V2(AccountEntryExtensionV2) = 2,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountEntryExtensionV2 {
+    pub num_sponsored: u32,
+    pub num_sponsoring: u32,
+    pub signer_sponsoring_ids: Vec<Option<PublicKey>>,
+    pub ext: AccountEntryExtensionV2Ext,
+}
+
+xdr_union! {
+    pub enum AccountEntryExtensionV2Ext {
+        V0 = 0,
+        V3(AccountEntryExtensionV3) = 3,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountEntryExtensionV3 {
+    pub ext: ExtensionPoint,
+    pub seq_ledger: u32,
+    pub seq_time: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Liabilities {
+    pub buying: Stroops,
+    pub selling: Stroops,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Signer {
+    pub key: SignerKey,
+    pub weight: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SignerKey {
+    Ed25519(Hash),
+    PreAuthTx(Hash),
+    HashX(Hash),
+    Ed25519SignedPayload(SignedPayload),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedPayload {
+    pub ed25519: Hash,
+    pub payload: Payload,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustLineEntry {
+    pub account_id: PublicKey,
+    pub asset: TrustLineAsset,
+    pub balance: Stroops,
+    pub limit: Stroops,
+    pub flags: u32,
+    pub ext: TrustLineEntryExt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TrustLineAsset {
+    Native,
+    Alphanum4(Alphanum4),
+    Alphanum12(Alphanum12),
+    PoolShare(Hash),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TrustLineEntryExt {
+    V0,
+    V1(TrustLineEntryV1),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustLineEntryV1 {
+    pub liabilities: Liabilities,
+    pub ext: TrustLineEntryV1Ext,
+}
+
+xdr_union! {
+    pub enum TrustLineEntryV1Ext {
+        V0 = 0,
+        V2(TrustLineEntryExtensionV2) = 2,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustLineEntryExtensionV2 {
+    pub liquidity_pool_use_count: i32,
+    pub ext: ExtensionPoint,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfferEntry {
+    pub seller_id: PublicKey,
+    pub offer_id: i64,
+    pub selling: Asset,
+    pub buying: Asset,
+    pub amount: Stroops,
+    pub price: Price,
+    pub flags: u32,
+    pub ext: ExtensionPoint,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataEntry {
+    pub account_id: PublicKey,
+    pub data_name: String64,
+    pub data_value: DataValue,
+    pub ext: ExtensionPoint,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ClaimableBalanceId {
+    V0(Hash),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ClaimPredicate {
+    Unconditional,
+    And(Vec<ClaimPredicate>),
+    Or(Vec<ClaimPredicate>),
+    Not(Option<Box<ClaimPredicate>>),
+    BeforeAbsoluteTime(i64),
+    BeforeRelativeTime(i64),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Claimant {
+    V0(ClaimantV0),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimantV0 {
+    pub destination: PublicKey,
+    pub predicate: ClaimPredicate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimableBalanceEntry {
+    pub balance_id: ClaimableBalanceId,
+    pub claimants: Vec<Claimant>,
+    pub asset: Asset,
+    pub amount: Stroops,
+    pub ext: ClaimableBalanceEntryExt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ClaimableBalanceEntryExt {
+    V0,
+    V1(ClaimableBalanceEntryExtensionV1),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimableBalanceEntryExtensionV1 {
+    pub ext: ExtensionPoint,
+    pub flags: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiquidityPoolEntry {
+    pub liquidity_pool_id: Hash,
+    pub body: LiquidityPoolBody,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LiquidityPoolBody {
+    ConstantProduct(ConstantProduct),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstantProduct {
+    pub params: ConstantProductParameters,
+    pub reserve_a: Stroops,
+    pub reserve_b: Stroops,
+    pub total_pool_shares: Stroops,
+    pub pool_shares_trust_line_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstantProductParameters {
+    pub asset_a: Asset,
+    pub asset_b: Asset,
+    pub fee: i32,
+}
+
+fn to_optional_key(key: &Option<ed25519_dalek::PublicKey>) -> Result<Option<PublicKey>> {
+    match *key {
+        None => Ok(None),
+        Some(ref pk) => Ok(Some(pk.to_xdr()?)),
+    }
+}
+
+fn from_optional_key(key: Option<PublicKey>) -> Result<Option<ed25519_dalek::PublicKey>> {
+    match key {
+        None => Ok(None),
+        Some(pk) => Ok(Some(ed25519_dalek::PublicKey::from_xdr(pk)?)),
+    }
+}
+
+impl ToXdr<LedgerEntry> for ::LedgerEntry {
+    fn to_xdr(&self) -> Result<LedgerEntry> {
+        let ext = match self.extension {
+            None => LedgerEntryExt::V0,
+            Some(ref v1) => LedgerEntryExt::V1(LedgerEntryExtensionV1 {
+                sponsoring_id: to_optional_key(&v1.sponsoring_id)?,
+                ext: ExtensionPoint::V0,
+            }),
+        };
+        Ok(LedgerEntry {
+            last_modified_ledger_seq: self.last_modified_ledger_seq,
+            data: self.data.to_xdr()?,
+            ext,
+        })
+    }
+}
+
+impl<'de> FromXdr<'de, LedgerEntry> for ::LedgerEntry {
+    fn from_xdr(entry: LedgerEntry) -> Result<::LedgerEntry> {
+        let extension = match entry.ext {
+            LedgerEntryExt::V0 => None,
+            LedgerEntryExt::V1(v1) => Some(::LedgerEntryExtensionV1 {
+                sponsoring_id: from_optional_key(v1.sponsoring_id)?,
+            }),
+        };
+        Ok(::LedgerEntry {
+            last_modified_ledger_seq: entry.last_modified_ledger_seq,
+            data: ::LedgerEntryData::from_xdr(entry.data)?,
+            extension,
+        })
+    }
+}
+
+impl ToXdr<LedgerEntryData> for ::LedgerEntryData {
+    fn to_xdr(&self) -> Result<LedgerEntryData> {
+        match *self {
+            ::LedgerEntryData::Account(ref entry) => Ok(LedgerEntryData::Account(entry.to_xdr()?)),
+            ::LedgerEntryData::TrustLine(ref entry) => {
+                Ok(LedgerEntryData::TrustLine(entry.to_xdr()?))
+            }
+            ::LedgerEntryData::Offer(ref entry) => Ok(LedgerEntryData::Offer(entry.to_xdr()?)),
+            ::LedgerEntryData::Data(ref entry) => Ok(LedgerEntryData::Data(entry.to_xdr()?)),
+            ::LedgerEntryData::ClaimableBalance(ref entry) => {
+                Ok(LedgerEntryData::ClaimableBalance(entry.to_xdr()?))
+            }
+            ::LedgerEntryData::LiquidityPool(ref entry) => {
+                Ok(LedgerEntryData::LiquidityPool(entry.to_xdr()?))
+            }
+        }
+    }
+}
+
+impl<'de> FromXdr<'de, LedgerEntryData> for ::LedgerEntryData {
+    fn from_xdr(data: LedgerEntryData) -> Result<::LedgerEntryData> {
+        match data {
+            LedgerEntryData::Account(entry) => {
+                Ok(::LedgerEntryData::Account(::AccountEntry::from_xdr(entry)?))
+            }
+            LedgerEntryData::TrustLine(entry) => {
+                Ok(::LedgerEntryData::TrustLine(::TrustLineEntry::from_xdr(entry)?))
+            }
+            LedgerEntryData::Offer(entry) => {
+                Ok(::LedgerEntryData::Offer(::OfferEntry::from_xdr(entry)?))
+            }
+            LedgerEntryData::Data(entry) => {
+                Ok(::LedgerEntryData::Data(::DataEntry::from_xdr(entry)?))
+            }
+            LedgerEntryData::ClaimableBalance(entry) => Ok(::LedgerEntryData::ClaimableBalance(
+                ::ClaimableBalanceEntry::from_xdr(entry)?,
+            )),
+            LedgerEntryData::LiquidityPool(entry) => Ok(::LedgerEntryData::LiquidityPool(
+                ::LiquidityPoolEntry::from_xdr(entry)?,
+            )),
+        }
+    }
+}
+
+impl ToXdr<LedgerKey> for ::LedgerKey {
+    fn to_xdr(&self) -> Result<LedgerKey> {
+        match *self {
+            ::LedgerKey::Account { ref account_id } => Ok(LedgerKey::Account(LedgerKeyAccount {
+                account_id: account_id.to_xdr()?,
+            })),
+            ::LedgerKey::TrustLine {
+                ref account_id,
+                ref asset,
+            } => Ok(LedgerKey::TrustLine(LedgerKeyTrustLine {
+                account_id: account_id.to_xdr()?,
+                asset: asset.to_xdr()?,
+            })),
+            ::LedgerKey::Offer {
+                ref seller_id,
+                offer_id,
+            } => Ok(LedgerKey::Offer(LedgerKeyOffer {
+                seller_id: seller_id.to_xdr()?,
+                offer_id,
+            })),
+            ::LedgerKey::Data {
+                ref account_id,
+                ref name,
+            } => Ok(LedgerKey::Data(LedgerKeyData {
+                account_id: account_id.to_xdr()?,
+                data_name: String64::new(name.clone()).ok_or(Error::InvalidDataName)?,
+            })),
+            ::LedgerKey::ClaimableBalance { balance_id } => Ok(LedgerKey::ClaimableBalance(
+                ClaimableBalanceId::V0(Hash(balance_id)),
+            )),
+            ::LedgerKey::LiquidityPool { liquidity_pool_id } => {
+                Ok(LedgerKey::LiquidityPool(Hash(liquidity_pool_id)))
+            }
+        }
+    }
+}
+
+impl<'de> FromXdr<'de, LedgerKey> for ::LedgerKey {
+    fn from_xdr(key: LedgerKey) -> Result<::LedgerKey> {
+        match key {
+            LedgerKey::Account(key) => Ok(::LedgerKey::Account {
+                account_id: ed25519_dalek::PublicKey::from_xdr(key.account_id)?,
+            }),
+            LedgerKey::TrustLine(key) => Ok(::LedgerKey::TrustLine {
+                account_id: ed25519_dalek::PublicKey::from_xdr(key.account_id)?,
+                asset: ::TrustLineAsset::from_xdr(key.asset)?,
+            }),
+            LedgerKey::Offer(key) => Ok(::LedgerKey::Offer {
+                seller_id: ed25519_dalek::PublicKey::from_xdr(key.seller_id)?,
+                offer_id: key.offer_id,
+            }),
+            LedgerKey::Data(key) => Ok(::LedgerKey::Data {
+                account_id: ed25519_dalek::PublicKey::from_xdr(key.account_id)?,
+                name: key.data_name.into_string(),
+            }),
+            LedgerKey::ClaimableBalance(ClaimableBalanceId::V0(Hash(balance_id))) => {
+                Ok(::LedgerKey::ClaimableBalance { balance_id })
+            }
+            LedgerKey::LiquidityPool(Hash(liquidity_pool_id)) => {
+                Ok(::LedgerKey::LiquidityPool { liquidity_pool_id })
+            }
+        }
+    }
+}
+
+impl ToXdr<AccountEntry> for ::AccountEntry {
+    fn to_xdr(&self) -> Result<AccountEntry> {
+        if self.signers.len() > MAX_SIGNERS {
+            return Err(Error::InvalidLedgerEntry);
+        }
+        let signers_res: Result<Vec<_>> = self.signers.iter().map(|s| s.to_xdr()).collect();
+        let home_domain =
+            String32::new(self.home_domain.clone()).ok_or(Error::InvalidLedgerEntry)?;
+        let thresholds = [
+            self.thresholds.master_weight,
+            self.thresholds.low,
+            self.thresholds.medium,
+            self.thresholds.high,
+        ];
+        let ext = match self.extension {
+            None => AccountEntryExt::V0,
+            Some(ref v1) => AccountEntryExt::V1(to_account_extension_v1(v1)?),
+        };
+        Ok(AccountEntry {
+            account_id: self.account_id.to_xdr()?,
+            balance: self.balance.clone(),
+            seq_num: self.sequence,
+            num_sub_entries: self.num_sub_entries,
+            inflation_dest: to_optional_key(&self.inflation_dest)?,
+            flags: self.flags,
+            home_domain,
+            thresholds,
+            signers: signers_res?,
+            ext,
+        })
+    }
+}
+
+fn to_account_extension_v1(v1: &::AccountEntryExtensionV1) -> Result<AccountEntryExtensionV1> {
+    let ext = match v1.v2 {
+        None => AccountEntryExtensionV1Ext::V0,
+        Some(ref v2) => {
+            if v2.signer_sponsoring_ids.len() > MAX_SIGNERS {
+                return Err(Error::InvalidLedgerEntry);
+            }
+            let ids_res: Result<Vec<_>> = v2.signer_sponsoring_ids
+                .iter()
+                .map(to_optional_key)
+                .collect();
+            let ext = match v2.v3 {
+                None => AccountEntryExtensionV2Ext::V0,
+                Some(ref v3) => AccountEntryExtensionV2Ext::V3(AccountEntryExtensionV3 {
+                    ext: ExtensionPoint::V0,
+                    seq_ledger: v3.seq_ledger,
+                    seq_time: v3.seq_time,
+                }),
+            };
+            AccountEntryExtensionV1Ext::V2(AccountEntryExtensionV2 {
+                num_sponsored: v2.num_sponsored,
+                num_sponsoring: v2.num_sponsoring,
+                signer_sponsoring_ids: ids_res?,
+                ext,
+            })
+        }
+    };
+    Ok(AccountEntryExtensionV1 {
+        liabilities: v1.liabilities.to_xdr()?,
+        ext,
+    })
+}
+
+impl<'de> FromXdr<'de, AccountEntry> for ::AccountEntry {
+    fn from_xdr(entry: AccountEntry) -> Result<::AccountEntry> {
+        if entry.signers.len() > MAX_SIGNERS {
+            return Err(Error::InvalidLedgerEntry);
+        }
+        let signers_res: Result<Vec<_>> = entry
+            .signers
+            .into_iter()
+            .map(::AccountSigner::from_xdr)
+            .collect();
+        let extension = match entry.ext {
+            AccountEntryExt::V0 => None,
+            AccountEntryExt::V1(v1) => Some(from_account_extension_v1(v1)?),
+        };
+        Ok(::AccountEntry {
+            account_id: ed25519_dalek::PublicKey::from_xdr(entry.account_id)?,
+            balance: entry.balance,
+            sequence: entry.seq_num,
+            num_sub_entries: entry.num_sub_entries,
+            inflation_dest: from_optional_key(entry.inflation_dest)?,
+            flags: entry.flags,
+            home_domain: entry.home_domain.into_string(),
+            thresholds: ::Thresholds {
+                master_weight: entry.thresholds[0],
+                low: entry.thresholds[1],
+                medium: entry.thresholds[2],
+                high: entry.thresholds[3],
+            },
+            signers: signers_res?,
+            extension,
+        })
+    }
+}
+
+fn from_account_extension_v1(v1: AccountEntryExtensionV1) -> Result<::AccountEntryExtensionV1> {
+    let v2 = match v1.ext {
+        AccountEntryExtensionV1Ext::V0 => None,
+        AccountEntryExtensionV1Ext::V2(v2) => {
+            if v2.signer_sponsoring_ids.len() > MAX_SIGNERS {
+                return Err(Error::InvalidLedgerEntry);
+            }
+            let ids_res: Result<Vec<_>> = v2.signer_sponsoring_ids
+                .into_iter()
+                .map(from_optional_key)
+                .collect();
+            let v3 = match v2.ext {
+                AccountEntryExtensionV2Ext::V0 => None,
+                AccountEntryExtensionV2Ext::V3(v3) => Some(::AccountEntryExtensionV3 {
+                    seq_ledger: v3.seq_ledger,
+                    seq_time: v3.seq_time,
+                }),
+            };
+            Some(::AccountEntryExtensionV2 {
+                num_sponsored: v2.num_sponsored,
+                num_sponsoring: v2.num_sponsoring,
+                signer_sponsoring_ids: ids_res?,
+                v3,
+            })
+        }
+    };
+    Ok(::AccountEntryExtensionV1 {
+        liabilities: ::Liabilities::from_xdr(v1.liabilities)?,
+        v2,
+    })
+}
+
+impl ToXdr<Liabilities> for ::Liabilities {
+    fn to_xdr(&self) -> Result<Liabilities> {
+        Ok(Liabilities {
+            buying: self.buying.clone(),
+            selling: self.selling.clone(),
+        })
+    }
+}
+
+impl<'de> FromXdr<'de, Liabilities> for ::Liabilities {
+    fn from_xdr(liabilities: Liabilities) -> Result<::Liabilities> {
+        Ok(::Liabilities {
+            buying: liabilities.buying,
+            selling: liabilities.selling,
+        })
+    }
+}
+
+impl ToXdr<Signer> for ::AccountSigner {
+    fn to_xdr(&self) -> Result<Signer> {
+        let key = match self.key {
+            ::SignerKey::Ed25519(key) => SignerKey::Ed25519(Hash(key)),
+            ::SignerKey::PreAuthTx(hash) => SignerKey::PreAuthTx(Hash(hash)),
+            ::SignerKey::HashX(hash) => SignerKey::HashX(Hash(hash)),
+            ::SignerKey::Ed25519SignedPayload { key, ref payload } => {
+                SignerKey::Ed25519SignedPayload(SignedPayload {
+                    ed25519: Hash(key),
+                    payload: Payload::new(payload.clone()).ok_or(Error::InvalidLedgerEntry)?,
+                })
+            }
+        };
+        Ok(Signer {
+            key,
+            weight: self.weight,
+        })
+    }
+}
+
+impl<'de> FromXdr<'de, Signer> for ::AccountSigner {
+    fn from_xdr(signer: Signer) -> Result<::AccountSigner> {
+        let key = match signer.key {
+            SignerKey::Ed25519(Hash(key)) => ::SignerKey::Ed25519(key),
+            SignerKey::PreAuthTx(Hash(hash)) => ::SignerKey::PreAuthTx(hash),
+            SignerKey::HashX(Hash(hash)) => ::SignerKey::HashX(hash),
+            SignerKey::Ed25519SignedPayload(signed) => ::SignerKey::Ed25519SignedPayload {
+                key: signed.ed25519.0,
+                payload: signed.payload.into_vec(),
+            },
+        };
+        Ok(::AccountSigner {
+            key,
+            weight: signer.weight,
+        })
+    }
+}
+
+impl ToXdr<TrustLineEntry> for ::TrustLineEntry {
+    fn to_xdr(&self) -> Result<TrustLineEntry> {
+        let ext = match self.extension {
+            None => TrustLineEntryExt::V0,
+            Some(ref v1) => {
+                let ext = match v1.liquidity_pool_use_count {
+                    None => TrustLineEntryV1Ext::V0,
+                    Some(count) => TrustLineEntryV1Ext::V2(TrustLineEntryExtensionV2 {
+                        liquidity_pool_use_count: count,
+                        ext: ExtensionPoint::V0,
+                    }),
+                };
+                TrustLineEntryExt::V1(TrustLineEntryV1 {
+                    liabilities: v1.liabilities.to_xdr()?,
+                    ext,
+                })
+            }
+        };
+        Ok(TrustLineEntry {
+            account_id: self.account_id.to_xdr()?,
+            asset: self.asset.to_xdr()?,
+            balance: self.balance.clone(),
+            limit: self.limit.clone(),
+            flags: self.flags,
+            ext,
+        })
+    }
+}
+
+impl<'de> FromXdr<'de, TrustLineEntry> for ::TrustLineEntry {
+    fn from_xdr(entry: TrustLineEntry) -> Result<::TrustLineEntry> {
+        let extension = match entry.ext {
+            TrustLineEntryExt::V0 => None,
+            TrustLineEntryExt::V1(v1) => {
+                let liquidity_pool_use_count = match v1.ext {
+                    TrustLineEntryV1Ext::V0 => None,
+                    TrustLineEntryV1Ext::V2(v2) => Some(v2.liquidity_pool_use_count),
+                };
+                Some(::TrustLineEntryExtensionV1 {
+                    liabilities: ::Liabilities::from_xdr(v1.liabilities)?,
+                    liquidity_pool_use_count,
+                })
+            }
+        };
+        Ok(::TrustLineEntry {
+            account_id: ed25519_dalek::PublicKey::from_xdr(entry.account_id)?,
+            asset: ::TrustLineAsset::from_xdr(entry.asset)?,
+            balance: entry.balance,
+            limit: entry.limit,
+            flags: entry.flags,
+            extension,
+        })
+    }
+}
+
+impl ToXdr<TrustLineAsset> for ::TrustLineAsset {
+    fn to_xdr(&self) -> Result<TrustLineAsset> {
+        match *self {
+            ::TrustLineAsset::Native => Ok(TrustLineAsset::Native),
+            ::TrustLineAsset::Credit(ref credit) => {
+                match ::Asset::Credit(credit.clone()).to_xdr()? {
+                    Asset::Native => Ok(TrustLineAsset::Native),
+                    Asset::Alphanum4(inner) => Ok(TrustLineAsset::Alphanum4(inner)),
+                    Asset::Alphanum12(inner) => Ok(TrustLineAsset::Alphanum12(inner)),
+                }
+            }
+            ::TrustLineAsset::PoolShare(pool_id) => Ok(TrustLineAsset::PoolShare(Hash(pool_id))),
+        }
+    }
+}
+
+impl<'de> FromXdr<'de, TrustLineAsset> for ::TrustLineAsset {
+    fn from_xdr(asset: TrustLineAsset) -> Result<::TrustLineAsset> {
+        let asset = match asset {
+            TrustLineAsset::Native => Asset::Native,
+            TrustLineAsset::Alphanum4(inner) => Asset::Alphanum4(inner),
+            TrustLineAsset::Alphanum12(inner) => Asset::Alphanum12(inner),
+            TrustLineAsset::PoolShare(Hash(pool_id)) => {
+                return Ok(::TrustLineAsset::PoolShare(pool_id))
+            }
+        };
+        Ok(::Asset::from_xdr(asset)?.into())
+    }
+}
+
+impl ToXdr<OfferEntry> for ::OfferEntry {
+    fn to_xdr(&self) -> Result<OfferEntry> {
+        Ok(OfferEntry {
+            seller_id: self.seller_id.to_xdr()?,
+            offer_id: self.offer_id,
+            selling: self.selling.to_xdr()?,
+            buying: self.buying.to_xdr()?,
+            amount: self.amount.clone(),
+            price: self.price.clone(),
+            flags: self.flags,
+            ext: ExtensionPoint::V0,
+        })
+    }
+}
+
+impl<'de> FromXdr<'de, OfferEntry> for ::OfferEntry {
+    fn from_xdr(entry: OfferEntry) -> Result<::OfferEntry> {
+        Ok(::OfferEntry {
+            seller_id: ed25519_dalek::PublicKey::from_xdr(entry.seller_id)?,
+            offer_id: entry.offer_id,
+            selling: ::Asset::from_xdr(entry.selling)?,
+            buying: ::Asset::from_xdr(entry.buying)?,
+            amount: entry.amount,
+            price: entry.price,
+            flags: entry.flags,
+        })
+    }
+}
+
+impl ToXdr<DataEntry> for ::DataEntry {
+    fn to_xdr(&self) -> Result<DataEntry> {
+        Ok(DataEntry {
+            account_id: self.account_id.to_xdr()?,
+            data_name: String64::new(self.name.clone()).ok_or(Error::InvalidDataName)?,
+            data_value: DataValue::new(self.value.clone()).ok_or(Error::InvalidDataValue)?,
+            ext: ExtensionPoint::V0,
+        })
+    }
+}
+
+impl<'de> FromXdr<'de, DataEntry> for ::DataEntry {
+    fn from_xdr(entry: DataEntry) -> Result<::DataEntry> {
+        Ok(::DataEntry {
+            account_id: ed25519_dalek::PublicKey::from_xdr(entry.account_id)?,
+            name: entry.data_name.into_string(),
+            value: entry.data_value.into_vec(),
+        })
+    }
+}
+
+impl ToXdr<ClaimableBalanceEntry> for ::ClaimableBalanceEntry {
+    fn to_xdr(&self) -> Result<ClaimableBalanceEntry> {
+        if self.claimants.len() > MAX_CLAIMANTS {
+            return Err(Error::InvalidLedgerEntry);
+        }
+        let claimants_res: Result<Vec<_>> = self.claimants
+            .iter()
+            .map(|claimant| {
+                Ok(Claimant::V0(ClaimantV0 {
+                    destination: claimant.destination.to_xdr()?,
+                    predicate: claimant.predicate.to_xdr()?,
+                }))
+            })
+            .collect();
+        let ext = match self.flags {
+            None => ClaimableBalanceEntryExt::V0,
+            Some(flags) => ClaimableBalanceEntryExt::V1(ClaimableBalanceEntryExtensionV1 {
+                ext: ExtensionPoint::V0,
+                flags,
+            }),
+        };
+        Ok(ClaimableBalanceEntry {
+            balance_id: ClaimableBalanceId::V0(Hash(self.balance_id)),
+            claimants: claimants_res?,
+            asset: self.asset.to_xdr()?,
+            amount: self.amount.clone(),
+            ext,
+        })
+    }
+}
+
+impl<'de> FromXdr<'de, ClaimableBalanceEntry> for ::ClaimableBalanceEntry {
+    fn from_xdr(entry: ClaimableBalanceEntry) -> Result<::ClaimableBalanceEntry> {
+        if entry.claimants.len() > MAX_CLAIMANTS {
+            return Err(Error::InvalidLedgerEntry);
+        }
+        let claimants_res: Result<Vec<_>> = entry
+            .claimants
+            .into_iter()
+            .map(|claimant| match claimant {
+                Claimant::V0(v0) => Ok(::Claimant {
+                    destination: ed25519_dalek::PublicKey::from_xdr(v0.destination)?,
+                    predicate: ::ClaimPredicate::from_xdr(v0.predicate)?,
+                }),
+            })
+            .collect();
+        let ClaimableBalanceId::V0(Hash(balance_id)) = entry.balance_id;
+        let flags = match entry.ext {
+            ClaimableBalanceEntryExt::V0 => None,
+            ClaimableBalanceEntryExt::V1(v1) => Some(v1.flags),
+        };
+        Ok(::ClaimableBalanceEntry {
+            balance_id,
+            claimants: claimants_res?,
+            asset: ::Asset::from_xdr(entry.asset)?,
+            amount: entry.amount,
+            flags,
+        })
+    }
+}
+
+impl ToXdr<ClaimPredicate> for ::ClaimPredicate {
+    fn to_xdr(&self) -> Result<ClaimPredicate> {
+        match *self {
+            ::ClaimPredicate::Unconditional => Ok(ClaimPredicate::Unconditional),
+            ::ClaimPredicate::And(ref left, ref right) => {
+                Ok(ClaimPredicate::And(vec![left.to_xdr()?, right.to_xdr()?]))
+            }
+            ::ClaimPredicate::Or(ref left, ref right) => {
+                Ok(ClaimPredicate::Or(vec![left.to_xdr()?, right.to_xdr()?]))
+            }
+            ::ClaimPredicate::Not(ref inner) => {
+                Ok(ClaimPredicate::Not(Some(Box::new(inner.to_xdr()?))))
+            }
+            ::ClaimPredicate::BeforeAbsoluteTime(time) => {
+                Ok(ClaimPredicate::BeforeAbsoluteTime(time))
+            }
+            ::ClaimPredicate::BeforeRelativeTime(time) => {
+                Ok(ClaimPredicate::BeforeRelativeTime(time))
+            }
+        }
+    }
+}
+
+impl<'de> FromXdr<'de, ClaimPredicate> for ::ClaimPredicate {
+    fn from_xdr(predicate: ClaimPredicate) -> Result<::ClaimPredicate> {
+        match predicate {
+            ClaimPredicate::Unconditional => Ok(::ClaimPredicate::Unconditional),
+            ClaimPredicate::And(predicates) => {
+                let (left, right) = from_predicate_pair(predicates)?;
+                Ok(::ClaimPredicate::And(left, right))
+            }
+            ClaimPredicate::Or(predicates) => {
+                let (left, right) = from_predicate_pair(predicates)?;
+                Ok(::ClaimPredicate::Or(left, right))
+            }
+            ClaimPredicate::Not(Some(inner)) => Ok(::ClaimPredicate::Not(Box::new(
+                ::ClaimPredicate::from_xdr(*inner)?,
+            ))),
+            ClaimPredicate::Not(None) => Err(Error::InvalidLedgerEntry),
+            ClaimPredicate::BeforeAbsoluteTime(time) => {
+                Ok(::ClaimPredicate::BeforeAbsoluteTime(time))
+            }
+            ClaimPredicate::BeforeRelativeTime(time) => {
+                Ok(::ClaimPredicate::BeforeRelativeTime(time))
+            }
+        }
+    }
+}
+
+fn from_predicate_pair(
+    predicates: Vec<ClaimPredicate>,
+) -> Result<(Box<::ClaimPredicate>, Box<::ClaimPredicate>)> {
+    if predicates.len() != 2 {
+        return Err(Error::InvalidLedgerEntry);
+    }
+    let mut iter = predicates.into_iter();
+    let left = ::ClaimPredicate::from_xdr(iter.next().unwrap())?;
+    let right = ::ClaimPredicate::from_xdr(iter.next().unwrap())?;
+    Ok((Box::new(left), Box::new(right)))
+}
+
+impl ToXdr<LiquidityPoolEntry> for ::LiquidityPoolEntry {
+    fn to_xdr(&self) -> Result<LiquidityPoolEntry> {
+        let params = ConstantProductParameters {
+            asset_a: self.asset_a.to_xdr()?,
+            asset_b: self.asset_b.to_xdr()?,
+            fee: self.fee,
+        };
+        Ok(LiquidityPoolEntry {
+            liquidity_pool_id: Hash(self.liquidity_pool_id),
+            body: LiquidityPoolBody::ConstantProduct(ConstantProduct {
+                params,
+                reserve_a: self.reserve_a.clone(),
+                reserve_b: self.reserve_b.clone(),
+                total_pool_shares: self.total_pool_shares.clone(),
+                pool_shares_trust_line_count: self.pool_shares_trust_line_count,
+            }),
+        })
+    }
+}
+
+impl<'de> FromXdr<'de, LiquidityPoolEntry> for ::LiquidityPoolEntry {
+    fn from_xdr(entry: LiquidityPoolEntry) -> Result<::LiquidityPoolEntry> {
+        let LiquidityPoolBody::ConstantProduct(pool) = entry.body;
+        Ok(::LiquidityPoolEntry {
+            liquidity_pool_id: entry.liquidity_pool_id.0,
+            asset_a: ::Asset::from_xdr(pool.params.asset_a)?,
+            asset_b: ::Asset::from_xdr(pool.params.asset_b)?,
+            fee: pool.params.fee,
+            reserve_a: pool.reserve_a,
+            reserve_b: pool.reserve_b,
+            total_pool_shares: pool.total_pool_shares,
+            pool_shares_trust_line_count: pool.pool_shares_trust_line_count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crypto::keypair::from_account_id;
+    use error::Error;
+    use {ClaimPredicate, LedgerEntry, LedgerEntryData, LedgerKey, Stroops, TrustLineAsset};
+    use {FromXdr, ToXdr};
+
+    const ACCOUNT_ID: &str = "GCEAKB6W342KSAQ6SVJYROF5W5FJTPZDDOSIOT3Y6CNQ3U2ZBAH7AQN3";
+
+    fn round_trip(encoded: &str) -> LedgerEntry {
+        let entry = LedgerEntry::from_base64(encoded).unwrap();
+        assert_eq!(entry.to_base64().unwrap(), encoded);
+        entry
+    }
+
+    #[test]
+    fn test_account_entry() {
+        let entry = round_trip(
+            "AAAAKgAAAAAAAAAAiAUH1t80qQIelVOIuL23Spm/Ixukh0948JsN01kID/AAAAAABfXhAAAAAAAAADA5AAAAAQAA\
+             AAAAAAABAAAAC2V4YW1wbGUuY29tAAEAAgMAAAABAAAAAAABAgMEBQYHCAkKCwwNDg8QERITFBUWFxgZGhscHR4f\
+             AAAABQAAAAEAAAAAAAAACgAAAAAAAAAUAAAAAgAAAAEAAAACAAAAAQAAAAEAAAAAiAUH1t80qQIelVOIuL23Spm/\
+             Ixukh0948JsN01kID/AAAAADAAAAAAAAAE0AAAAAX14QAAAAAAEAAAAAAAAAAA==",
+        );
+        let public = from_account_id(ACCOUNT_ID).unwrap();
+        assert_eq!(entry.last_modified_ledger_seq, 42);
+        assert_eq!(entry.sponsoring_id(), None);
+        assert!(entry.extension.is_some());
+        let account = match entry.data {
+            LedgerEntryData::Account(ref account) => account,
+            ref other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(account.account_id, public);
+        assert_eq!(account.balance, Stroops::new(100_000_000));
+        assert_eq!(account.sequence, 12345);
+        assert_eq!(account.home_domain, "example.com");
+        assert_eq!(account.thresholds.master_weight, 1);
+        assert_eq!(account.thresholds.high, 3);
+        assert_eq!(account.signers.len(), 1);
+        assert_eq!(account.signers[0].weight, 5);
+        assert_eq!(account.liabilities().unwrap().selling, Stroops::new(20));
+        let sponsorship = account.sponsorship().unwrap();
+        assert_eq!(sponsorship.signer_sponsoring_ids, vec![Some(public)]);
+        let v3 = sponsorship.v3.as_ref().unwrap();
+        assert_eq!(v3.seq_ledger, 77);
+        assert_eq!(v3.seq_time, 1_600_000_000);
+
+        let key = entry.ledger_key();
+        assert_eq!(key, LedgerKey::Account { account_id: public });
+        assert_eq!(
+            key.to_base64().unwrap(),
+            "AAAAAAAAAACIBQfW3zSpAh6VU4i4vbdKmb8jG6SHT3jwmw3TWQgP8A=="
+        );
+        assert_eq!(
+            LedgerKey::from_base64("AAAAAAAAAACIBQfW3zSpAh6VU4i4vbdKmb8jG6SHT3jwmw3TWQgP8A==").unwrap(),
+            key
+        );
+    }
+
+    #[test]
+    fn test_claimable_balance_entry() {
+        let entry = round_trip(
+            "AAAABwAAAAQAAAAABwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcAAAABAAAAAAAAAACIBQfW3zSpAh6V\
+             U4i4vbdKmb8jG6SHT3jwmw3TWQgP8AAAAAEAAAACAAAAAwAAAAEAAAAEAAAAAAAAAGQAAAACAAAAAgAAAAAAAAAF\
+             AAAAAAAAADwAAAAAAAAAAAAAAfQAAAABAAAAAAAAAAEAAAAA",
+        );
+        let balance = match entry.data {
+            LedgerEntryData::ClaimableBalance(ref balance) => balance,
+            ref other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(balance.balance_id, [7; 32]);
+        assert_eq!(balance.amount, Stroops::new(500));
+        assert_eq!(balance.flags, Some(1));
+        assert_eq!(
+            balance.claimants[0].predicate,
+            ClaimPredicate::And(
+                Box::new(ClaimPredicate::Not(Box::new(
+                    ClaimPredicate::BeforeAbsoluteTime(100)
+                ))),
+                Box::new(ClaimPredicate::Or(
+                    Box::new(ClaimPredicate::Unconditional),
+                    Box::new(ClaimPredicate::BeforeRelativeTime(60)),
+                )),
+            )
+        );
+        assert_eq!(
+            entry.ledger_key(),
+            LedgerKey::ClaimableBalance {
+                balance_id: [7; 32],
+            }
+        );
+    }
+
+    #[test]
+    fn test_trust_line_entry() {
+        let entry = round_trip(
+            "AAAACAAAAAEAAAAAiAUH1t80qQIelVOIuL23Spm/Ixukh0948JsN01kID/AAAAADCQkJCQkJCQkJCQkJCQkJCQkJ\
+             CQkJCQkJCQkJCQkJCQkAAAAAAAAABQAAAAAAAAPoAAAAAQAAAAEAAAAAAAAAAQAAAAAAAAACAAAAAgAAAAMAAAAA\
+             AAAAAA==",
+        );
+        let trust_line = match entry.data {
+            LedgerEntryData::TrustLine(ref trust_line) => trust_line,
+            ref other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(trust_line.asset, TrustLineAsset::PoolShare([9; 32]));
+        assert_eq!(trust_line.limit, Stroops::new(1000));
+        let v1 = trust_line.extension.as_ref().unwrap();
+        assert_eq!(v1.liabilities.buying, Stroops::new(1));
+        assert_eq!(v1.liquidity_pool_use_count, Some(3));
+    }
+
+    #[test]
+    fn test_invalid_entries() {
+        // A claimable balance whose `and` predicate has a single operand.
+        let res = LedgerEntry::from_base64(
+            "AAAABwAAAAQAAAAABwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcAAAABAAAAAAAAAACIBQfW3zSpAh6V\
+             U4i4vbdKmb8jG6SHT3jwmw3TWQgP8AAAAAEAAAABAAAAAAAAAAAAAAAAAAAB9AAAAAAAAAAA",
+        );
+        match res {
+            Err(Error::InvalidLedgerEntry) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        // A trust line v1 extension with the unassigned discriminant 1.
+        let res = LedgerEntry::from_base64(
+            "AAAACAAAAAEAAAAAiAUH1t80qQIelVOIuL23Spm/Ixukh0948JsN01kID/AAAAADCQkJCQkJCQkJCQkJCQkJCQkJ\
+             CQkJCQkJCQkJCQkJCQkAAAAAAAAABQAAAAAAAAPoAAAAAQAAAAEAAAAAAAAAAQAAAAAAAAACAAAAAQAAAAMAAAAA\
+             AAAAAA==",
+        );
+        assert!(res.is_err());
+    }
+}
@@ -0,0 +1,239 @@
+use amount::Stroops;
+use error::{Error, Result};
+use ledger_header::MAX_UPGRADES;
+use xdr::ledger_entry::{ExtensionPoint, Hash};
+use xdr::{FromXdr, PublicKey, Signature, ToXdr, UpgradeType};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerHeader {
+    pub ledger_version: u32,
+    pub previous_ledger_hash: Hash,
+    pub scp_value: StellarValue,
+    pub tx_set_result_hash: Hash,
+    pub bucket_list_hash: Hash,
+    pub ledger_seq: u32,
+    pub total_coins: Stroops,
+    pub fee_pool: Stroops,
+    pub inflation_seq: u32,
+    pub id_pool: u64,
+    pub base_fee: u32,
+    pub base_reserve: u32,
+    pub max_tx_set_size: u32,
+    pub skip_list: [Hash; 4],
+    pub ext: LedgerHeaderExt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LedgerHeaderExt {
+    V0,
+    V1(LedgerHeaderExtensionV1),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerHeaderExtensionV1 {
+    pub flags: u32,
+    pub ext: ExtensionPoint,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StellarValue {
+    pub tx_set_hash: Hash,
+    pub close_time: u64,
+    pub upgrades: Vec<UpgradeType>,
+    pub ext: StellarValueExt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum StellarValueExt {
+    Basic,
+    Signed(LedgerCloseValueSignature),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerCloseValueSignature {
+    pub node_id: PublicKey,
+    pub signature: Signature,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerHeaderHistoryEntry {
+    pub hash: Hash,
+    pub header: LedgerHeader,
+    pub ext: ExtensionPoint,
+}
+
+impl ToXdr<LedgerHeader> for ::LedgerHeader {
+    fn to_xdr(&self) -> Result<LedgerHeader> {
+        let ext = match self.flags {
+            None => LedgerHeaderExt::V0,
+            Some(flags) => LedgerHeaderExt::V1(LedgerHeaderExtensionV1 {
+                flags,
+                ext: ExtensionPoint::V0,
+            }),
+        };
+        let skip = &self.skip_list;
+        Ok(LedgerHeader {
+            ledger_version: self.ledger_version,
+            previous_ledger_hash: Hash(self.previous_ledger_hash),
+            scp_value: self.scp_value.to_xdr()?,
+            tx_set_result_hash: Hash(self.tx_set_result_hash),
+            bucket_list_hash: Hash(self.bucket_list_hash),
+            ledger_seq: self.ledger_seq,
+            total_coins: self.total_coins.clone(),
+            fee_pool: self.fee_pool.clone(),
+            inflation_seq: self.inflation_seq,
+            id_pool: self.id_pool,
+            base_fee: self.base_fee,
+            base_reserve: self.base_reserve,
+            max_tx_set_size: self.max_tx_set_size,
+            skip_list: [Hash(skip[0]), Hash(skip[1]), Hash(skip[2]), Hash(skip[3])],
+            ext,
+        })
+    }
+}
+
+impl<'de> FromXdr<'de, LedgerHeader> for ::LedgerHeader {
+    fn from_xdr(header: LedgerHeader) -> Result<::LedgerHeader> {
+        let flags = match header.ext {
+            LedgerHeaderExt::V0 => None,
+            LedgerHeaderExt::V1(v1) => Some(v1.flags),
+        };
+        let [skip0, skip1, skip2, skip3] = header.skip_list;
+        Ok(::LedgerHeader {
+            ledger_version: header.ledger_version,
+            previous_ledger_hash: header.previous_ledger_hash.0,
+            scp_value: ::StellarValue::from_xdr(header.scp_value)?,
+            tx_set_result_hash: header.tx_set_result_hash.0,
+            bucket_list_hash: header.bucket_list_hash.0,
+            ledger_seq: header.ledger_seq,
+            total_coins: header.total_coins,
+            fee_pool: header.fee_pool,
+            inflation_seq: header.inflation_seq,
+            id_pool: header.id_pool,
+            base_fee: header.base_fee,
+            base_reserve: header.base_reserve,
+            max_tx_set_size: header.max_tx_set_size,
+            skip_list: [skip0.0, skip1.0, skip2.0, skip3.0],
+            flags,
+        })
+    }
+}
+
+impl ToXdr<StellarValue> for ::StellarValue {
+    fn to_xdr(&self) -> Result<StellarValue> {
+        if self.upgrades.len() > MAX_UPGRADES {
+            return Err(Error::InvalidLedgerHeader);
+        }
+        let upgrades = self.upgrades
+            .iter()
+            .map(|upgrade| UpgradeType::new(upgrade.clone()).ok_or(Error::InvalidLedgerHeader))
+            .collect::<Result<_>>()?;
+        let ext = match self.signature {
+            None => StellarValueExt::Basic,
+            Some(ref sig) => StellarValueExt::Signed(LedgerCloseValueSignature {
+                node_id: sig.node_id.to_xdr()?,
+                signature: Signature::new(sig.signature.clone()).ok_or(Error::InvalidSignature)?,
+            }),
+        };
+        Ok(StellarValue {
+            tx_set_hash: Hash(self.tx_set_hash),
+            close_time: self.close_time,
+            upgrades,
+            ext,
+        })
+    }
+}
+
+impl<'de> FromXdr<'de, StellarValue> for ::StellarValue {
+    fn from_xdr(value: StellarValue) -> Result<::StellarValue> {
+        if value.upgrades.len() > MAX_UPGRADES {
+            return Err(Error::InvalidLedgerHeader);
+        }
+        let signature = match value.ext {
+            StellarValueExt::Basic => None,
+            StellarValueExt::Signed(sig) => Some(::LedgerCloseValueSignature {
+                node_id: ::ed25519_dalek::PublicKey::from_xdr(sig.node_id)?,
+                signature: sig.signature.into_vec(),
+            }),
+        };
+        Ok(::StellarValue {
+            tx_set_hash: value.tx_set_hash.0,
+            close_time: value.close_time,
+            upgrades: value.upgrades.into_iter().map(UpgradeType::into_vec).collect(),
+            signature,
+        })
+    }
+}
+
+impl ToXdr<LedgerHeaderHistoryEntry> for ::LedgerHeaderHistoryEntry {
+    fn to_xdr(&self) -> Result<LedgerHeaderHistoryEntry> {
+        Ok(LedgerHeaderHistoryEntry {
+            hash: Hash(self.hash),
+            header: self.header.to_xdr()?,
+            ext: ExtensionPoint::V0,
+        })
+    }
+}
+
+impl<'de> FromXdr<'de, LedgerHeaderHistoryEntry> for ::LedgerHeaderHistoryEntry {
+    fn from_xdr(entry: LedgerHeaderHistoryEntry) -> Result<::LedgerHeaderHistoryEntry> {
+        Ok(::LedgerHeaderHistoryEntry {
+            hash: entry.hash.0,
+            header: ::LedgerHeader::from_xdr(entry.header)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crypto::keypair::from_account_id;
+    use error::Error;
+    use {LedgerHeaderHistoryEntry, StellarValue, Stroops, MAX_UPGRADES};
+    use {FromXdr, ToXdr};
+
+    const ENTRY: &str =
+        "wswWKYpqZfDZm4iSk4FVA2G8yLWQlxih7WV3A4VojRsAAAATAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEB\
+         AQEHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwAAAABlU/EAAAAAAQAAAAgAAAABAAAAEwAAAAEAAAAA\
+         iAUH1t80qQIelVOIuL23Spm/Ixukh0948JsN01kID/AAAABACQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJ\
+         CQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCSh3wbTwZ4tEzSwxc4dhRp1sPyfRC7JuYmbSys/gaVDD\
+         AgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgIAAABADeC2s6dkAAAAAAAAAAAB9AAAAAAAAAAAAAAATQAA\
+         AGQATEtAAAAD6AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA\
+         AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA\
+         AAAAAAAAAAAAAAAAAAA=";
+
+    #[test]
+    fn test_ledger_header_history_entry() {
+        let entry = LedgerHeaderHistoryEntry::from_base64(ENTRY).unwrap();
+        let header = &entry.header;
+        assert_eq!(header.ledger_version, 19);
+        assert_eq!(header.ledger_seq, 64);
+        assert_eq!(header.previous_ledger_hash, [1; 32]);
+        assert_eq!(header.total_coins, Stroops::new(1_000_000_000_000_000_000));
+        assert_eq!(header.base_reserve, 5_000_000);
+        assert_eq!(header.flags, None);
+        assert_eq!(header.scp_value.close_time, 1_700_000_000);
+        assert_eq!(header.scp_value.upgrades, vec![vec![0, 0, 0, 1, 0, 0, 0, 19]]);
+        let signature = header.scp_value.signature.as_ref().unwrap();
+        assert_eq!(
+            signature.node_id,
+            from_account_id("GCEAKB6W342KSAQ6SVJYROF5W5FJTPZDDOSIOT3Y6CNQ3U2ZBAH7AQN3").unwrap()
+        );
+        assert_eq!(signature.signature, vec![9; 64]);
+        assert_eq!(header.hash().unwrap(), entry.hash);
+        assert_eq!(entry.to_base64().unwrap(), ENTRY);
+    }
+
+    #[test]
+    fn test_too_many_upgrades() {
+        let value = StellarValue {
+            tx_set_hash: [0; 32],
+            close_time: 0,
+            upgrades: vec![vec![0; 8]; MAX_UPGRADES + 1],
+            signature: None,
+        };
+        match value.to_base64() {
+            Err(Error::InvalidLedgerHeader) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
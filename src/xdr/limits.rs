@@ -0,0 +1,692 @@
+//! Resource limits applied when decoding XDR.
+//!
+//! `serde_xdr` trusts the lengths declared in its input, so decoding goes
+//! through a reader that counts bytes and a deserializer adapter that counts
+//! nesting depth and sequence elements. The length prefix of strings and
+//! variable-length opaque data is checked against the bytes left before
+//! `serde_xdr` allocates for it.
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::io::{self, Read};
+use std::rc::Rc;
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess,
+                VariantAccess, Visitor};
+use serde_xdr;
+use error::{Error, Result};
+
+/// Default maximum number of bytes read.
+const DEFAULT_MAX_BYTES: usize = 1 << 20;
+/// Default maximum nesting depth of structs, unions and arrays.
+const DEFAULT_MAX_DEPTH: usize = 64;
+/// Default maximum number of array elements and struct fields.
+const DEFAULT_MAX_ELEMENTS: usize = 1 << 18;
+
+/// Limits on the resources used to decode an XDR object.
+///
+/// The default limits, 1 MiB, a depth of 64 and 262144 elements, are
+/// enough for any transaction envelope or result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeLimits {
+    /// Maximum number of bytes read.
+    pub max_bytes: usize,
+    /// Maximum nesting depth of structs, unions and arrays.
+    pub max_depth: usize,
+    /// Maximum number of array elements and struct fields, in total.
+    pub max_elements: usize,
+}
+
+impl DecodeLimits {
+    /// Create new limits.
+    pub fn new(max_bytes: usize, max_depth: usize, max_elements: usize) -> DecodeLimits {
+        DecodeLimits {
+            max_bytes,
+            max_depth,
+            max_elements,
+        }
+    }
+
+    /// Use `max_bytes` as maximum number of bytes.
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> DecodeLimits {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Use `max_depth` as maximum depth.
+    pub fn with_max_depth(mut self, max_depth: usize) -> DecodeLimits {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Use `max_elements` as maximum number of elements.
+    pub fn with_max_elements(mut self, max_elements: usize) -> DecodeLimits {
+        self.max_elements = max_elements;
+        self
+    }
+}
+
+impl Default for DecodeLimits {
+    fn default() -> DecodeLimits {
+        DecodeLimits::new(DEFAULT_MAX_BYTES, DEFAULT_MAX_DEPTH, DEFAULT_MAX_ELEMENTS)
+    }
+}
+
+/// Resources left while decoding.
+#[derive(Debug)]
+struct Budget {
+    limits: DecodeLimits,
+    bytes: Cell<usize>,
+    depth: Cell<usize>,
+    elements: Cell<usize>,
+    exceeded: Cell<bool>,
+    // Set while the next read is the length prefix of a string or opaque data.
+    length_prefix: Cell<bool>,
+}
+
+impl Budget {
+    fn new(limits: DecodeLimits) -> Budget {
+        Budget {
+            limits,
+            bytes: Cell::new(0),
+            depth: Cell::new(0),
+            elements: Cell::new(0),
+            exceeded: Cell::new(false),
+            length_prefix: Cell::new(false),
+        }
+    }
+
+    fn exceed<E: de::Error>(&self, what: &str) -> E {
+        self.exceeded.set(true);
+        E::custom(format_args!("XDR {} limit exceeded", what))
+    }
+
+    fn read(&self, n: usize) -> io::Result<()> {
+        let bytes = self.bytes.get() + n;
+        if bytes > self.limits.max_bytes {
+            self.exceeded.set(true);
+            return Err(io::Error::other("XDR bytes limit exceeded"));
+        }
+        self.bytes.set(bytes);
+        Ok(())
+    }
+
+    fn remaining(&self) -> usize {
+        self.limits.max_bytes - self.bytes.get()
+    }
+
+    fn enter<E: de::Error>(&self) -> ::std::result::Result<(), E> {
+        let depth = self.depth.get() + 1;
+        if depth > self.limits.max_depth {
+            return Err(self.exceed("depth"));
+        }
+        self.depth.set(depth);
+        Ok(())
+    }
+
+    fn leave(&self) {
+        self.depth.set(self.depth.get() - 1);
+    }
+
+    fn reserve<E: de::Error>(&self, n: usize) -> ::std::result::Result<(), E> {
+        if self.elements.get() + n > self.limits.max_elements {
+            return Err(self.exceed("elements"));
+        }
+        Ok(())
+    }
+
+    fn element<E: de::Error>(&self) -> ::std::result::Result<(), E> {
+        self.reserve(1)?;
+        self.elements.set(self.elements.get() + 1);
+        Ok(())
+    }
+}
+
+thread_local! {
+    // `serde_xdr::from_reader` only accepts `Deserialize` types, so the budget
+    // of the current decode is passed to `Limited` out of band.
+    static BUDGET: RefCell<Option<Rc<Budget>>> = const { RefCell::new(None) };
+}
+
+/// Deserialize a `T` from `r`, within `limits`.
+pub fn from_reader<'de, R: Read, T: Deserialize<'de>>(r: &mut R, limits: &DecodeLimits) -> Result<T> {
+    let budget = Rc::new(Budget::new(*limits));
+    let mut reader = LimitedReader {
+        inner: r,
+        budget: &budget,
+        prefix: [0; 4],
+        prefix_pos: 4,
+    };
+    let previous = BUDGET.with(|current| current.replace(Some(budget.clone())));
+    let result = serde_xdr::from_reader::<_, Limited<T>>(&mut reader);
+    BUDGET.with(|current| *current.borrow_mut() = previous);
+    match result {
+        Ok(Limited(x)) => Ok(x),
+        Err(_) if budget.exceeded.get() => Err(Error::XdrLimitExceeded),
+        Err(err) => Err(err.into()),
+    }
+}
+
+struct LimitedReader<'b, R: 'b> {
+    inner: &'b mut R,
+    budget: &'b Budget,
+    // A length prefix read ahead, served from `prefix_pos`.
+    prefix: [u8; 4],
+    prefix_pos: usize,
+}
+
+impl<'b, R: Read> LimitedReader<'b, R> {
+    // Read the length prefix ahead and reject lengths past the bytes left.
+    fn read_length_prefix(&mut self) -> io::Result<()> {
+        let mut filled = 0;
+        while filled < self.prefix.len() {
+            let n = self.inner.read(&mut self.prefix[filled..])?;
+            if n == 0 {
+                break;
+            }
+            self.budget.read(n)?;
+            filled += n;
+        }
+        self.prefix_pos = self.prefix.len() - filled;
+        if filled < self.prefix.len() {
+            // Truncated input, let `serde_xdr` report it.
+            self.prefix.copy_within(..filled, self.prefix_pos);
+            return Ok(());
+        }
+        // The data is padded to a multiple of 4 bytes.
+        let len = (u64::from(u32::from_be_bytes(self.prefix)) + 3) & !3;
+        if len > self.budget.remaining() as u64 {
+            self.budget.exceeded.set(true);
+            return Err(io::Error::other("XDR bytes limit exceeded"));
+        }
+        Ok(())
+    }
+}
+
+impl<'b, R: Read> Read for LimitedReader<'b, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.budget.length_prefix.replace(false) {
+            self.read_length_prefix()?;
+        }
+        if self.prefix_pos < self.prefix.len() {
+            let n = buf.len().min(self.prefix.len() - self.prefix_pos);
+            buf[..n].copy_from_slice(&self.prefix[self.prefix_pos..self.prefix_pos + n]);
+            self.prefix_pos += n;
+            return Ok(n);
+        }
+        let remaining = self.budget.remaining();
+        // Ask for one byte past the limit, so that reaching it is an error.
+        let len = buf.len().min(remaining + 1);
+        let n = self.inner.read(&mut buf[..len])?;
+        self.budget.read(n)?;
+        Ok(n)
+    }
+}
+
+struct Limited<T>(T);
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Limited<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> ::std::result::Result<Self, D::Error> {
+        let budget = BUDGET
+            .with(|current| current.borrow().clone())
+            .unwrap_or_else(|| Rc::new(Budget::new(DecodeLimits::default())));
+        T::deserialize(LimitedDeserializer {
+            inner: deserializer,
+            budget: &budget,
+        }).map(Limited)
+    }
+}
+
+struct LimitedDeserializer<'b, D> {
+    inner: D,
+    budget: &'b Budget,
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V)
+                -> ::std::result::Result<V::Value, D::Error>
+            {
+                let visitor = LimitedVisitor {
+                    inner: visitor,
+                    budget: self.budget,
+                };
+                self.inner.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+macro_rules! forward_deserialize_prefixed {
+    ($($method:ident();)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V)
+                -> ::std::result::Result<V::Value, D::Error>
+            {
+                let visitor = LimitedVisitor {
+                    inner: visitor,
+                    budget: self.budget,
+                };
+                self.budget.length_prefix.set(true);
+                let value = self.inner.$method(visitor);
+                self.budget.length_prefix.set(false);
+                value
+            }
+        )*
+    };
+}
+
+impl<'b, 'de, D: Deserializer<'de>> Deserializer<'de> for LimitedDeserializer<'b, D> {
+    type Error = D::Error;
+
+    forward_deserialize_prefixed! {
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+    }
+
+    forward_deserialize! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+struct LimitedVisitor<'b, V> {
+    inner: V,
+    budget: &'b Budget,
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty);)*) => {
+        $(
+            fn $method<E: de::Error>(self, v: $ty) -> ::std::result::Result<V::Value, E> {
+                self.inner.$method(v)
+            }
+        )*
+    };
+}
+
+impl<'b, 'de, V: Visitor<'de>> Visitor<'de> for LimitedVisitor<'b, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.expecting(f)
+    }
+
+    forward_visit! {
+        visit_bool(bool);
+        visit_i8(i8);
+        visit_i16(i16);
+        visit_i32(i32);
+        visit_i64(i64);
+        visit_u8(u8);
+        visit_u16(u16);
+        visit_u32(u32);
+        visit_u64(u64);
+        visit_f32(f32);
+        visit_f64(f64);
+        visit_char(char);
+        visit_str(&str);
+        visit_borrowed_str(&'de str);
+        visit_string(String);
+        visit_bytes(&[u8]);
+        visit_borrowed_bytes(&'de [u8]);
+        visit_byte_buf(Vec<u8>);
+    }
+
+    fn visit_unit<E: de::Error>(self) -> ::std::result::Result<V::Value, E> {
+        self.inner.visit_unit()
+    }
+
+    fn visit_none<E: de::Error>(self) -> ::std::result::Result<V::Value, E> {
+        self.inner.visit_none()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, d: D) -> ::std::result::Result<V::Value, D::Error> {
+        self.budget.enter()?;
+        let value = self.inner.visit_some(LimitedDeserializer {
+            inner: d,
+            budget: self.budget,
+        });
+        self.budget.leave();
+        value
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        d: D,
+    ) -> ::std::result::Result<V::Value, D::Error> {
+        self.budget.enter()?;
+        let value = self.inner.visit_newtype_struct(LimitedDeserializer {
+            inner: d,
+            budget: self.budget,
+        });
+        self.budget.leave();
+        value
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> ::std::result::Result<V::Value, A::Error> {
+        // Reject declared lengths before the visitor preallocates for them.
+        if let Some(len) = seq.size_hint() {
+            self.budget.reserve(len)?;
+        }
+        self.budget.enter()?;
+        let value = self.inner.visit_seq(LimitedAccess {
+            inner: seq,
+            budget: self.budget,
+        });
+        self.budget.leave();
+        value
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> ::std::result::Result<V::Value, A::Error> {
+        if let Some(len) = map.size_hint() {
+            self.budget.reserve(len)?;
+        }
+        self.budget.enter()?;
+        let value = self.inner.visit_map(LimitedAccess {
+            inner: map,
+            budget: self.budget,
+        });
+        self.budget.leave();
+        value
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> ::std::result::Result<V::Value, A::Error> {
+        self.budget.enter()?;
+        let value = self.inner.visit_enum(LimitedAccess {
+            inner: data,
+            budget: self.budget,
+        });
+        self.budget.leave();
+        value
+    }
+}
+
+/// Wraps the `SeqAccess`, `MapAccess`, `EnumAccess` and `VariantAccess` of the inner deserializer.
+struct LimitedAccess<'b, A> {
+    inner: A,
+    budget: &'b Budget,
+}
+
+impl<'b, A> LimitedAccess<'b, A> {
+    fn seed<S>(&self, seed: S) -> LimitedSeed<'b, S> {
+        LimitedSeed {
+            inner: seed,
+            budget: self.budget,
+        }
+    }
+
+    fn visitor<V>(&self, visitor: V) -> LimitedVisitor<'b, V> {
+        LimitedVisitor {
+            inner: visitor,
+            budget: self.budget,
+        }
+    }
+}
+
+impl<'b, 'de, A: SeqAccess<'de>> SeqAccess<'de> for LimitedAccess<'b, A> {
+    type Error = A::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> ::std::result::Result<Option<T::Value>, A::Error> {
+        let seed = self.seed(seed);
+        let element = self.inner.next_element_seed(seed)?;
+        if element.is_some() {
+            self.budget.element()?;
+        }
+        Ok(element)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'b, 'de, A: MapAccess<'de>> MapAccess<'de> for LimitedAccess<'b, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> ::std::result::Result<Option<K::Value>, A::Error> {
+        let seed = self.seed(seed);
+        let key = self.inner.next_key_seed(seed)?;
+        if key.is_some() {
+            self.budget.element()?;
+        }
+        Ok(key)
+    }
+
+    fn next_value_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> ::std::result::Result<T::Value, A::Error> {
+        let seed = self.seed(seed);
+        self.inner.next_value_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'b, 'de, A: EnumAccess<'de>> EnumAccess<'de> for LimitedAccess<'b, A> {
+    type Error = A::Error;
+    type Variant = LimitedAccess<'b, A::Variant>;
+
+    fn variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> ::std::result::Result<(T::Value, Self::Variant), A::Error> {
+        let budget = self.budget;
+        let (value, variant) = self.inner.variant_seed(seed)?;
+        Ok((
+            value,
+            LimitedAccess {
+                inner: variant,
+                budget,
+            },
+        ))
+    }
+}
+
+impl<'b, 'de, A: VariantAccess<'de>> VariantAccess<'de> for LimitedAccess<'b, A> {
+    type Error = A::Error;
+
+    fn unit_variant(self) -> ::std::result::Result<(), A::Error> {
+        self.inner.unit_variant()
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> ::std::result::Result<T::Value, A::Error> {
+        let seed = self.seed(seed);
+        self.inner.newtype_variant_seed(seed)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> ::std::result::Result<V::Value, A::Error> {
+        let visitor = self.visitor(visitor);
+        self.inner.tuple_variant(len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> ::std::result::Result<V::Value, A::Error> {
+        let visitor = self.visitor(visitor);
+        self.inner.struct_variant(fields, visitor)
+    }
+}
+
+struct LimitedSeed<'b, S> {
+    inner: S,
+    budget: &'b Budget,
+}
+
+impl<'b, 'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for LimitedSeed<'b, S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, d: D) -> ::std::result::Result<S::Value, D::Error> {
+        self.inner.deserialize(LimitedDeserializer {
+            inner: d,
+            budget: self.budget,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use super::{from_reader, DecodeLimits};
+    use error::Error;
+    use xdr::opaque::{DataValue, String64};
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Inner {
+        values: Vec<u32>,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Outer {
+        id: u64,
+        inner: Vec<Inner>,
+    }
+
+    fn encoded(inner: &[&[u32]]) -> Vec<u8> {
+        let mut buf = vec![0, 0, 0, 0, 0, 0, 0, 7];
+        buf.extend_from_slice(&(inner.len() as u32).to_be_bytes());
+        for values in inner {
+            buf.extend_from_slice(&(values.len() as u32).to_be_bytes());
+            for value in values.iter() {
+                buf.extend_from_slice(&value.to_be_bytes());
+            }
+        }
+        buf
+    }
+
+    fn decode(buf: &[u8], limits: &DecodeLimits) -> Result<Outer, Error> {
+        from_reader(&mut Cursor::new(buf), limits)
+    }
+
+    #[test]
+    fn test_within_limits() {
+        let buf = encoded(&[&[1, 2], &[3]]);
+        let outer = decode(&buf, &DecodeLimits::default()).unwrap();
+        assert_eq!(outer.id, 7);
+        assert_eq!(outer.inner, vec![Inner { values: vec![1, 2] }, Inner { values: vec![3] }]);
+        // Exactly at the limit.
+        let limits = DecodeLimits::default().with_max_bytes(buf.len());
+        assert!(decode(&buf, &limits).is_ok());
+    }
+
+    #[test]
+    fn test_max_bytes() {
+        let buf = encoded(&[&[1, 2], &[3]]);
+        let limits = DecodeLimits::default().with_max_bytes(buf.len() - 1);
+        match decode(&buf, &limits) {
+            Err(Error::XdrLimitExceeded) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let buf = encoded(&[&[1, 2], &[3]]);
+        // Outer struct, inner array, inner struct and values array.
+        assert!(decode(&buf, &DecodeLimits::default().with_max_depth(4)).is_ok());
+        match decode(&buf, &DecodeLimits::default().with_max_depth(3)) {
+            Err(Error::XdrLimitExceeded) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_max_elements() {
+        // A huge declared length is rejected before reading the elements.
+        let mut buf = vec![0, 0, 0, 0, 0, 0, 0, 7];
+        buf.extend_from_slice(&[0xff, 0xff, 0xff, 0xff]);
+        match decode(&buf, &DecodeLimits::default()) {
+            Err(Error::XdrLimitExceeded) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let buf = encoded(&[&[1, 2], &[3]]);
+        // 2 outer fields, 2 inner structs with 1 field each, 3 values.
+        assert!(decode(&buf, &DecodeLimits::default().with_max_elements(9)).is_ok());
+        match decode(&buf, &DecodeLimits::default().with_max_elements(8)) {
+            Err(Error::XdrLimitExceeded) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_opaque_length_prefix() {
+        // Huge declared lengths are rejected before allocating for them.
+        let buf = [0xff, 0xff, 0xff, 0xff, 0x61, 0x62, 0x63, 0x64];
+        let limits = DecodeLimits::default();
+        match from_reader::<_, String64>(&mut Cursor::new(&buf[..]), &limits) {
+            Err(Error::XdrLimitExceeded) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        match from_reader::<_, DataValue>(&mut Cursor::new(&buf[..]), &limits) {
+            Err(Error::XdrLimitExceeded) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // The padded length must fit in the bytes left.
+        let buf = [0, 0, 0, 3, 0x61, 0x62, 0x63, 0];
+        let limits = DecodeLimits::default().with_max_bytes(buf.len());
+        let s = from_reader::<_, String64>(&mut Cursor::new(&buf[..]), &limits).unwrap();
+        assert_eq!(s.as_str(), "abc");
+        let limits = DecodeLimits::default().with_max_bytes(buf.len() - 1);
+        match from_reader::<_, DataValue>(&mut Cursor::new(&buf[..]), &limits) {
+            Err(Error::XdrLimitExceeded) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_malformed_is_not_limit() {
+        let buf = encoded(&[&[1, 2], &[3]]);
+        match decode(&buf[..buf.len() - 2], &DecodeLimits::default()) {
+            Err(Error::DeserializationError(_)) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -1,11 +1,11 @@
 use serde_xdr;
-use error::Result;
-use xdr::{FromXdr, ToXdr};
+use error::{Error, Result};
+use xdr::{FromXdr, String28, ToXdr};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Memo {
     None,
-    Text(String),
+    Text(String28),
     Id(u64),
     Hash(MemoHash),
     Return(MemoHash),
@@ -21,7 +21,10 @@ impl ToXdr<Memo> for ::Memo {
         match *self {
             ::Memo::None => Ok(Memo::None),
             ::Memo::Id(id) => Ok(Memo::Id(id)),
-            ::Memo::Text(ref s) => Ok(Memo::Text(s.clone())),
+            ::Memo::Text(ref s) => {
+                let text = String28::new(s.clone()).ok_or(Error::InvalidMemoText)?;
+                Ok(Memo::Text(text))
+            }
             ::Memo::Hash(buf) => Ok(Memo::Hash(MemoHash { buf })),
             ::Memo::Return(buf) => Ok(Memo::Return(MemoHash { buf })),
         }
@@ -33,7 +36,7 @@ impl<'de> FromXdr<'de, Memo> for ::Memo {
         match memo {
             Memo::None => Ok(::Memo::None),
             Memo::Id(id) => Ok(::Memo::Id(id)),
-            Memo::Text(s) => Ok(::Memo::Text(s)),
+            Memo::Text(s) => Ok(::Memo::Text(s.into_string())),
             Memo::Hash(MemoHash { buf }) => Ok(::Memo::Hash(buf)),
             Memo::Return(MemoHash { buf }) => Ok(::Memo::Return(buf)),
         }
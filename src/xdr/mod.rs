@@ -1,20 +1,32 @@
 //! xdr data structures and conversion functions.
+#[macro_use]
+mod union;
+
 mod amount;
 mod asset;
+mod history;
 mod keypair;
+mod ledger_entry;
+mod ledger_header;
+mod limits;
 mod memo;
+mod opaque;
 mod operation;
 mod signature;
 mod time_bounds;
 mod transaction;
+mod transaction_meta;
+mod transaction_result;
 
 mod xdr_trait;
 
 use self::asset::Asset;
 use self::keypair::PublicKey;
 use self::memo::Memo;
+use self::opaque::{DataValue, Payload, Signature, String28, String32, String64, UpgradeType};
 use self::signature::DecoratedSignature;
 use self::time_bounds::TimeBounds;
 use self::operation::Operation;
 
+pub use self::limits::DecodeLimits;
 pub use self::xdr_trait::{FromXdr, ToXdr};
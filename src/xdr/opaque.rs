@@ -0,0 +1,234 @@
+//! Bounded variable-length opaque data and strings.
+//!
+//! Fixed-length opaque data uses `serde_xdr::opaque_data::fixed_length`.
+use std::fmt;
+use std::str;
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+macro_rules! opaque {
+    ($(#[$attr:meta])* $name:ident, $max:expr) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $name(Vec<u8>);
+
+        #[allow(dead_code)]
+        impl $name {
+            /// Maximum length in bytes.
+            pub const MAX_LEN: usize = $max;
+
+            /// Create from `data`, or `None` if it is longer than `MAX_LEN`.
+            pub fn new(data: Vec<u8>) -> Option<$name> {
+                if data.len() > Self::MAX_LEN {
+                    None
+                } else {
+                    Some($name(data))
+                }
+            }
+
+            /// Return the data.
+            pub fn as_bytes(&self) -> &[u8] {
+                &self.0
+            }
+
+            /// Convert into the data.
+            pub fn into_vec(self) -> Vec<u8> {
+                self.0
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_bytes(&self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<$name, D::Error> {
+                let data = deserializer.deserialize_byte_buf(OpaqueVisitor($max))?;
+                Ok($name(data))
+            }
+        }
+    };
+}
+
+macro_rules! string {
+    ($(#[$attr:meta])* $name:ident, $max:expr) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $name(String);
+
+        #[allow(dead_code)]
+        impl $name {
+            /// Maximum length in bytes.
+            pub const MAX_LEN: usize = $max;
+
+            /// Create from `s`, or `None` if it is longer than `MAX_LEN` bytes.
+            pub fn new(s: String) -> Option<$name> {
+                if s.len() > Self::MAX_LEN {
+                    None
+                } else {
+                    Some($name(s))
+                }
+            }
+
+            /// Return the string.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            /// Convert into the string.
+            pub fn into_string(self) -> String {
+                self.0
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<$name, D::Error> {
+                let s = deserializer.deserialize_string(StringVisitor($max))?;
+                Ok($name(s))
+            }
+        }
+    };
+}
+
+opaque!(
+    /// `opaque Signature<64>`, an ed25519 signature or a hash-x preimage.
+    Signature,
+    64
+);
+opaque!(
+    /// `opaque DataValue<64>`.
+    DataValue,
+    64
+);
+opaque!(
+    /// `opaque payload<64>` of a signed payload signer.
+    Payload,
+    64
+);
+opaque!(
+    /// `opaque UpgradeType<128>`, an encoded ledger upgrade.
+    UpgradeType,
+    128
+);
+string!(
+    /// `string<28>`, used by text memos.
+    String28,
+    28
+);
+string!(
+    /// `string<32>`, used by account home domains.
+    String32,
+    32
+);
+string!(
+    /// `string<64>`, used by data entry names.
+    String64,
+    64
+);
+
+struct OpaqueVisitor(usize);
+
+impl<'de> Visitor<'de> for OpaqueVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "at most {} bytes", self.0)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Vec<u8>, E> {
+        self.visit_byte_buf(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+        if v.len() > self.0 {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+        Ok(v)
+    }
+}
+
+struct StringVisitor(usize);
+
+impl<'de> Visitor<'de> for StringVisitor {
+    type Value = String;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a string of at most {} bytes", self.0)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<String, E> {
+        self.visit_string(v.to_string())
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<String, E> {
+        if v.len() > self.0 {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+        Ok(v)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<String, E> {
+        let s = str::from_utf8(v).map_err(|_| E::invalid_value(de::Unexpected::Bytes(v), &self))?;
+        self.visit_str(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use serde_xdr;
+    use super::{DataValue, Signature, String28};
+
+    #[test]
+    fn test_opaque_padding() {
+        let data = DataValue::new(vec![1, 2, 3, 4, 5]).unwrap();
+        let mut buf = Vec::new();
+        serde_xdr::to_writer(&mut buf, &data).unwrap();
+        assert_eq!(buf, vec![0, 0, 0, 5, 1, 2, 3, 4, 5, 0, 0, 0]);
+        let decoded: DataValue = serde_xdr::from_reader(&mut Cursor::new(&buf)).unwrap();
+        assert_eq!(decoded, data);
+
+        let empty = Signature::new(Vec::new()).unwrap();
+        let mut buf = Vec::new();
+        serde_xdr::to_writer(&mut buf, &empty).unwrap();
+        assert_eq!(buf, vec![0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_max_len() {
+        assert!(Signature::new(vec![0; 64]).is_some());
+        assert!(Signature::new(vec![0; 65]).is_none());
+        assert!(String28::new("a".repeat(28)).is_some());
+        assert!(String28::new("a".repeat(29)).is_none());
+
+        let mut buf = vec![0, 0, 0, 65];
+        buf.extend_from_slice(&[0; 68]);
+        let decoded: Result<Signature, _> = serde_xdr::from_reader(&mut Cursor::new(&buf));
+        assert!(decoded.is_err());
+        buf[3] = 64;
+        let decoded: Signature = serde_xdr::from_reader(&mut Cursor::new(&buf[..68])).unwrap();
+        assert_eq!(decoded.as_bytes().len(), 64);
+    }
+
+    #[test]
+    fn test_string() {
+        let text = String28::new("hello".to_string()).unwrap();
+        let mut buf = Vec::new();
+        serde_xdr::to_writer(&mut buf, &text).unwrap();
+        assert_eq!(buf, vec![0, 0, 0, 5, b'h', b'e', b'l', b'l', b'o', 0, 0, 0]);
+        let decoded: String28 = serde_xdr::from_reader(&mut Cursor::new(&buf)).unwrap();
+        assert_eq!(decoded.as_str(), "hello");
+
+        let invalid = vec![0, 0, 0, 2, 0xff, 0xfe, 0, 0];
+        let decoded: Result<String28, _> = serde_xdr::from_reader(&mut Cursor::new(&invalid));
+        assert!(decoded.is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use amount::{Amount, Price, Stroops};
-use error::Result;
+use error::{Error, Result};
 use operation;
-use xdr::{Asset, FromXdr, PublicKey, ToXdr};
+use xdr::{Asset, DataValue, FromXdr, PublicKey, String64, ToXdr};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Operation {
@@ -72,8 +72,8 @@ pub struct CreatePassiveOfferOperation {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ManageDataOperation {
-    name: String,
-    value: Option<Vec<u8>>,
+    name: String64,
+    value: Option<DataValue>,
 }
 
 impl ToXdr<Operation> for ::Operation {
@@ -86,7 +86,10 @@ impl ToXdr<Operation> for ::Operation {
             ::Operation::CreatePassiveOffer(ref op) => to_create_passive_offer(op),
             ::Operation::ManageData(ref op) => to_manage_data(op),
             ::Operation::Inflation(ref op) => to_inflation(op),
-            _ => unimplemented!(),
+            ::Operation::SetOptions
+            | ::Operation::ChangeTrust
+            | ::Operation::AllowTrust
+            | ::Operation::AccountMerge => Err(Error::UnsupportedOperation),
         }
     }
 }
@@ -189,10 +192,12 @@ fn to_manage_data(manage: &::ManageDataOperation) -> Result<Operation> {
         None => None,
         Some(ref pk) => Some(pk.to_xdr()?),
     };
-    let inner = OperationInner::ManageData(ManageDataOperation {
-        name: manage.name.clone(),
-        value: manage.value.clone(),
-    });
+    let name = String64::new(manage.name.clone()).ok_or(Error::InvalidDataName)?;
+    let value = match manage.value {
+        None => None,
+        Some(ref value) => Some(DataValue::new(value.clone()).ok_or(Error::InvalidDataValue)?),
+    };
+    let inner = OperationInner::ManageData(ManageDataOperation { name, value });
     Ok(Operation::new(source, inner))
 }
 
@@ -219,7 +224,10 @@ impl<'de> FromXdr<'de, Operation> for ::Operation {
             OperationInner::CreatePassiveOffer(inner) => from_create_passive_offer(source, inner),
             OperationInner::ManageData(inner) => from_manage_data(source, inner),
             OperationInner::Inflation => from_inflation(source),
-            _ => unimplemented!(),
+            OperationInner::SetOptions
+            | OperationInner::ChangeTrust
+            | OperationInner::AllowTrust
+            | OperationInner::AccountMerge => Err(Error::UnsupportedOperation),
         }
     }
 }
@@ -322,8 +330,8 @@ fn from_manage_data(
 ) -> Result<::Operation> {
     Ok(::Operation::ManageData(operation::ManageDataOperation {
         source,
-        name: inner.name,
-        value: inner.value,
+        name: inner.name.into_string(),
+        value: inner.value.map(DataValue::into_vec),
     }))
 }
 
@@ -340,6 +348,7 @@ mod tests {
     use {Amount, Asset, Price};
     use {FromXdr, ToXdr};
     use crypto::keypair::from_account_id;
+    use error::Error;
 
     fn do_it(op: Operation, expected: &str) {
         let encoded = op.clone().to_base64().unwrap();
@@ -435,8 +444,35 @@ mod tests {
     fn test_manage_data() {
         let delete_op = OperationBuilder::delete_data("THE KEY".to_string()).build();
         do_it(delete_op, "AAAAAAAAAAoAAAAHVEhFIEtFWQAAAAAA");
-        // TODO(fracek) Figure out serialization of var length opaque types
-        //let set_op = OperationBuilder::set_data("THE KEY".to_string(), vec![1, 2, 3]).build();
-        //do_it(set_op, "AAAAAAAAAAoAAAAHVEhFIEtFWQAAAAABAAAAAwECAwA=");
+        let set_op = OperationBuilder::set_data("THE KEY".to_string(), vec![1, 2, 3]).build();
+        do_it(set_op, "AAAAAAAAAAoAAAAHVEhFIEtFWQAAAAABAAAAAwECAwA=");
+    }
+
+    #[test]
+    fn test_manage_data_too_long() {
+        let op = OperationBuilder::set_data("k".repeat(65), vec![1]).build();
+        assert!(op.to_base64().is_err());
+        let op = OperationBuilder::set_data("key".to_string(), vec![0; 65]).build();
+        assert!(op.to_base64().is_err());
+    }
+
+    #[test]
+    fn test_unsupported_operations() {
+        let ops = [
+            (Operation::SetOptions, "AAAAAAAAAAU="),
+            (Operation::ChangeTrust, "AAAAAAAAAAY="),
+            (Operation::AllowTrust, "AAAAAAAAAAc="),
+            (Operation::AccountMerge, "AAAAAAAAAAg="),
+        ];
+        for &(ref op, encoded) in &ops {
+            match op.to_base64() {
+                Err(Error::UnsupportedOperation) => {}
+                other => panic!("unexpected {:?}", other),
+            }
+            match Operation::from_base64(encoded) {
+                Err(Error::UnsupportedOperation) => {}
+                other => panic!("unexpected {:?}", other),
+            }
+        }
     }
 }
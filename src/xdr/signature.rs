@@ -1,47 +1,25 @@
 use serde_xdr::opaque_data;
-use error::Result;
-use xdr::{FromXdr, ToXdr};
+use error::{Error, Result};
+use xdr::{FromXdr, Signature, ToXdr};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DecoratedSignature {
     #[serde(with = "opaque_data::fixed_length")] pub hint: [u8; 4],
-    // TODO(fracek): Once var length opaque data support is added,
-    // change to be a Vec<u8>
-    pub len: u32,
-    #[serde(with = "opaque_data::fixed_length")] pub signature0: [u8; 32],
-    #[serde(with = "opaque_data::fixed_length")] pub signature1: [u8; 32],
+    pub signature: Signature,
 }
 
 impl ToXdr<DecoratedSignature> for ::DecoratedSignature {
     fn to_xdr(&self) -> Result<DecoratedSignature> {
-        let hint = self.hint().0.clone();
-        let signature_vec = self.signature().to_bytes();
-        let len = signature_vec.len();
-        let mut signature0 = [0; 32];
-        let mut signature1 = [0; 32];
-        if len > 32 {
-            signature0.copy_from_slice(&signature_vec[..32]);
-            signature1.copy_from_slice(&signature_vec[32..len]);
-        } else {
-            signature0.copy_from_slice(&signature_vec[..len]);
-        }
-        Ok(DecoratedSignature {
-            hint,
-            len: len as u32,
-            signature0,
-            signature1,
-        })
+        let hint = self.hint().0;
+        let signature = Signature::new(self.signature().to_vec()).ok_or(Error::InvalidSignature)?;
+        Ok(DecoratedSignature { hint, signature })
     }
 }
 
 impl<'de> FromXdr<'de, DecoratedSignature> for ::DecoratedSignature {
     fn from_xdr(sig: DecoratedSignature) -> Result<::DecoratedSignature> {
         let hint = ::signature::SignatureHint(sig.hint);
-        let mut signature_slice = [0; 64];
-        signature_slice[..32].copy_from_slice(&sig.signature0);
-        signature_slice[32..].copy_from_slice(&sig.signature1);
-        let signature = ed25519_dalek::Signature::from_bytes(&signature_slice).unwrap();
-        Ok(::DecoratedSignature::new(hint, signature))
+        ::DecoratedSignature::from_bytes(hint, sig.signature.as_bytes())
     }
 }
 
@@ -49,6 +27,7 @@ impl<'de> FromXdr<'de, DecoratedSignature> for ::DecoratedSignature {
 mod tests {
     use {DecoratedSignature};
     use {FromXdr, ToXdr};
+    use base64;
     use crypto::keypair::{from_secret_seed, sign_decorated};
     #[test]
     fn test_decorated_signature() {
@@ -66,4 +45,22 @@ mod tests {
         let decoded = DecoratedSignature::from_base64(&encoded).unwrap();
         assert_eq!(decoded, sig);
     }
+
+    #[test]
+    fn test_hash_x_signature() {
+        let sig = DecoratedSignature::hash_x(b"preimage").unwrap();
+        let encoded = sig.to_base64().unwrap();
+        let buf = base64::decode(&encoded).unwrap();
+        assert_eq!(&buf[4..8], &[0, 0, 0, 8]);
+        assert_eq!(&buf[8..], b"preimage");
+        let decoded = DecoratedSignature::from_base64(&encoded).unwrap();
+        assert_eq!(decoded, sig);
+    }
+
+    #[test]
+    fn test_decorated_signature_too_long() {
+        let mut buf = vec![1, 2, 3, 4, 0, 0, 0, 65];
+        buf.extend_from_slice(&[0; 68]);
+        assert!(DecoratedSignature::from_base64(&base64::encode(&buf)).is_err());
+    }
 }
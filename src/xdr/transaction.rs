@@ -190,7 +190,8 @@ mod tests {
                 ).build(),
             )
             .with_memo(Memo::Id(123))
-            .build();
+            .build()
+            .unwrap();
         do_it(tx, "AAAAAGPgfnO/mUw71s3yUF9r1CS8IOgym90QLaqUHgG8D4URAAAAyAAAAAAAAAPoAAAAAAAAAAIAAAAAAAAAewAAAAIAAAAAAAAACQAAAAAAAAABAAAAAGPgfnO/mUw71s3yUF9r1CS8IOgym90QLaqUHgG8D4URAAAAAAAAAABJjViAAAAAAA==");
     }
 
@@ -203,7 +204,8 @@ mod tests {
         let mut account = Account::new(kp.public, 999);
         let tx = TransactionBuilder::new(&mut account)
             .operation(OperationBuilder::inflation().build())
-            .build();
+            .build()
+            .unwrap();
         let network = Network::public_network();
         let signed_tx = tx.sign(&kp, &network).unwrap();
         let expected_signature_base = vec![
@@ -219,4 +221,45 @@ mod tests {
         assert_eq!(signed_tx.signature_base().unwrap(), expected_signature_base);
         do_it(signed_tx, "AAAAAGPgfnO/mUw71s3yUF9r1CS8IOgym90QLaqUHgG8D4URAAAAZAAAAAAAAAPoAAAAAAAAAAAAAAABAAAAAAAAAAkAAAAAAAAAAbwPhREAAABAkqlNirgebGCMoc0kdl7FLMl/k2q36LZN1EI7+kfY5xiGg9Mb0txYsIZY3zx1RREQywp/wgpLTpfHqIcnDs2HAg==");
     }
+
+    #[test]
+    fn test_decode_limits() {
+        use base64;
+        use error::Error;
+        use {DecodeLimits, SignedTransaction};
+
+        let encoded = "AAAAAGPgfnO/mUw71s3yUF9r1CS8IOgym90QLaqUHgG8D4URAAAAZAAAAAAAAAPoAAAAAAAAAAAAAAABAAAAAAAAAAkAAAAAAAAAAbwPhREAAABAkqlNirgebGCMoc0kdl7FLMl/k2q36LZN1EI7+kfY5xiGg9Mb0txYsIZY3zx1RREQywp/wgpLTpfHqIcnDs2HAg==";
+        let limits = DecodeLimits::default().with_max_bytes(100);
+        match SignedTransaction::from_base64_with_limits(encoded, &limits) {
+            Err(Error::XdrLimitExceeded) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // Declare 2^31 operations.
+        let mut buf = base64::decode(encoded).unwrap();
+        buf[56..60].copy_from_slice(&[0x80, 0, 0, 0]);
+        match SignedTransaction::from_base64(&base64::encode(&buf)) {
+            Err(Error::XdrLimitExceeded) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unsupported_operation() {
+        use base64;
+        use error::Error;
+        use SignedTransaction;
+
+        let encoded = "AAAAAGPgfnO/mUw71s3yUF9r1CS8IOgym90QLaqUHgG8D4URAAAAZAAAAAAAAAPoAAAAAAAAAAAAAAABAAAAAAAAAAkAAAAAAAAAAbwPhREAAABAkqlNirgebGCMoc0kdl7FLMl/k2q36LZN1EI7+kfY5xiGg9Mb0txYsIZY3zx1RREQywp/wgpLTpfHqIcnDs2HAg==";
+        let mut buf = base64::decode(encoded).unwrap();
+        // Replace the inflation operation by set options, change trust, allow trust
+        // and account merge.
+        for op_type in 5..9 {
+            buf[67] = op_type;
+            match SignedTransaction::from_base64(&base64::encode(&buf)) {
+                Err(Error::UnsupportedOperation) => {}
+                other => panic!("unexpected {:?}", other),
+            }
+        }
+    }
 }
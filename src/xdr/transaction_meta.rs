@@ -0,0 +1,220 @@
+use std::fmt;
+use serde::de::{self, Deserialize, Deserializer};
+use error::Result;
+use xdr::ledger_entry::{ExtensionPoint, LedgerEntry, LedgerKey};
+use xdr::FromXdr;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LedgerEntryChange {
+    Created(LedgerEntry),
+    Updated(LedgerEntry),
+    Removed(LedgerKey),
+    State(LedgerEntry),
+    Restored(LedgerEntry),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationMeta {
+    pub changes: Vec<LedgerEntryChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionMetaV1 {
+    pub tx_changes: Vec<LedgerEntryChange>,
+    pub operations: Vec<OperationMeta>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionMetaV2 {
+    pub tx_changes_before: Vec<LedgerEntryChange>,
+    pub operations: Vec<OperationMeta>,
+    pub tx_changes_after: Vec<LedgerEntryChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionMetaV3 {
+    pub ext: ExtensionPoint,
+    pub tx_changes_before: Vec<LedgerEntryChange>,
+    pub operations: Vec<OperationMeta>,
+    pub tx_changes_after: Vec<LedgerEntryChange>,
+    pub soroban_meta: Option<SorobanTransactionMeta>,
+}
+
+// Soroban meta is not decoded, only its absence is accepted.
+#[derive(Debug, Clone, Serialize)]
+pub enum SorobanTransactionMeta {}
+
+impl<'de> Deserialize<'de> for SorobanTransactionMeta {
+    fn deserialize<D>(_deserializer: D) -> ::std::result::Result<SorobanTransactionMeta, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Err(de::Error::custom(Unsupported))
+    }
+}
+
+struct Unsupported;
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("soroban transaction meta is not supported")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TransactionMeta {
+    V0(Vec<OperationMeta>),
+    V1(TransactionMetaV1),
+    V2(TransactionMetaV2),
+    V3(TransactionMetaV3),
+}
+
+impl<'de> FromXdr<'de, LedgerEntryChange> for ::LedgerEntryChange {
+    fn from_xdr(change: LedgerEntryChange) -> Result<::LedgerEntryChange> {
+        Ok(match change {
+            LedgerEntryChange::Created(entry) => {
+                ::LedgerEntryChange::Created(::LedgerEntry::from_xdr(entry)?)
+            }
+            LedgerEntryChange::Updated(entry) => {
+                ::LedgerEntryChange::Updated(::LedgerEntry::from_xdr(entry)?)
+            }
+            LedgerEntryChange::Removed(key) => {
+                ::LedgerEntryChange::Removed(::LedgerKey::from_xdr(key)?)
+            }
+            LedgerEntryChange::State(entry) => {
+                ::LedgerEntryChange::State(::LedgerEntry::from_xdr(entry)?)
+            }
+            LedgerEntryChange::Restored(entry) => {
+                ::LedgerEntryChange::Restored(::LedgerEntry::from_xdr(entry)?)
+            }
+        })
+    }
+}
+
+// Standalone `LedgerEntryChanges`, e.g. the fee processing changes.
+impl<'de> FromXdr<'de, Vec<LedgerEntryChange>> for Vec<::LedgerEntryChange> {
+    fn from_xdr(changes: Vec<LedgerEntryChange>) -> Result<Vec<::LedgerEntryChange>> {
+        from_changes(changes)
+    }
+}
+
+fn from_changes(changes: Vec<LedgerEntryChange>) -> Result<Vec<::LedgerEntryChange>> {
+    changes
+        .into_iter()
+        .map(::LedgerEntryChange::from_xdr)
+        .collect()
+}
+
+impl<'de> FromXdr<'de, OperationMeta> for ::OperationMeta {
+    fn from_xdr(meta: OperationMeta) -> Result<::OperationMeta> {
+        Ok(::OperationMeta {
+            changes: from_changes(meta.changes)?,
+        })
+    }
+}
+
+fn from_operations(operations: Vec<OperationMeta>) -> Result<Vec<::OperationMeta>> {
+    operations
+        .into_iter()
+        .map(::OperationMeta::from_xdr)
+        .collect()
+}
+
+impl<'de> FromXdr<'de, TransactionMeta> for ::TransactionMeta {
+    fn from_xdr(meta: TransactionMeta) -> Result<::TransactionMeta> {
+        Ok(match meta {
+            TransactionMeta::V0(operations) => ::TransactionMeta {
+                version: 0,
+                tx_changes_before: Vec::new(),
+                operations: from_operations(operations)?,
+                tx_changes_after: Vec::new(),
+            },
+            TransactionMeta::V1(meta) => ::TransactionMeta {
+                version: 1,
+                tx_changes_before: from_changes(meta.tx_changes)?,
+                operations: from_operations(meta.operations)?,
+                tx_changes_after: Vec::new(),
+            },
+            TransactionMeta::V2(meta) => ::TransactionMeta {
+                version: 2,
+                tx_changes_before: from_changes(meta.tx_changes_before)?,
+                operations: from_operations(meta.operations)?,
+                tx_changes_after: from_changes(meta.tx_changes_after)?,
+            },
+            TransactionMeta::V3(meta) => ::TransactionMeta {
+                version: 3,
+                tx_changes_before: from_changes(meta.tx_changes_before)?,
+                operations: from_operations(meta.operations)?,
+                tx_changes_after: from_changes(meta.tx_changes_after)?,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crypto::keypair::from_account_id;
+    use error::Error;
+    use {BalanceAnalyzer, LedgerEntryChange, LedgerEntryData, LedgerKey, Stroops,
+         TransactionMeta};
+    use FromXdr;
+
+    #[test]
+    fn test_transaction_meta_v2() {
+        let meta = TransactionMeta::from_base64(
+            "AAAAAgAAAAIAAAADAAAACQAAAAAAAAAAiAUH1t80qQIelVOIuL23Spm/Ixukh0948JsN01kID/AAAAAAAAAD6AAA\
+             AAAAAAAFAAAAAAAAAAAAAAAAAAAAAAEAAAAAAAAAAAAAAAAAAAAAAAABAAAACQAAAAAAAAAAiAUH1t80qQIelVOI\
+             uL23Spm/Ixukh0948JsN01kID/AAAAAAAAAD6AAAAAAAAAAGAAAAAAAAAAAAAAAAAAAAAAEAAAAAAAAAAAAAAAAA\
+             AAAAAAABAAAAAwAAAAAAAAAJAAAAAAAAAACWNrD5mTHn0xAOHVJEIc6tuK2YdLNjgZjseYtpvmM4sgAAAAAAAAH0\
+             AAAAAAAAAB4AAAAAAAAAAAAAAAAAAAAAAQAAAAAAAAAAAAAAAAAAAAAAAAMAAAAJAAAAAAAAAACIBQfW3zSpAh6V\
+             U4i4vbdKmb8jG6SHT3jwmw3TWQgP8AAAAAAAAAPoAAAAAAAAAAYAAAAAAAAAAAAAAAAAAAAAAQAAAAAAAAAAAAAA\
+             AAAAAAAAAAEAAAAJAAAAAAAAAACIBQfW3zSpAh6VU4i4vbdKmb8jG6SHT3jwmw3TWQgP8AAAAAAAAAH0AAAAAAAA\
+             AAYAAAAAAAAAAAAAAAAAAAAAAQAAAAAAAAAAAAAAAAAAAAAAAAA=",
+        ).unwrap();
+        assert_eq!(meta.version, 2);
+        assert_eq!(meta.tx_changes_before.len(), 2);
+        assert_eq!(meta.operations.len(), 1);
+        assert_eq!(meta.operations[0].changes.len(), 3);
+        assert!(meta.tx_changes_after.is_empty());
+        match meta.operations[0].changes[0] {
+            LedgerEntryChange::Created(ref entry) => match entry.data {
+                LedgerEntryData::Account(ref account) => {
+                    assert_eq!(account.balance, Stroops::new(500));
+                    assert_eq!(account.sequence, 30);
+                }
+                ref other => panic!("unexpected {:?}", other),
+            },
+            ref other => panic!("unexpected {:?}", other),
+        }
+
+        let mut analyzer = BalanceAnalyzer::new();
+        analyzer.add_meta(&meta).unwrap();
+        let changes = analyzer.finish().unwrap();
+        assert_eq!(changes.balances.len(), 2);
+        assert_eq!(changes.balances[0].delta, Stroops::new(-500));
+        assert_eq!(changes.balances[1].delta, Stroops::new(500));
+        assert_eq!(changes.sequences[0].before, Some(5));
+        assert_eq!(changes.sequences[0].after, Some(6));
+    }
+
+    #[test]
+    fn test_ledger_entry_changes() {
+        let changes = Vec::<LedgerEntryChange>::from_base64(
+            "AAAAAQAAAAIAAAAAAAAAAIgFB9bfNKkCHpVTiLi9t0qZvyMbpIdPePCbDdNZCA/w",
+        ).unwrap();
+        let account_id =
+            from_account_id("GCEAKB6W342KSAQ6SVJYROF5W5FJTPZDDOSIOT3Y6CNQ3U2ZBAH7AQN3").unwrap();
+        assert_eq!(
+            changes,
+            vec![LedgerEntryChange::Removed(LedgerKey::Account { account_id })]
+        );
+    }
+
+    #[test]
+    fn test_soroban_meta_unsupported() {
+        match TransactionMeta::from_base64("AAAAAwAAAAAAAAAAAAAAAAAAAAAAAAABAAAAAAAAAAA=") {
+            Err(Error::DeserializationError(_)) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
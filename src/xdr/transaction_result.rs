@@ -0,0 +1,764 @@
+use std::io::{self, Read};
+use amount::Stroops;
+use crypto;
+use error::{Error, Result};
+use xdr::ledger_entry::{ClaimableBalanceId, ExtensionPoint, Hash, OfferEntry};
+use xdr::limits::{self, DecodeLimits};
+use xdr::{Asset, FromXdr, PublicKey};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionResultPair {
+    pub transaction_hash: Hash,
+    pub result: TransactionResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionResultSet {
+    pub results: Vec<TransactionResultPair>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionResult {
+    pub fee_charged: Stroops,
+    pub result: TransactionResultResult,
+    pub ext: ExtensionPoint,
+}
+
+xdr_union! {
+    pub enum TransactionResultResult {
+        FeeBumpInnerSuccess(InnerTransactionResultPair) = 1,
+        Success(Vec<OperationResult>) = 0,
+        Failed(Vec<OperationResult>) = -1,
+        FeeBumpInnerFailed(InnerTransactionResultPair) = -13,
+        Other = default,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InnerTransactionResultPair {
+    pub transaction_hash: Hash,
+    pub result: InnerTransactionResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InnerTransactionResult {
+    pub fee_charged: Stroops,
+    pub result: InnerTransactionResultResult,
+    pub ext: ExtensionPoint,
+}
+
+xdr_union! {
+    pub enum InnerTransactionResultResult {
+        Success(Vec<OperationResult>) = 0,
+        Failed(Vec<OperationResult>) = -1,
+        Other = default,
+    }
+}
+
+xdr_union! {
+    pub enum OperationResult {
+        Inner(OperationResultTr) = 0,
+        Other = default,
+    }
+}
+
+// Results whose arms are all void are decoded as their code.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum OperationResultTr {
+    CreateAccount(i32),
+    Payment(i32),
+    PathPaymentStrictReceive(PathPaymentResult),
+    ManageSellOffer(ManageOfferResult),
+    CreatePassiveSellOffer(ManageOfferResult),
+    SetOptions(i32),
+    ChangeTrust(i32),
+    AllowTrust(i32),
+    AccountMerge(AccountMergeResult),
+    Inflation(InflationResult),
+    ManageData(i32),
+    BumpSequence(i32),
+    ManageBuyOffer(ManageOfferResult),
+    PathPaymentStrictSend(PathPaymentResult),
+    CreateClaimableBalance(CreateClaimableBalanceResult),
+    ClaimClaimableBalance(i32),
+    BeginSponsoringFutureReserves(i32),
+    EndSponsoringFutureReserves(i32),
+    RevokeSponsorship(i32),
+    Clawback(i32),
+    ClawbackClaimableBalance(i32),
+    SetTrustLineFlags(i32),
+    LiquidityPoolDeposit(i32),
+    LiquidityPoolWithdraw(i32),
+    InvokeHostFunction(InvokeHostFunctionResult),
+    ExtendFootprintTtl(i32),
+    RestoreFootprint(i32),
+}
+
+// Strict receive and strict send results share their arms.
+xdr_union! {
+    pub enum PathPaymentResult {
+        Success(PathPaymentSuccess) = 0,
+        NoIssuer(Asset) = -9,
+        Other = default,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathPaymentSuccess {
+    pub offers: Vec<ClaimAtom>,
+    pub last: SimplePaymentResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimplePaymentResult {
+    pub destination: PublicKey,
+    pub asset: Asset,
+    pub amount: Stroops,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ClaimAtom {
+    V0(ClaimOfferAtomV0),
+    OrderBook(ClaimOfferAtom),
+    LiquidityPool(ClaimLiquidityAtom),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimOfferAtomV0 {
+    pub seller_ed25519: Hash,
+    pub offer_id: i64,
+    pub asset_sold: Asset,
+    pub amount_sold: Stroops,
+    pub asset_bought: Asset,
+    pub amount_bought: Stroops,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimOfferAtom {
+    pub seller_id: PublicKey,
+    pub offer_id: i64,
+    pub asset_sold: Asset,
+    pub amount_sold: Stroops,
+    pub asset_bought: Asset,
+    pub amount_bought: Stroops,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimLiquidityAtom {
+    pub liquidity_pool_id: Hash,
+    pub asset_sold: Asset,
+    pub amount_sold: Stroops,
+    pub asset_bought: Asset,
+    pub amount_bought: Stroops,
+}
+
+xdr_union! {
+    pub enum ManageOfferResult {
+        Success(ManageOfferSuccessResult) = 0,
+        Other = default,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManageOfferSuccessResult {
+    pub offers_claimed: Vec<ClaimAtom>,
+    pub offer: ManageOfferEffect,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ManageOfferEffect {
+    Created(OfferEntry),
+    Updated(OfferEntry),
+    Deleted,
+}
+
+xdr_union! {
+    pub enum AccountMergeResult {
+        Success(Stroops) = 0,
+        Other = default,
+    }
+}
+
+xdr_union! {
+    pub enum InflationResult {
+        Success(Vec<InflationPayout>) = 0,
+        Other = default,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InflationPayout {
+    pub destination: PublicKey,
+    pub amount: Stroops,
+}
+
+xdr_union! {
+    pub enum CreateClaimableBalanceResult {
+        Success(ClaimableBalanceId) = 0,
+        Other = default,
+    }
+}
+
+xdr_union! {
+    pub enum InvokeHostFunctionResult {
+        Success(Hash) = 0,
+        Other = default,
+    }
+}
+
+fn result_code<C, F>(from_code: F, code: i32) -> Result<C>
+where
+    F: Fn(i32) -> Option<C>,
+{
+    from_code(code).ok_or(Error::InvalidResultCode)
+}
+
+impl<'de> FromXdr<'de, TransactionResultSet> for ::TransactionResultSet {
+    fn from_xdr(_: TransactionResultSet) -> Result<::TransactionResultSet> {
+        // The hash is of the bytes as read, only `from_reader_with_limits` sees them.
+        unreachable!("result sets are decoded from a reader")
+    }
+
+    fn from_reader_with_limits<R: Read>(
+        r: &mut R,
+        limits: &DecodeLimits,
+    ) -> Result<::TransactionResultSet> {
+        let mut reader = RecordingReader {
+            inner: r,
+            bytes: Vec::new(),
+        };
+        let set: TransactionResultSet = limits::from_reader(&mut reader, limits)?;
+        let mut hash = [0; 32];
+        hash.copy_from_slice(&crypto::hash(&reader.bytes));
+        let results = set.results
+            .into_iter()
+            .map(::TransactionResultPair::from_xdr)
+            .collect::<Result<_>>()?;
+        Ok(::TransactionResultSet { results, hash })
+    }
+}
+
+// Keeps a copy of the bytes read from `inner`.
+struct RecordingReader<'r, R: 'r> {
+    inner: &'r mut R,
+    bytes: Vec<u8>,
+}
+
+impl<'r, R: Read> Read for RecordingReader<'r, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.bytes.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<'de> FromXdr<'de, TransactionResultPair> for ::TransactionResultPair {
+    fn from_xdr(pair: TransactionResultPair) -> Result<::TransactionResultPair> {
+        Ok(::TransactionResultPair {
+            transaction_hash: pair.transaction_hash.0,
+            result: ::TransactionResult::from_xdr(pair.result)?,
+        })
+    }
+}
+
+impl<'de> FromXdr<'de, TransactionResult> for ::TransactionResult {
+    fn from_xdr(result: TransactionResult) -> Result<::TransactionResult> {
+        let (code, operations, inner) = match result.result {
+            TransactionResultResult::FeeBumpInnerSuccess(pair) => (
+                ::TransactionResultCode::FeeBumpInnerSuccess,
+                Vec::new(),
+                Some(::InnerTransactionResultPair::from_xdr(pair)?),
+            ),
+            TransactionResultResult::FeeBumpInnerFailed(pair) => (
+                ::TransactionResultCode::FeeBumpInnerFailed,
+                Vec::new(),
+                Some(::InnerTransactionResultPair::from_xdr(pair)?),
+            ),
+            TransactionResultResult::Success(ops) => (
+                ::TransactionResultCode::Success,
+                from_operation_results(ops)?,
+                None,
+            ),
+            TransactionResultResult::Failed(ops) => (
+                ::TransactionResultCode::Failed,
+                from_operation_results(ops)?,
+                None,
+            ),
+            TransactionResultResult::Other(code) => (
+                result_code(::TransactionResultCode::from_code, code)?,
+                Vec::new(),
+                None,
+            ),
+        };
+        Ok(::TransactionResult {
+            fee_charged: result.fee_charged,
+            code,
+            operations,
+            inner,
+        })
+    }
+}
+
+impl<'de> FromXdr<'de, InnerTransactionResultPair> for ::InnerTransactionResultPair {
+    fn from_xdr(pair: InnerTransactionResultPair) -> Result<::InnerTransactionResultPair> {
+        Ok(::InnerTransactionResultPair {
+            transaction_hash: pair.transaction_hash.0,
+            result: ::InnerTransactionResult::from_xdr(pair.result)?,
+        })
+    }
+}
+
+impl<'de> FromXdr<'de, InnerTransactionResult> for ::InnerTransactionResult {
+    fn from_xdr(result: InnerTransactionResult) -> Result<::InnerTransactionResult> {
+        let (code, operations) = match result.result {
+            InnerTransactionResultResult::Success(ops) => {
+                (::TransactionResultCode::Success, from_operation_results(ops)?)
+            }
+            InnerTransactionResultResult::Failed(ops) => {
+                (::TransactionResultCode::Failed, from_operation_results(ops)?)
+            }
+            InnerTransactionResultResult::Other(code) => {
+                let code = result_code(::TransactionResultCode::from_code, code)?;
+                match code {
+                    ::TransactionResultCode::FeeBumpInnerSuccess
+                    | ::TransactionResultCode::FeeBumpInnerFailed => {
+                        return Err(Error::InvalidResultCode)
+                    }
+                    _ => (code, Vec::new()),
+                }
+            }
+        };
+        Ok(::InnerTransactionResult {
+            fee_charged: result.fee_charged,
+            code,
+            operations,
+        })
+    }
+}
+
+fn from_operation_results(results: Vec<OperationResult>) -> Result<Vec<::OperationResult>> {
+    results.into_iter().map(::OperationResult::from_xdr).collect()
+}
+
+impl<'de> FromXdr<'de, OperationResult> for ::OperationResult {
+    fn from_xdr(result: OperationResult) -> Result<::OperationResult> {
+        let tr = match result {
+            OperationResult::Inner(tr) => tr,
+            OperationResult::Other(code) => {
+                let code = result_code(::OperationResultCode::from_code, code)?;
+                return Ok(::OperationResult::Failed(code));
+            }
+        };
+        let result = match tr {
+            OperationResultTr::CreateAccount(code) => ::OperationResult::CreateAccount(
+                result_code(::CreateAccountResultCode::from_code, code)?,
+            ),
+            OperationResultTr::Payment(code) => {
+                ::OperationResult::Payment(result_code(::PaymentResultCode::from_code, code)?)
+            }
+            OperationResultTr::PathPaymentStrictReceive(result) => {
+                let result = match result {
+                    PathPaymentResult::Success(success) => {
+                        ::PathPaymentStrictReceiveResult::Success(Box::new(from_path_payment(success)?))
+                    }
+                    PathPaymentResult::NoIssuer(asset) => {
+                        ::PathPaymentStrictReceiveResult::NoIssuer(Box::new(::Asset::from_xdr(asset)?))
+                    }
+                    PathPaymentResult::Other(code) => ::PathPaymentStrictReceiveResult::Failure(
+                        result_code(::PathPaymentStrictReceiveResultCode::from_code, code)?,
+                    ),
+                };
+                ::OperationResult::PathPaymentStrictReceive(result)
+            }
+            OperationResultTr::ManageSellOffer(result) => {
+                ::OperationResult::ManageSellOffer(from_manage_offer(result)?)
+            }
+            OperationResultTr::CreatePassiveSellOffer(result) => {
+                ::OperationResult::CreatePassiveSellOffer(from_manage_offer(result)?)
+            }
+            OperationResultTr::SetOptions(code) => ::OperationResult::SetOptions(result_code(
+                ::SetOptionsResultCode::from_code,
+                code,
+            )?),
+            OperationResultTr::ChangeTrust(code) => ::OperationResult::ChangeTrust(result_code(
+                ::ChangeTrustResultCode::from_code,
+                code,
+            )?),
+            OperationResultTr::AllowTrust(code) => ::OperationResult::AllowTrust(result_code(
+                ::AllowTrustResultCode::from_code,
+                code,
+            )?),
+            OperationResultTr::AccountMerge(result) => {
+                let result = match result {
+                    AccountMergeResult::Success(balance) => ::AccountMergeResult::Success(balance),
+                    AccountMergeResult::Other(code) => ::AccountMergeResult::Failure(
+                        result_code(::AccountMergeResultCode::from_code, code)?,
+                    ),
+                };
+                ::OperationResult::AccountMerge(result)
+            }
+            OperationResultTr::Inflation(result) => {
+                let result = match result {
+                    InflationResult::Success(payouts) => {
+                        let payouts_res: Result<Vec<_>> = payouts
+                            .into_iter()
+                            .map(|payout| {
+                                Ok(::InflationPayout {
+                                    destination: ed25519_dalek::PublicKey::from_xdr(
+                                        payout.destination,
+                                    )?,
+                                    amount: payout.amount,
+                                })
+                            })
+                            .collect();
+                        ::InflationResult::Success(payouts_res?)
+                    }
+                    InflationResult::Other(code) => ::InflationResult::Failure(result_code(
+                        ::InflationResultCode::from_code,
+                        code,
+                    )?),
+                };
+                ::OperationResult::Inflation(result)
+            }
+            OperationResultTr::ManageData(code) => ::OperationResult::ManageData(result_code(
+                ::ManageDataResultCode::from_code,
+                code,
+            )?),
+            OperationResultTr::BumpSequence(code) => ::OperationResult::BumpSequence(
+                result_code(::BumpSequenceResultCode::from_code, code)?,
+            ),
+            OperationResultTr::ManageBuyOffer(result) => {
+                ::OperationResult::ManageBuyOffer(from_manage_offer(result)?)
+            }
+            OperationResultTr::PathPaymentStrictSend(result) => {
+                let result = match result {
+                    PathPaymentResult::Success(success) => {
+                        ::PathPaymentStrictSendResult::Success(Box::new(from_path_payment(success)?))
+                    }
+                    PathPaymentResult::NoIssuer(asset) => {
+                        ::PathPaymentStrictSendResult::NoIssuer(Box::new(::Asset::from_xdr(asset)?))
+                    }
+                    PathPaymentResult::Other(code) => ::PathPaymentStrictSendResult::Failure(
+                        result_code(::PathPaymentStrictSendResultCode::from_code, code)?,
+                    ),
+                };
+                ::OperationResult::PathPaymentStrictSend(result)
+            }
+            OperationResultTr::CreateClaimableBalance(result) => {
+                let result = match result {
+                    CreateClaimableBalanceResult::Success(ClaimableBalanceId::V0(Hash(id))) => {
+                        ::CreateClaimableBalanceResult::Success(id)
+                    }
+                    CreateClaimableBalanceResult::Other(code) => {
+                        ::CreateClaimableBalanceResult::Failure(result_code(
+                            ::CreateClaimableBalanceResultCode::from_code,
+                            code,
+                        )?)
+                    }
+                };
+                ::OperationResult::CreateClaimableBalance(result)
+            }
+            OperationResultTr::ClaimClaimableBalance(code) => {
+                ::OperationResult::ClaimClaimableBalance(result_code(
+                    ::ClaimClaimableBalanceResultCode::from_code,
+                    code,
+                )?)
+            }
+            OperationResultTr::BeginSponsoringFutureReserves(code) => {
+                ::OperationResult::BeginSponsoringFutureReserves(result_code(
+                    ::BeginSponsoringFutureReservesResultCode::from_code,
+                    code,
+                )?)
+            }
+            OperationResultTr::EndSponsoringFutureReserves(code) => {
+                ::OperationResult::EndSponsoringFutureReserves(result_code(
+                    ::EndSponsoringFutureReservesResultCode::from_code,
+                    code,
+                )?)
+            }
+            OperationResultTr::RevokeSponsorship(code) => {
+                ::OperationResult::RevokeSponsorship(result_code(
+                    ::RevokeSponsorshipResultCode::from_code,
+                    code,
+                )?)
+            }
+            OperationResultTr::Clawback(code) => {
+                ::OperationResult::Clawback(result_code(::ClawbackResultCode::from_code, code)?)
+            }
+            OperationResultTr::ClawbackClaimableBalance(code) => {
+                ::OperationResult::ClawbackClaimableBalance(result_code(
+                    ::ClawbackClaimableBalanceResultCode::from_code,
+                    code,
+                )?)
+            }
+            OperationResultTr::SetTrustLineFlags(code) => {
+                ::OperationResult::SetTrustLineFlags(result_code(
+                    ::SetTrustLineFlagsResultCode::from_code,
+                    code,
+                )?)
+            }
+            OperationResultTr::LiquidityPoolDeposit(code) => {
+                ::OperationResult::LiquidityPoolDeposit(result_code(
+                    ::LiquidityPoolDepositResultCode::from_code,
+                    code,
+                )?)
+            }
+            OperationResultTr::LiquidityPoolWithdraw(code) => {
+                ::OperationResult::LiquidityPoolWithdraw(result_code(
+                    ::LiquidityPoolWithdrawResultCode::from_code,
+                    code,
+                )?)
+            }
+            OperationResultTr::InvokeHostFunction(result) => {
+                let result = match result {
+                    InvokeHostFunctionResult::Success(Hash(hash)) => {
+                        ::InvokeHostFunctionResult::Success(hash)
+                    }
+                    InvokeHostFunctionResult::Other(code) => {
+                        ::InvokeHostFunctionResult::Failure(result_code(
+                            ::InvokeHostFunctionResultCode::from_code,
+                            code,
+                        )?)
+                    }
+                };
+                ::OperationResult::InvokeHostFunction(result)
+            }
+            OperationResultTr::ExtendFootprintTtl(code) => {
+                ::OperationResult::ExtendFootprintTtl(result_code(
+                    ::ExtendFootprintTtlResultCode::from_code,
+                    code,
+                )?)
+            }
+            OperationResultTr::RestoreFootprint(code) => {
+                ::OperationResult::RestoreFootprint(result_code(
+                    ::RestoreFootprintResultCode::from_code,
+                    code,
+                )?)
+            }
+        };
+        Ok(result)
+    }
+}
+
+fn from_path_payment(success: PathPaymentSuccess) -> Result<::PathPaymentSuccessResult> {
+    Ok(::PathPaymentSuccessResult {
+        offers: from_claim_atoms(success.offers)?,
+        last: ::SimplePaymentResult {
+            destination: ed25519_dalek::PublicKey::from_xdr(success.last.destination)?,
+            asset: ::Asset::from_xdr(success.last.asset)?,
+            amount: success.last.amount,
+        },
+    })
+}
+
+fn from_manage_offer(result: ManageOfferResult) -> Result<::ManageOfferResult> {
+    let success = match result {
+        ManageOfferResult::Success(success) => success,
+        ManageOfferResult::Other(code) => {
+            let code = result_code(::ManageOfferResultCode::from_code, code)?;
+            return Ok(::ManageOfferResult::Failure(code));
+        }
+    };
+    let effect = match success.offer {
+        ManageOfferEffect::Created(offer) => {
+            ::ManageOfferEffect::Created(::OfferEntry::from_xdr(offer)?)
+        }
+        ManageOfferEffect::Updated(offer) => {
+            ::ManageOfferEffect::Updated(::OfferEntry::from_xdr(offer)?)
+        }
+        ManageOfferEffect::Deleted => ::ManageOfferEffect::Deleted,
+    };
+    Ok(::ManageOfferResult::Success(Box::new(::ManageOfferSuccessResult {
+        offers_claimed: from_claim_atoms(success.offers_claimed)?,
+        effect,
+    })))
+}
+
+fn from_claim_atoms(atoms: Vec<ClaimAtom>) -> Result<Vec<::ClaimAtom>> {
+    atoms.into_iter().map(::ClaimAtom::from_xdr).collect()
+}
+
+impl<'de> FromXdr<'de, ClaimAtom> for ::ClaimAtom {
+    fn from_xdr(atom: ClaimAtom) -> Result<::ClaimAtom> {
+        match atom {
+            ClaimAtom::V0(atom) => Ok(::ClaimAtom::OrderBook(::ClaimOfferAtom {
+                seller_id: ed25519_dalek::PublicKey::from_bytes(&(atom.seller_ed25519.0))
+                    .map_err(|_| Error::InvalidPublicKey)?,
+                offer_id: atom.offer_id,
+                asset_sold: ::Asset::from_xdr(atom.asset_sold)?,
+                amount_sold: atom.amount_sold,
+                asset_bought: ::Asset::from_xdr(atom.asset_bought)?,
+                amount_bought: atom.amount_bought,
+            })),
+            ClaimAtom::OrderBook(atom) => Ok(::ClaimAtom::OrderBook(::ClaimOfferAtom {
+                seller_id: ed25519_dalek::PublicKey::from_xdr(atom.seller_id)?,
+                offer_id: atom.offer_id,
+                asset_sold: ::Asset::from_xdr(atom.asset_sold)?,
+                amount_sold: atom.amount_sold,
+                asset_bought: ::Asset::from_xdr(atom.asset_bought)?,
+                amount_bought: atom.amount_bought,
+            })),
+            ClaimAtom::LiquidityPool(atom) => Ok(::ClaimAtom::LiquidityPool(::ClaimLiquidityAtom {
+                liquidity_pool_id: atom.liquidity_pool_id.0,
+                asset_sold: ::Asset::from_xdr(atom.asset_sold)?,
+                amount_sold: atom.amount_sold,
+                asset_bought: ::Asset::from_xdr(atom.asset_bought)?,
+                amount_bought: atom.amount_bought,
+            })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use crypto;
+    use crypto::keypair::from_account_id;
+    use error::Error;
+    use {Asset, ClaimAtom, ManageOfferEffect, ManageOfferResult, OperationResult,
+         OperationResultCode, PaymentResultCode, Stroops, TransactionResult,
+         TransactionResultCode, TransactionResultPair, TransactionResultSet};
+    use FromXdr;
+
+    #[test]
+    fn test_payment_success() {
+        let result = TransactionResult::from_base64("AAAAAAAAAGQAAAAAAAAAAQAAAAAAAAABAAAAAAAAAAA=")
+            .unwrap();
+        assert_eq!(result.fee_charged, Stroops::new(100));
+        assert_eq!(result.code, TransactionResultCode::Success);
+        assert!(result.is_success());
+        assert_eq!(
+            result.operations,
+            vec![OperationResult::Payment(PaymentResultCode::Success)]
+        );
+        assert_eq!(result.to_string(), "all operations succeeded");
+    }
+
+    #[test]
+    fn test_payment_underfunded() {
+        let result = TransactionResult::from_base64("AAAAAAAAAGT/////AAAAAQAAAAAAAAAB/////gAAAAA=")
+            .unwrap();
+        assert_eq!(result.code, TransactionResultCode::Failed);
+        assert!(!result.is_success());
+        assert_eq!(
+            result.operations,
+            vec![OperationResult::Payment(PaymentResultCode::Underfunded)]
+        );
+        assert_eq!(result.operations[0].code(), -2);
+        assert_eq!(
+            result.to_string(),
+            "one of the operations failed; operation 0: payment: not enough funds in source account"
+        );
+    }
+
+    #[test]
+    fn test_transaction_result_pair() {
+        let pair = TransactionResultPair::from_base64(
+            "BQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUAAAAAAAAAZP////sAAAAA",
+        ).unwrap();
+        assert_eq!(pair.transaction_hash, [5; 32]);
+        assert_eq!(pair.result.code, TransactionResultCode::BadSeq);
+        assert!(pair.result.operations.is_empty());
+    }
+
+    #[test]
+    fn test_manage_offer_success() {
+        let result = TransactionResult::from_base64(
+            "AAAAAAAAAGQAAAAAAAAAAQAAAAAAAAADAAAAAAAAAAEAAAABAAAAAIgFB9bfNKkCHpVTiLi9t0qZvyMbpIdPePCb\
+             DdNZCA/wAAAAAAAAAAcAAAAAAAAAAAAAAGQAAAAAAAAAAAAAADIAAAAAAAAAAIgFB9bfNKkCHpVTiLi9t0qZvyMb\
+             pIdPePCbDdNZCA/wAAAAAAAAAAgAAAAAAAAAAAAAAAAAAAAZAAAAAQAAAAIAAAAAAAAAAAAAAAA=",
+        ).unwrap();
+        let seller =
+            from_account_id("GCEAKB6W342KSAQ6SVJYROF5W5FJTPZDDOSIOT3Y6CNQ3U2ZBAH7AQN3").unwrap();
+        let success = match result.operations[0] {
+            OperationResult::ManageSellOffer(ManageOfferResult::Success(ref success)) => success,
+            ref other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(success.offers_claimed.len(), 1);
+        match success.offers_claimed[0] {
+            ClaimAtom::OrderBook(ref atom) => {
+                assert_eq!(atom.seller_id, seller);
+                assert_eq!(atom.offer_id, 7);
+                assert_eq!(atom.asset_sold, Asset::Native);
+                assert_eq!(atom.amount_sold, Stroops::new(100));
+                assert_eq!(atom.amount_bought, Stroops::new(50));
+            }
+            ref other => panic!("unexpected {:?}", other),
+        }
+        match success.effect {
+            ManageOfferEffect::Created(ref offer) => {
+                assert_eq!(offer.offer_id, 8);
+                assert_eq!(offer.amount, Stroops::new(25));
+            }
+            ref other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_fee_bump_inner_failed() {
+        let result = TransactionResult::from_base64(
+            "AAAAAAAAAMj////zAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMAAAAAAAAAZP////8AAAACAAAAAAAA\
+             AAH////+/////wAAAAAAAAAA",
+        ).unwrap();
+        assert_eq!(result.fee_charged, Stroops::new(200));
+        assert_eq!(result.code, TransactionResultCode::FeeBumpInnerFailed);
+        let inner = result.inner.as_ref().unwrap();
+        assert_eq!(inner.transaction_hash, [3; 32]);
+        assert_eq!(inner.result.code, TransactionResultCode::Failed);
+        assert_eq!(
+            inner.result.operations,
+            vec![
+                OperationResult::Payment(PaymentResultCode::Underfunded),
+                OperationResult::Failed(OperationResultCode::BadAuth),
+            ]
+        );
+        assert_eq!(
+            result.to_string(),
+            "fee bump inner transaction failed: one of the operations failed; \
+             operation 0: payment: not enough funds in source account; \
+             operation 1: too few valid signatures or wrong network"
+        );
+    }
+
+    #[test]
+    fn test_transaction_result_set_hash() {
+        let set = TransactionResultSet::from_base64(
+            "AAAAAQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFAAAAAAAAAGQAAAAAAAAAAQAAAAAAAAAB\
+             AAAAAAAAAAA=",
+        ).unwrap();
+        assert_eq!(set.results.len(), 1);
+        assert_eq!(set.results[0].transaction_hash, [5; 32]);
+        assert!(set.results[0].result.is_success());
+        assert_eq!(
+            *set.hash(),
+            [
+                0x28, 0x77, 0xc1, 0xb4, 0xf0, 0x67, 0x8b, 0x44, 0xcd, 0x2c, 0x31, 0x73, 0x87, 0x61,
+                0x46, 0x9d, 0x6c, 0x3f, 0x27, 0xd1, 0x0b, 0xb2, 0x6e, 0x62, 0x66, 0xd2, 0xca, 0xcf,
+                0xe0, 0x69, 0x50, 0xc3,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_transaction_result_set_hash_of_bytes_read() {
+        let mut buf = vec![0, 0, 0, 1];
+        buf.extend_from_slice(&[5; 32]);
+        buf.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 100, 0, 0, 0, 0, 0, 0, 0, 1]);
+        buf.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0]);
+        let len = buf.len();
+        // The start of the next record.
+        buf.extend_from_slice(&[0, 0, 0, 2]);
+        let mut cursor = Cursor::new(&buf);
+        let set = TransactionResultSet::from_reader(&mut cursor).unwrap();
+        assert_eq!(cursor.position(), len as u64);
+        assert_eq!(set.hash()[..], crypto::hash(&buf[..len])[..]);
+    }
+
+    #[test]
+    fn test_unknown_result_code() {
+        match TransactionResult::from_base64("AAAAAAAAAGT///+dAAAAAA==") {
+            Err(Error::InvalidResultCode) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! Unions whose discriminants are not `0..n`.
+//!
+//! Derived enums encode the variant index as the discriminant, which does not
+//! fit extension unions with gaps (`case 0`, `case 2`) or result unions with
+//! negative codes. `xdr_union!` declares the discriminant of every arm and
+//! encodes the union as an `int` followed by the arm, if any. A last
+//! `Variant = default` arm keeps any other discriminant of a void arm.
+use std::fmt;
+use serde::de::{self, Deserialize, SeqAccess};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+
+macro_rules! xdr_union {
+    ($(#[$attr:meta])* pub enum $name:ident { $($arms:tt)* }) => {
+        xdr_union!(@parse [$(#[$attr])*] $name [serializer seq] [] [] [] $($arms)*);
+    };
+    (@parse $attrs:tt $name:ident [$s:ident $seq:ident] [$($var:tt)*] [$($ser:tt)*] [$($de:tt)*]
+     $variant:ident = $disc:literal, $($rest:tt)*) => {
+        xdr_union!(@parse $attrs $name [$s $seq]
+            [$($var)* $variant,]
+            [$($ser)* $name::$variant => ::xdr::union::serialize_arm::<_, ()>($s, $disc, None),]
+            [$($de)* $disc => Ok($name::$variant),]
+            $($rest)*);
+    };
+    (@parse $attrs:tt $name:ident [$s:ident $seq:ident] [$($var:tt)*] [$($ser:tt)*] [$($de:tt)*]
+     $variant:ident($ty:ty) = $disc:literal, $($rest:tt)*) => {
+        xdr_union!(@parse $attrs $name [$s $seq]
+            [$($var)* $variant($ty),]
+            [$($ser)* $name::$variant(ref arm) => ::xdr::union::serialize_arm($s, $disc, Some(arm)),]
+            [$($de)* $disc => Ok($name::$variant(::xdr::union::next_arm(&mut $seq)?)),]
+            $($rest)*);
+    };
+    (@parse $attrs:tt $name:ident [$s:ident $seq:ident] [$($var:tt)*] [$($ser:tt)*] [$($de:tt)*]
+     $variant:ident = default,) => {
+        xdr_union!(@parse $attrs $name [$s $seq]
+            [$($var)* $variant(i32),]
+            [$($ser)* $name::$variant(code) => ::xdr::union::serialize_arm::<_, ()>($s, code, None),]
+            [$($de)* code => Ok($name::$variant(code)),]);
+    };
+    (@parse [$($attr:tt)*] $name:ident [$s:ident $seq:ident] [$($var:tt)*] [$($ser:tt)*] [$($de:tt)*]) => {
+        $($attr)*
+        #[derive(Debug, Clone)]
+        pub enum $name {
+            $($var)*
+        }
+
+        impl ::serde::Serialize for $name {
+            fn serialize<S>(&self, $s: S) -> ::std::result::Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                match *self {
+                    $($ser)*
+                }
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<$name, D::Error>
+            where
+                D: ::serde::Deserializer<'de>,
+            {
+                struct UnionVisitor;
+
+                impl<'de> ::serde::de::Visitor<'de> for UnionVisitor {
+                    type Value = $name;
+
+                    fn expecting(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                        f.write_str(concat!("union ", stringify!($name)))
+                    }
+
+                    fn visit_seq<A>(self, mut $seq: A) -> ::std::result::Result<$name, A::Error>
+                    where
+                        A: ::serde::de::SeqAccess<'de>,
+                    {
+                        let discriminant: i32 = ::xdr::union::next_arm(&mut $seq)?;
+                        #[allow(unreachable_patterns)]
+                        match discriminant {
+                            $($de)*
+                            _ => Err(::xdr::union::unknown_discriminant(discriminant)),
+                        }
+                    }
+                }
+
+                deserializer.deserialize_tuple(2, UnionVisitor)
+            }
+        }
+    };
+}
+
+/// Serialize the `discriminant` followed by the `arm`, if any.
+pub fn serialize_arm<S, T>(serializer: S, discriminant: i32, arm: Option<&T>) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    let mut tuple = serializer.serialize_tuple(2)?;
+    tuple.serialize_element(&discriminant)?;
+    if let Some(arm) = arm {
+        tuple.serialize_element(arm)?;
+    }
+    tuple.end()
+}
+
+/// Deserialize the next part of a union.
+pub fn next_arm<'de, A, T>(seq: &mut A) -> Result<T, A::Error>
+where
+    A: SeqAccess<'de>,
+    T: Deserialize<'de>,
+{
+    seq.next_element()?
+        .ok_or_else(|| de::Error::custom("truncated union"))
+}
+
+/// The error for a discriminant without an arm.
+pub fn unknown_discriminant<E: de::Error>(discriminant: i32) -> E {
+    E::custom(UnknownDiscriminant(discriminant))
+}
+
+struct UnknownDiscriminant(i32);
+
+impl fmt::Display for UnknownDiscriminant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown union discriminant {}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use serde_xdr;
+
+    xdr_union! {
+        pub enum Sparse {
+            Void = 0,
+            Value(u32) = 2,
+            Failed = -3,
+        }
+    }
+
+    xdr_union! {
+        pub enum WithDefault {
+            Success(u32) = 0,
+            Other = default,
+        }
+    }
+
+    fn encode(x: &Sparse) -> Vec<u8> {
+        let mut buf = Vec::new();
+        serde_xdr::to_writer(&mut buf, x).unwrap();
+        buf
+    }
+
+    fn decode(buf: &[u8]) -> Result<Sparse, serde_xdr::CompatDeserializationError> {
+        serde_xdr::from_reader(&mut Cursor::new(buf))
+    }
+
+    #[test]
+    fn test_union_discriminants() {
+        assert_eq!(encode(&Sparse::Void), vec![0, 0, 0, 0]);
+        assert_eq!(encode(&Sparse::Value(7)), vec![0, 0, 0, 2, 0, 0, 0, 7]);
+        assert_eq!(encode(&Sparse::Failed), vec![0xff, 0xff, 0xff, 0xfd]);
+
+        match decode(&[0, 0, 0, 2, 0, 0, 0, 7]).unwrap() {
+            Sparse::Value(7) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        match decode(&[0xff, 0xff, 0xff, 0xfd]).unwrap() {
+            Sparse::Failed => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_union_default() {
+        let mut buf = Vec::new();
+        serde_xdr::to_writer(&mut buf, &WithDefault::Other(-5)).unwrap();
+        assert_eq!(buf, vec![0xff, 0xff, 0xff, 0xfb]);
+        let decoded: WithDefault = serde_xdr::from_reader(&mut Cursor::new(&buf)).unwrap();
+        match decoded {
+            WithDefault::Other(-5) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        let buf = [0, 0, 0, 0, 0, 0, 0, 9];
+        let decoded: WithDefault = serde_xdr::from_reader(&mut Cursor::new(&buf)).unwrap();
+        match decoded {
+            WithDefault::Success(9) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_union_invalid() {
+        assert!(decode(&[0, 0, 0, 1]).is_err());
+        assert!(decode(&[0, 0, 0, 2]).is_err());
+    }
+}
@@ -1,8 +1,9 @@
 use std::io::{Cursor, Read, Write};
 use serde::{Deserialize, Serialize};
-use error::Result;
+use error::{Error, Result};
 use serde_xdr;
 use base64;
+use xdr::limits::{self, DecodeLimits};
 
 /// A trait to try and serialize some type into an XDR object.
 pub trait ToXdr<T: Serialize>: Sized {
@@ -28,16 +29,39 @@ pub trait FromXdr<'de, T: Deserialize<'de>>: Sized {
     /// Build the type from the XDR `other` object .
     fn from_xdr(other: T) -> Result<Self>;
 
-    /// Deserialize from a reader `r`.
-    fn from_reader<R: Read>(mut r: &mut R) -> Result<Self> {
-        let x = serde_xdr::from_reader(&mut r)?;
+    /// Deserialize from a reader `r`, within the default `DecodeLimits`.
+    fn from_reader<R: Read>(r: &mut R) -> Result<Self> {
+        Self::from_reader_with_limits(r, &DecodeLimits::default())
+    }
+
+    /// Deserialize from a reader `r`, within `limits`.
+    fn from_reader_with_limits<R: Read>(r: &mut R, limits: &DecodeLimits) -> Result<Self> {
+        let x = limits::from_reader(r, limits)?;
         Self::from_xdr(x)
     }
 
-    /// Deserialize from base64.
+    /// Deserialize from base64, within the default `DecodeLimits`.
+    ///
+    /// The input must be canonical, padded base64 and contain exactly one XDR object.
     fn from_base64(input: &str) -> Result<Self> {
+        Self::from_base64_with_limits(input, &DecodeLimits::default())
+    }
+
+    /// Deserialize from base64, within `limits`.
+    fn from_base64_with_limits(input: &str, limits: &DecodeLimits) -> Result<Self> {
+        // Base64 encodes 3 bytes in 4 characters.
+        if input.len() / 4 * 3 > limits.max_bytes + 2 {
+            return Err(Error::XdrLimitExceeded);
+        }
         let buf = base64::decode(&input)?;
+        if base64::encode(&buf) != input {
+            return Err(Error::NonCanonicalEncoding);
+        }
         let mut cursor = Cursor::new(&buf);
-        Self::from_reader(&mut cursor)
+        let x = Self::from_reader_with_limits(&mut cursor, limits)?;
+        if cursor.position() != buf.len() as u64 {
+            return Err(Error::TrailingXdrData);
+        }
+        Ok(x)
     }
 }